@@ -14,15 +14,23 @@ use ratatui::{
 };
 use std::{
     io::{self, stdout},
+    path::PathBuf,
     time::{Duration, Instant},
 };
 
-use dprs::dprs::app::{actions, AppState};
-use dprs::dprs::commands::{CommandExecutor, CommandResult};
+use dprs::dprs::app::{actions, AppEvent, AppState};
+use dprs::dprs::commands::{completion, CommandExecutor, CommandResult};
 use dprs::dprs::display;
+use dprs::dprs::display::confirm_dialog::{confirm_title, PendingAction};
+use dprs::dprs::display::context_menu::context_menu_problems;
 use dprs::dprs::display::toast::ToastManager;
+use dprs::dprs::modes::history::HistoryFile;
+use dprs::dprs::modes::keymap::{validate_keybindings, KeyDispatch};
 use dprs::dprs::modes::Mode;
-use dprs::shared::config::Config;
+use dprs::shared::config::{
+    key_event_to_string, project_config_path, set_config_file_override, ActionRunMode, Config,
+    ConfigWatcher, ContextMenuAction, CONFIG_POLL_INTERVAL,
+};
 use dprs::shared::input::input_watcher::InputWatcher;
 use std::process::Command;
 use tachyonfx::EffectManager;
@@ -99,6 +107,27 @@ fn main() -> Result<(), io::Error> {
         print_etchosts();
         return Ok(());
     }
+    match config_flag(&args) {
+        Ok(Some(path)) => set_config_file_override(path),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+    if args.contains(&"--check-config".to_string()) {
+        std::process::exit(check_config());
+    }
+
+    // Load configuration before the terminal is taken over, so problems
+    // can be listed once the TUI starts
+    let upgraded = Config::upgrade_file();
+    let (config, error) = Config::load_reporting();
+    let mut config_problems: Vec<String> = error.into_iter().collect();
+    if let Err(e) = &upgraded {
+        config_problems.push(e.clone());
+    }
+    config_problems.extend(config_problems_of(&config));
 
     // Setup terminal
     enable_raw_mode()?;
@@ -106,11 +135,15 @@ fn main() -> Result<(), io::Error> {
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    // Load configuration and create app state
-    let config = Config::load();
     let mut toast_manager = ToastManager::new();
+    if let Ok(Some(backup)) = upgraded {
+        toast_manager.show(
+            &format!("Config file upgraded, the old one is {}", backup.display()),
+            4000,
+        );
+    }
 
-    let result = run_app(&mut terminal, &mut toast_manager, config);
+    let result = run_app(&mut terminal, &mut toast_manager, config, config_problems);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -124,22 +157,144 @@ fn main() -> Result<(), io::Error> {
     Ok(())
 }
 
+/// The file named by `--config PATH` or `--config=PATH`.
+fn config_flag(args: &[String]) -> Result<Option<PathBuf>, String> {
+    for (i, arg) in args.iter().enumerate() {
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Ok(Some(PathBuf::from(path)));
+        }
+        if arg == "--config" {
+            return match args.get(i + 1) {
+                Some(path) => Ok(Some(PathBuf::from(path))),
+                None => Err("--config needs a file path".to_string()),
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// The config files in use, for messages: the user config and any
+/// project config merged over it.
+fn config_files_description() -> String {
+    let user = Config::config_file_path().display().to_string();
+    match project_config_path() {
+        Some(project) => format!("{} and {}", user, project.display()),
+        None => user,
+    }
+}
+
+/// Checks the config file for `--check-config`, printing each problem.
+/// Returns the exit code: 0 if the file is fine, 1 otherwise.
+fn check_config() -> i32 {
+    let files = config_files_description();
+    let problems = match Config::read() {
+        Ok(config) => config_problems_of(&config),
+        Err(e) => vec![e],
+    };
+
+    if problems.is_empty() {
+        println!("{}: no problems found", files);
+        return 0;
+    }
+    eprintln!(
+        "{} problem{} in {}:",
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" },
+        files
+    );
+    for problem in &problems {
+        eprintln!("  {}", problem);
+    }
+    1
+}
+
+/// Problems in a config that parsed: bad keybindings and colours.
+fn config_problems_of(config: &Config) -> Vec<String> {
+    let mut problems = validate_keybindings(config);
+    problems.extend(config.color_problems());
+    problems.extend(context_menu_problems(config));
+    problems
+}
+
+/// Lists config problems in the output pager, which `q` dismisses.
+fn show_config_problems(problems: &[String], app_state: &mut AppState) {
+    if problems.is_empty() {
+        return;
+    }
+
+    let mut content = format!(
+        "{} problem{} in {}:\n\n",
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" },
+        config_files_description()
+    );
+    for problem in problems {
+        content.push_str(&format!("  {}\n", problem));
+    }
+    content.push_str("\nRun `dprs --check-config` to check the file again.\n");
+    app_state
+        .output_pager
+        .open("Config problems".to_string(), content);
+}
+
+/// Applies a config file edited while dprs runs. Keybindings, colours,
+/// layout and context menu actions are read from the config as they are
+/// used, so replacing it is enough. A file that does not parse leaves the
+/// current config in place.
+fn reload_changed_config(
+    config: &mut Config,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+) {
+    match Config::read() {
+        // Our own `:set!` also changes the file
+        Ok(new_config) if new_config.same_settings(config) => {}
+        Ok(new_config) => {
+            *config = new_config;
+            app_state.set_max_history(config.general.max_history_items);
+            toast_manager.show("Config file changed, reloaded", 2000);
+            show_config_problems(&config_problems_of(config), app_state);
+        }
+        Err(e) => {
+            // TOML errors quote the offending line; the toast only has room
+            // for the message itself
+            let summary: Vec<&str> = e
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.contains('|'))
+                .collect();
+            toast_manager.show(
+                &format!("Config not reloaded: {}", summary.join(": ")),
+                5000,
+            );
+        }
+    }
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     toast_manager: &mut ToastManager,
     mut config: Config,
+    config_problems: Vec<String>,
 ) -> Result<(), io::Error> {
     let mut last_refresh = Instant::now();
-    let refresh_interval = if config.should_auto_refresh() {
-        config.auto_refresh_interval()
-    } else {
-        Duration::from_millis(500) // Default refresh interval
-    };
     let mut app_state = AppState::new();
+    app_state.apply_config(&config);
     let mut command_executor = CommandExecutor::new();
     let input_watcher = InputWatcher::new();
     let mut effects: EffectManager<()> = EffectManager::default();
     let mut last_frame = Instant::now();
+    let mut watched_files = vec![Config::config_file_path()];
+    watched_files.extend(project_config_path());
+    let mut config_watcher = ConfigWatcher::new(watched_files, CONFIG_POLL_INTERVAL);
+
+    show_config_problems(&config_problems, &mut app_state);
+
+    // Restore command and search history from previous sessions
+    let history_file = HistoryFile::new(Config::history_file_path());
+    if let Err(e) = app_state.load_history(history_file, config.general.max_history_items) {
+        toast_manager.show(&format!("Could not load history: {}", e), 3000);
+    }
 
     // Initial load of containers
     if let Err(e) = app_state.refresh_containers() {
@@ -159,6 +314,9 @@ fn run_app<B: Backend>(
         // Update progress
         app_state.update_progress();
 
+        // Follow the selected container in the log pane
+        app_state.update_log_pane();
+
         // Draw UI
         terminal.draw(|f| {
             display::draw::<B>(
@@ -174,6 +332,11 @@ fn run_app<B: Backend>(
         // Handle toast expiration
         toast_manager.check_expired();
 
+        // Drop a key sequence left unfinished for too long
+        if app_state.key_sequence.expire(Instant::now()) {
+            app_state.take_count();
+        }
+
         // Handle input events from watcher
         if let Ok(Event::Key(key)) = input_watcher.try_recv() {
             handle_key_event(
@@ -185,10 +348,36 @@ fn run_app<B: Backend>(
             );
         }
 
+        // Hand the terminal to a foreground context menu command
+        if let Some(command) = app_state.foreground_command.take() {
+            run_in_foreground(terminal, &input_watcher, toast_manager, &command)?;
+        }
+
+        // Stream output of background commands into the pager
+        for message in app_state.update_running_actions() {
+            toast_manager.show(&message, 3000);
+        }
+
+        // Continue a command chain once its docker step has finished
+        if let Some((command, result)) = command_executor.resume(&mut app_state, &mut config) {
+            handle_command_result(&command, result, &mut app_state, toast_manager, &mut config);
+        }
+
+        // Pick up edits to the config file
+        if config_watcher.changed() {
+            reload_changed_config(&mut config, &mut app_state, toast_manager);
+        }
+
         // Small sleep to prevent busy waiting
         std::thread::sleep(Duration::from_millis(10));
 
-        // Periodic refresh check (if poll timed out or no event handled that resets the timer)
+        // Periodic refresh check (if poll timed out or no event handled that resets the timer).
+        // The interval is read every time since `:set autorefresh` can change it.
+        let refresh_interval = if config.should_auto_refresh() {
+            config.auto_refresh_interval()
+        } else {
+            Duration::from_millis(500) // Default refresh interval
+        };
         if last_refresh.elapsed() >= refresh_interval {
             if let Err(e) = app_state.refresh_containers() {
                 toast_manager.show(&format!("Auto-refresh error: {}", e), 3000);
@@ -204,6 +393,36 @@ fn run_app<B: Backend>(
     }
 }
 
+/// Suspends the TUI while `command` runs attached to the terminal, then
+/// waits for Enter so its last output can be read.
+fn run_in_foreground<B: Backend>(
+    terminal: &mut Terminal<B>,
+    input_watcher: &InputWatcher,
+    toast_manager: &mut ToastManager,
+    command: &str,
+) -> Result<(), io::Error> {
+    input_watcher.pause();
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+
+    println!("$ {}", command);
+    let status = match actions::run_foreground(command) {
+        Ok(Some(code)) => format!("exit status {}", code),
+        Ok(None) => "killed by a signal".to_string(),
+        Err(e) => e,
+    };
+    println!("\n[{}] Press Enter to return to dprs", status);
+    let mut line = String::new();
+    let _ = io::stdin().read_line(&mut line);
+
+    stdout().execute(EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+    input_watcher.resume();
+    toast_manager.show(&format!("Command finished: {}", status), 3000);
+    Ok(())
+}
+
 fn handle_key_event(
     key: crossterm::event::KeyEvent,
     app_state: &mut AppState,
@@ -211,6 +430,12 @@ fn handle_key_event(
     toast_manager: &mut ToastManager,
     config: &mut Config,
 ) {
+    // A pending confirmation takes every key until answered
+    if app_state.confirm_dialog.active {
+        handle_confirm_dialog_keys(key, app_state, command_executor, toast_manager, config);
+        return;
+    }
+
     match app_state.mode {
         Mode::Normal => handle_normal_mode(key, app_state, toast_manager, config),
         Mode::Visual => handle_visual_mode(key, app_state, toast_manager, config),
@@ -221,13 +446,77 @@ fn handle_key_event(
     }
 }
 
+/// Collects a count prefix digit (`5` of `5j`). Returns true if the key was
+/// consumed as part of the count.
+fn push_count_digit(key: crossterm::event::KeyEvent, app_state: &mut AppState) -> bool {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    match key.code {
+        KeyCode::Char(c @ '0'..='9') if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app_state.push_count_digit(c as usize - '0' as usize)
+        }
+        _ => false,
+    }
+}
+
+fn handle_confirm_dialog_keys(
+    key: crossterm::event::KeyEvent,
+    app_state: &mut AppState,
+    command_executor: &mut CommandExecutor,
+    toast_manager: &mut ToastManager,
+    config: &mut Config,
+) {
+    use crossterm::event::KeyCode;
+
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            match app_state.confirm_dialog.accept() {
+                Some(PendingAction::Command {
+                    operation,
+                    containers,
+                    then,
+                }) => {
+                    // The rest of a chain waits for the operation to succeed
+                    let result = command_executor.run_confirmed(&operation, &containers, &then);
+                    handle_command_result("", result, app_state, toast_manager, config);
+                }
+                Some(PendingAction::BatchStop) => stop_batch(app_state, toast_manager, config),
+                Some(PendingAction::ContextAction {
+                    label,
+                    command,
+                    run,
+                }) => run_context_action(&label, run, command, app_state, toast_manager),
+                Some(PendingAction::BatchRestart) => {
+                    restart_batch(app_state, toast_manager, config)
+                }
+                None => {}
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+            app_state.confirm_dialog.cancel();
+            toast_manager.show("Cancelled", 1500);
+        }
+        _ => {}
+    }
+}
+
 fn handle_normal_mode(
     key: crossterm::event::KeyEvent,
     app_state: &mut AppState,
     toast_manager: &mut ToastManager,
     config: &mut Config,
 ) {
-    use crossterm::event::{KeyCode, KeyModifiers};
+    // Handle output pager if open
+    if app_state.output_pager.active {
+        handle_output_pager_keys(key, app_state, toast_manager);
+        return;
+    }
+
+    // Handle inspect viewer if open
+    if app_state.json_viewer.active {
+        handle_json_viewer_keys(key, app_state, toast_manager);
+        return;
+    }
 
     // Handle context menu if active
     if app_state.context_menu.active {
@@ -235,57 +524,162 @@ fn handle_normal_mode(
         return;
     }
 
-    match key.code {
-        // Context menu
-        KeyCode::Char('.') => {
-            let container = app_state.get_selected_container().cloned();
-            let project = if app_state.compose_view_mode {
-                use dprs::dprs::display::compose_view::group_containers_by_project;
-                let projects = group_containers_by_project(app_state);
-                app_state
-                    .list_state
-                    .selected()
-                    .and_then(|idx| projects.get(idx).cloned())
-            } else {
-                None
-            };
-            app_state.context_menu.activate(container, project, config);
-        }
+    // A maximized log pane takes over navigation keys
+    if app_state.log_pane.maximized {
+        handle_log_pane_keys(key, app_state);
+        return;
+    }
 
-        // Quit
-        KeyCode::Char('q') => app_state.request_exit(),
+    // The filter prompt takes typed characters
+    if app_state.filter_mode {
+        handle_filter_input(key, app_state);
+        return;
+    }
 
-        // Basic navigation
-        KeyCode::Char('j') | KeyCode::Down => app_state.next(),
-        KeyCode::Char('k') | KeyCode::Up => app_state.previous(),
+    if let Some(event) = dispatch_key(key, app_state, toast_manager, config) {
+        handle_normal_event(event, app_state, toast_manager, config);
+    }
+}
+
+/// Feeds a key to the keymap, collecting count digits first. Returns the
+/// event once a bound key or sequence is complete.
+fn dispatch_key(
+    key: crossterm::event::KeyEvent,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &Config,
+) -> Option<AppEvent> {
+    // Digits only start a count outside a sequence
+    if app_state.key_sequence.pending().is_empty() && push_count_digit(key, app_state) {
+        return None;
+    }
 
-        // Vim-style navigation
-        KeyCode::Char('g') => {
-            // Handle gg sequence - for simplicity, just go to first for now
-            app_state.go_to_first();
+    let mode = app_state.mode.clone();
+    let key = key_event_to_string(key);
+    match app_state
+        .key_sequence
+        .feed(&key, &mode, config, Instant::now())
+    {
+        KeyDispatch::Event(event) => Some(event),
+        KeyDispatch::Pending => None,
+        KeyDispatch::UnknownAction(action) => {
+            app_state.take_count();
+            toast_manager.show(&format!("Unknown action for {}: {}", key, action), 3000);
+            None
         }
-        KeyCode::Char('G') => app_state.go_to_last(),
-        KeyCode::Char('w') => app_state.word_next(),
-        KeyCode::Char('b') => app_state.word_previous(),
-        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app_state.half_page_up()
+        KeyDispatch::Unbound => {
+            app_state.take_count();
+            None
         }
-        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app_state.half_page_down()
+    }
+}
+
+/// Moves the selection for navigation events, repeated or to a line by
+/// count. Returns false for any other event.
+fn apply_motion(event: AppEvent, count: Option<usize>, app_state: &mut AppState) -> bool {
+    let repeat = count.unwrap_or(1);
+    match event {
+        // A count moves that many lines without wrapping
+        AppEvent::SelectNext | AppEvent::ExtendSelectionNext => match count {
+            Some(n) => app_state.move_selection_by(n as isize),
+            None => app_state.next(),
+        },
+        AppEvent::SelectPrevious | AppEvent::ExtendSelectionPrevious => match count {
+            Some(n) => app_state.move_selection_by(-(n as isize)),
+            None => app_state.previous(),
+        },
+        AppEvent::GoToFirst => match count {
+            Some(n) => app_state.go_to_line(n - 1),
+            None => app_state.go_to_first(),
+        },
+        AppEvent::GoToLast => match count {
+            Some(n) => app_state.go_to_line(n - 1),
+            None => app_state.go_to_last(),
+        },
+        AppEvent::WordNext => (0..repeat).for_each(|_| app_state.word_next()),
+        AppEvent::WordPrevious => (0..repeat).for_each(|_| app_state.word_previous()),
+        AppEvent::HalfPageUp => (0..repeat).for_each(|_| app_state.half_page_up()),
+        AppEvent::HalfPageDown => (0..repeat).for_each(|_| app_state.half_page_down()),
+        _ => return false,
+    }
+    true
+}
+
+fn handle_normal_event(
+    event: AppEvent,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &mut Config,
+) {
+    let count = app_state.take_count();
+    if apply_motion(event, count, app_state) {
+        return;
+    }
+
+    match event {
+        AppEvent::OpenContextMenu => {
+            // In the compose view the cursor is on a project or one of its
+            // services, not a container
+            if app_state.compose_view_mode {
+                use dprs::dprs::display::compose_view::selected_target;
+                match selected_target(app_state) {
+                    Some((project, Some(service))) => app_state
+                        .context_menu
+                        .activate_service(project, service, config),
+                    project => app_state.context_menu.activate(
+                        None,
+                        project.map(|(project, _)| project),
+                        config,
+                    ),
+                }
+            } else {
+                let container = app_state.get_selected_container().cloned();
+                app_state.context_menu.activate(container, None, config);
+            }
         }
 
+        AppEvent::Quit => app_state.request_exit(),
+
         // Mode switching
-        KeyCode::Char('v') => app_state.enter_visual_mode(),
-        KeyCode::Char(':') => app_state.enter_command_mode(),
-        KeyCode::Char('/') => app_state.enter_search_mode(true),
-        KeyCode::Char('?') => app_state.enter_search_mode(false),
+        AppEvent::EnterVisualMode => app_state.enter_visual_mode(),
+        AppEvent::EnterCommandMode => app_state.enter_command_mode(),
+        AppEvent::EnterSearchForward => app_state.enter_search_mode(true),
+        AppEvent::EnterSearchBackward => app_state.enter_search_mode(false),
 
         // Search navigation
-        KeyCode::Char('n') => app_state.next_search_result(),
-        KeyCode::Char('N') => app_state.previous_search_result(),
+        AppEvent::NextSearchResult => app_state.next_search_result(),
+        AppEvent::PreviousSearchResult => app_state.previous_search_result(),
+
+        // Marked containers take precedence over the selected one
+        AppEvent::StopContainer | AppEvent::StopSelectedContainers if has_marks(app_state) => {
+            start_batch(PendingAction::BatchStop, app_state, toast_manager, config)
+        }
+        AppEvent::RestartContainer | AppEvent::RestartSelectedContainers
+            if has_marks(app_state) =>
+        {
+            start_batch(
+                PendingAction::BatchRestart,
+                app_state,
+                toast_manager,
+                config,
+            )
+        }
+        AppEvent::CopyIp if has_marks(app_state) => {
+            match actions::copy_marked_ip_addresses(app_state) {
+                Ok(count) => toast_manager.show(
+                    &format!(
+                        "IP addresses of {} container{} copied to clipboard",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    2000,
+                ),
+                Err(e) => toast_manager.show(&format!("Error copying IPs: {}", e), 3000),
+            }
+        }
 
         // Container/Project actions (behavior depends on compose_view_mode)
-        KeyCode::Char('s') => {
+        AppEvent::StopContainer => {
             if app_state.compose_view_mode {
                 if let Some(selected) = app_state.list_state.selected() {
                     match actions::stop_compose_project(app_state, selected, &*config) {
@@ -304,25 +698,21 @@ fn handle_normal_mode(
                 }
             }
         }
-        KeyCode::Char('c') => {
-            if !app_state.compose_view_mode {
-                match actions::copy_ip_address(app_state) {
-                    Ok(ip) => {
-                        toast_manager.show(&format!("IP address copied to clipboard: {}", ip), 2000)
-                    }
-                    Err(e) => toast_manager.show(&format!("Error copying IP: {}", e), 3000),
+        AppEvent::CopyIp if !app_state.compose_view_mode => {
+            match actions::copy_ip_address(app_state) {
+                Ok(ip) => {
+                    toast_manager.show(&format!("IP address copied to clipboard: {}", ip), 2000)
                 }
+                Err(e) => toast_manager.show(&format!("Error copying IP: {}", e), 3000),
             }
         }
-        KeyCode::Char('o') => {
-            if !app_state.compose_view_mode {
-                match actions::open_browser(app_state) {
-                    Ok(_) => toast_manager.show("Opening browser...", 2000),
-                    Err(e) => toast_manager.show(&format!("Error opening browser: {}", e), 3000),
-                }
+        AppEvent::OpenBrowser if !app_state.compose_view_mode => {
+            match actions::open_browser(app_state) {
+                Ok(_) => toast_manager.show("Opening browser...", 2000),
+                Err(e) => toast_manager.show(&format!("Error opening browser: {}", e), 3000),
             }
         }
-        KeyCode::Char('r') => {
+        AppEvent::RestartContainer => {
             if app_state.compose_view_mode {
                 if let Some(selected) = app_state.list_state.selected() {
                     match actions::restart_compose_project(app_state, selected, &*config) {
@@ -342,7 +732,11 @@ fn handle_normal_mode(
                 }
             }
         }
-        KeyCode::Char('t') => {
+        AppEvent::Refresh => match app_state.refresh_containers() {
+            Ok(_) => toast_manager.show("Container list refreshed", 1500),
+            Err(e) => toast_manager.show(&format!("Refresh error: {}", e), 3000),
+        },
+        AppEvent::ToggleTabular => {
             app_state.tabular_mode = !app_state.tabular_mode;
             let mode_text = if app_state.tabular_mode {
                 "tabular"
@@ -351,7 +745,7 @@ fn handle_normal_mode(
             };
             toast_manager.show(&format!("Switched to {} view", mode_text), 1500);
         }
-        KeyCode::Char('p') => {
+        AppEvent::ToggleComposeView => {
             app_state.compose_view_mode = !app_state.compose_view_mode;
             let mode_text = if app_state.compose_view_mode {
                 "compose projects"
@@ -364,37 +758,58 @@ fn handle_normal_mode(
             toast_manager.show(&format!("Switched to {} view", mode_text), 1500);
         }
 
+        // Log pane
+        AppEvent::ToggleLogPane => app_state.log_pane.toggle(),
+        AppEvent::MaximizeLogPane => app_state.log_pane.toggle_maximized(),
+        AppEvent::ScrollLogPaneDown => app_state.log_pane.scroll_down(),
+        AppEvent::ScrollLogPaneUp => app_state.log_pane.scroll_up(),
+        AppEvent::FollowLogPane => app_state.log_pane.enable_follow(),
+
         // Filter
-        KeyCode::Char('f') => app_state.enter_filter_mode(),
+        AppEvent::EnterFilterMode => app_state.enter_filter_mode(),
 
         // Container filter toggles
-        KeyCode::Char('+') => {
+        AppEvent::ToggleRecent => {
             app_state.toggle_recent();
             let filter_name = app_state.container_filter.display_name();
             toast_manager.show(&format!("Switched to {} containers", filter_name), 1500);
         }
-        KeyCode::Char('!') => {
+        AppEvent::ToggleAll => {
             app_state.toggle_all();
             let filter_name = app_state.container_filter.display_name();
             toast_manager.show(&format!("Switched to {} containers", filter_name), 1500);
         }
 
-        KeyCode::Esc => {
+        // Marks
+        AppEvent::ToggleMark if !app_state.compose_view_mode => {
+            for _ in 0..count.unwrap_or(1) {
+                app_state.toggle_mark();
+                app_state.move_selection_by(1);
+            }
+        }
+        AppEvent::MarkSearchMatches if !app_state.compose_view_mode => {
+            let count = app_state.mark_search_matches();
+            toast_manager.show(&format!("Marked {} search matches", count), 1500);
+        }
+        AppEvent::InvertMarks if !app_state.compose_view_mode => {
+            app_state.invert_marks();
+            toast_manager.show(&format!("{} marked", app_state.marked.len()), 1500);
+        }
+
+        AppEvent::ClearFilter => {
             if !app_state.filter_text.is_empty() {
                 app_state.clear_filter();
                 toast_manager.show("Filter cleared", 1500);
             } else if !app_state.search_state.matches.is_empty() {
                 app_state.search_state.clear();
                 toast_manager.show("Search cleared", 1500);
+            } else if !app_state.marked.is_empty() {
+                app_state.clear_marks();
+                toast_manager.show("Marks cleared", 1500);
             }
         }
 
-        // Legacy support for old filter mode
-        _ => {
-            if app_state.filter_mode {
-                handle_filter_input(key, app_state);
-            }
-        }
+        _ => {}
     }
 }
 
@@ -404,108 +819,192 @@ fn handle_visual_mode(
     toast_manager: &mut ToastManager,
     config: &mut Config,
 ) {
-    use crossterm::event::KeyCode;
+    let Some(event) = dispatch_key(key, app_state, toast_manager, config) else {
+        return;
+    };
 
-    match key.code {
-        KeyCode::Char('j') | KeyCode::Down => {
-            app_state.next();
-            if let Some(ref mut selection) = app_state.visual_selection {
-                if let Some(current) = app_state.list_state.selected() {
-                    selection.extend_to(current);
-                }
+    let count = app_state.take_count();
+    if apply_motion(event, count, app_state) {
+        if let Some(ref mut selection) = app_state.visual_selection {
+            if let Some(current) = app_state.list_state.selected() {
+                selection.extend_to(current);
             }
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            app_state.previous();
-            if let Some(ref mut selection) = app_state.visual_selection {
-                if let Some(current) = app_state.list_state.selected() {
-                    selection.extend_to(current);
+        return;
+    }
+
+    match event {
+        // As in vim, `:` from visual mode starts a command on the selection
+        AppEvent::EnterCommandMode => {
+            app_state.enter_command_mode();
+            app_state.command_state.input = "'<,'>".to_string();
+            app_state.command_state.cursor_pos = app_state.command_state.input.len();
+        }
+        AppEvent::StopContainer | AppEvent::StopSelectedContainers => {
+            start_batch(PendingAction::BatchStop, app_state, toast_manager, config)
+        }
+        AppEvent::RestartContainer | AppEvent::RestartSelectedContainers => start_batch(
+            PendingAction::BatchRestart,
+            app_state,
+            toast_manager,
+            config,
+        ),
+        // Marks every line of the selection and leaves visual mode
+        AppEvent::MarkSelection | AppEvent::ToggleMark if !app_state.compose_view_mode => {
+            let displayed = app_state.get_displayed_containers();
+            for index in app_state.get_selected_indices() {
+                if let Some(container) = displayed.get(index) {
+                    app_state.marked.insert(container.name.clone());
                 }
             }
+            toast_manager.show(&format!("{} marked", app_state.marked.len()), 1500);
+            app_state.enter_normal_mode();
         }
-        KeyCode::Char('G') => {
-            app_state.go_to_last();
+        AppEvent::EnterNormalMode | AppEvent::EnterVisualMode | AppEvent::ClearFilter => {
+            app_state.enter_normal_mode()
+        }
+        AppEvent::Quit => app_state.request_exit(),
+        _ => {}
+    }
+}
+
+/// Marks only apply to the container list, not the compose view.
+fn has_marks(app_state: &AppState) -> bool {
+    !app_state.compose_view_mode && !app_state.marked.is_empty()
+}
+
+/// The containers a batch action covers: the visual selection, else the
+/// marked containers. In the compose view these are the containers of the
+/// selected projects and services.
+fn batch_containers(app_state: &AppState) -> Vec<dprs::dprs::app::state_machine::Container> {
+    let indices = app_state.get_selected_indices();
+    if app_state.compose_view_mode {
+        use dprs::dprs::display::compose_view::{row_containers, rows_at};
+        let projects = &app_state.compose_projects;
+        let names: Vec<String> = rows_at(projects, &indices)
+            .into_iter()
+            .flat_map(|row| row_containers(projects, row).1)
+            .collect();
+        app_state
+            .containers
+            .iter()
+            .filter(|c| names.contains(&c.name))
+            .cloned()
+            .collect()
+    } else {
+        let displayed = app_state.get_displayed_containers();
+        indices
+            .iter()
+            .filter_map(|&i| displayed.get(i).cloned())
+            .collect()
+    }
+}
+
+/// Batches go through the `[confirm]` policy whatever the operation.
+fn batch_needs_confirmation(app_state: &AppState, config: &Config) -> bool {
+    let targets = batch_containers(app_state);
+    let labels: Vec<_> = targets.iter().map(|c| &c.labels).collect();
+    config.confirm.requires_confirmation(&labels)
+}
+
+/// Runs a batch stop or restart, asking first if the policy says so.
+fn start_batch(
+    action: PendingAction,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &Config,
+) {
+    let operation = match action {
+        PendingAction::BatchRestart => "restart",
+        _ => "stop",
+    };
+    if batch_needs_confirmation(app_state, config) {
+        open_batch_confirmation(operation, action, app_state);
+    } else if action == PendingAction::BatchRestart {
+        restart_batch(app_state, toast_manager, config);
+    } else {
+        stop_batch(app_state, toast_manager, config);
+    }
+}
+
+fn open_batch_confirmation(operation: &str, action: PendingAction, app_state: &mut AppState) {
+    let names: Vec<String> = batch_containers(app_state)
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    app_state
+        .confirm_dialog
+        .open(confirm_title(operation, names.len()), names, action);
+}
+
+fn stop_batch(app_state: &mut AppState, toast_manager: &mut ToastManager, config: &Config) {
+    if app_state.compose_view_mode {
+        match actions::stop_selected_compose_projects(app_state, config) {
+            Ok(_) => {
+                let count = app_state.get_selected_indices().len();
+                toast_manager.show(
+                    &format!(
+                        "Stopped {} project{}",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    2000,
+                );
+            }
+            Err(e) => toast_manager.show(&format!("Error stopping projects: {}", e), 3000),
         }
-        KeyCode::Char('g') => {
-            app_state.go_to_first();
+    } else {
+        match actions::stop_selected_containers(app_state, config) {
+            Ok(_) => {
+                let count = app_state.get_selected_indices().len();
+                toast_manager.show(
+                    &format!(
+                        "Stopped {} container{}",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    2000,
+                );
+            }
+            Err(e) => toast_manager.show(&format!("Error stopping containers: {}", e), 3000),
         }
-        KeyCode::Char('s') => {
-            if app_state.compose_view_mode {
-                match actions::stop_selected_compose_projects(app_state, &*config) {
-                    Ok(_) => {
-                        let count = app_state.get_selected_indices().len();
-                        toast_manager.show(
-                            &format!(
-                                "Stopped {} project{}",
-                                count,
-                                if count == 1 { "" } else { "s" }
-                            ),
-                            2000,
-                        );
-                    }
-                    Err(e) => toast_manager.show(&format!("Error stopping projects: {}", e), 3000),
-                }
-            } else {
-                match actions::stop_selected_containers(app_state, &*config) {
-                    Ok(_) => {
-                        let count = app_state.get_selected_indices().len();
-                        toast_manager.show(
-                            &format!(
-                                "Stopped {} container{}",
-                                count,
-                                if count == 1 { "" } else { "s" }
-                            ),
-                            2000,
-                        );
-                    }
-                    Err(e) => {
-                        toast_manager.show(&format!("Error stopping containers: {}", e), 3000)
-                    }
-                }
+    }
+    app_state.enter_normal_mode();
+}
+
+fn restart_batch(app_state: &mut AppState, toast_manager: &mut ToastManager, config: &Config) {
+    if app_state.compose_view_mode {
+        match actions::restart_selected_compose_projects(app_state, config) {
+            Ok(_) => {
+                let count = app_state.get_selected_indices().len();
+                toast_manager.show(
+                    &format!(
+                        "Restarted {} project{}",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    2000,
+                );
             }
-            app_state.enter_normal_mode();
+            Err(e) => toast_manager.show(&format!("Error restarting projects: {}", e), 3000),
         }
-        KeyCode::Char('r') => {
-            if app_state.compose_view_mode {
-                match actions::restart_selected_compose_projects(app_state, &*config) {
-                    Ok(_) => {
-                        let count = app_state.get_selected_indices().len();
-                        toast_manager.show(
-                            &format!(
-                                "Restarted {} project{}",
-                                count,
-                                if count == 1 { "" } else { "s" }
-                            ),
-                            2000,
-                        );
-                    }
-                    Err(e) => {
-                        toast_manager.show(&format!("Error restarting projects: {}", e), 3000)
-                    }
-                }
-            } else {
-                match actions::restart_selected_containers(app_state, &*config) {
-                    Ok(_) => {
-                        let count = app_state.get_selected_indices().len();
-                        toast_manager.show(
-                            &format!(
-                                "Restarted {} container{}",
-                                count,
-                                if count == 1 { "" } else { "s" }
-                            ),
-                            2000,
-                        );
-                    }
-                    Err(e) => {
-                        toast_manager.show(&format!("Error restarting containers: {}", e), 3000)
-                    }
-                }
+    } else {
+        match actions::restart_selected_containers(app_state, config) {
+            Ok(_) => {
+                let count = app_state.get_selected_indices().len();
+                toast_manager.show(
+                    &format!(
+                        "Restarted {} container{}",
+                        count,
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    2000,
+                );
             }
-            app_state.enter_normal_mode();
+            Err(e) => toast_manager.show(&format!("Error restarting containers: {}", e), 3000),
         }
-        KeyCode::Esc => app_state.enter_normal_mode(),
-        _ => {}
     }
+    app_state.enter_normal_mode();
 }
 
 fn handle_command_mode(
@@ -515,44 +1014,156 @@ fn handle_command_mode(
     toast_manager: &mut ToastManager,
     config: &mut Config,
 ) {
-    use crossterm::event::KeyCode;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    // Ctrl+R reverse incremental search through the command history
+    if app_state.command_state.reverse_search.is_some() {
+        let command_state = &mut app_state.command_state;
+        match key.code {
+            KeyCode::Char('r') if ctrl => command_state.reverse_search_next(),
+            KeyCode::Char(c) if !ctrl => command_state.reverse_search_push(c),
+            KeyCode::Backspace => command_state.reverse_search_pop(),
+            KeyCode::Esc => command_state.cancel_reverse_search(),
+            KeyCode::Char('g') if ctrl => command_state.cancel_reverse_search(),
+            // Any other key keeps the match and is handled as usual below
+            _ => {
+                command_state.accept_reverse_search();
+                handle_command_mode(key, app_state, command_executor, toast_manager, config);
+            }
+        }
+        return;
+    } else if key.code == KeyCode::Char('r') && ctrl {
+        app_state.completion.clear();
+        app_state.command_state.start_reverse_search();
+        return;
+    }
+
+    if config.get_key_binding("command", &key_event_to_string(key)) == Some("TabComplete") {
+        if !completion::tab_complete(app_state, config, true) {
+            toast_manager.show("No completions", 1000);
+        }
+        return;
+    }
 
     match key.code {
+        KeyCode::BackTab => {
+            completion::tab_complete(app_state, config, false);
+        }
+        KeyCode::Esc if app_state.completion.is_active() => {
+            let AppState {
+                completion,
+                command_state,
+                ..
+            } = app_state;
+            completion.cancel(command_state);
+        }
         KeyCode::Enter => {
+            app_state.completion.clear();
             let command = app_state.command_state.input.clone();
-            match command_executor.execute(&command, app_state) {
-                CommandResult::Success(msg) => {
-                    toast_manager.show(&msg, 2000);
-                    app_state.command_state.add_to_history(command);
-                }
-                CommandResult::Error(msg) => {
-                    toast_manager.show(&format!("Error: {}", msg), 3000);
-                }
-                CommandResult::Navigation(line) => {
-                    app_state.list_state.select(Some(line));
-                    app_state.table_state.select(Some(line));
-                    toast_manager.show(&format!("Jumped to line {}", line + 1), 1500);
-                }
-                CommandResult::Quit => {
-                    app_state.request_exit();
-                }
-                CommandResult::ConfigReload(new_config) => {
-                    *config = *new_config;
-                    toast_manager.show("Configuration reloaded", 2000);
-                    app_state.command_state.add_to_history(command);
+            match command_executor.execute(&command, app_state, config) {
+                CommandResult::ParseError { message, position } => {
+                    // Stay in command mode with the cursor on the bad token
+                    toast_manager.show(
+                        &format!("Error at column {}: {}", position + 1, message),
+                        4000,
+                    );
+                    app_state.command_state.cursor_pos =
+                        position.min(app_state.command_state.input.len());
+                    return;
                 }
+                result => handle_command_result(&command, result, app_state, toast_manager, config),
             }
+            save_history(app_state, toast_manager);
             app_state.enter_normal_mode();
         }
         KeyCode::Esc => {
             app_state.enter_normal_mode();
         }
         _ => {
+            app_state.completion.clear();
             app_state.command_state.handle_key(key);
         }
     }
 }
 
+/// Shows the outcome of a command line in the UI. Parse errors are handled by
+/// the caller, which keeps command mode open on them.
+fn handle_command_result(
+    command: &str,
+    result: CommandResult,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &mut Config,
+) {
+    match result {
+        CommandResult::Success(msg) => {
+            // Multi-line results are unreadable in a toast
+            if msg.lines().count() > 1 {
+                app_state.output_pager.open(format!(":{}", command), msg);
+            } else {
+                toast_manager.show(&msg, 2000);
+            }
+            app_state.command_state.add_to_history(command.to_string());
+        }
+        CommandResult::Error(msg) => {
+            toast_manager.show(&format!("Error: {}", msg), 3000);
+        }
+        CommandResult::Running(msg) => {
+            toast_manager.show(&msg, 2000);
+            app_state.command_state.add_to_history(command.to_string());
+        }
+        CommandResult::Navigation(line) => {
+            app_state.list_state.select(Some(line));
+            app_state.table_state.select(Some(line));
+            toast_manager.show(&format!("Jumped to line {}", line + 1), 1500);
+        }
+        CommandResult::Output { title, content } => {
+            app_state.output_pager.open(title, content);
+            app_state.command_state.add_to_history(command.to_string());
+        }
+        CommandResult::Inspect {
+            container_name,
+            data,
+        } => {
+            app_state
+                .json_viewer
+                .open(format!("Inspect: {}", container_name), data);
+            app_state.command_state.add_to_history(command.to_string());
+        }
+        CommandResult::Quit => {
+            app_state.request_exit();
+        }
+        CommandResult::ConfigReload(new_config) => {
+            *config = *new_config;
+            app_state.apply_config(config);
+            toast_manager.show("Configuration reloaded", 2000);
+            show_config_problems(&config_problems_of(config), app_state);
+            app_state.command_state.add_to_history(command.to_string());
+        }
+        CommandResult::ParseError { message, .. } => {
+            toast_manager.show(&format!("Error: {}", message), 3000);
+        }
+        CommandResult::Confirm {
+            operation,
+            containers,
+            then,
+        } => {
+            app_state.confirm_dialog.open(
+                confirm_title(&operation, containers.len()),
+                containers.clone(),
+                PendingAction::Command {
+                    operation,
+                    containers,
+                    then,
+                },
+            );
+            app_state.command_state.add_to_history(command.to_string());
+        }
+    }
+}
+
 fn handle_search_mode(
     key: crossterm::event::KeyEvent,
     app_state: &mut AppState,
@@ -564,6 +1175,8 @@ fn handle_search_mode(
         KeyCode::Enter => {
             let query = app_state.search_state.query.clone();
             app_state.perform_search(&query);
+            app_state.search_state.add_to_history(query);
+            save_history(app_state, toast_manager);
 
             let matches_count = app_state.search_state.matches.len();
             if matches_count > 0 {
@@ -592,10 +1205,25 @@ fn handle_search_mode(
                 app_state.search_state.clear();
             }
         }
+        KeyCode::Up | KeyCode::Down => {
+            app_state
+                .search_state
+                .navigate_history(key.code == KeyCode::Up);
+            let query = app_state.search_state.query.clone();
+            if !query.is_empty() {
+                app_state.perform_search(&query);
+            }
+        }
         _ => {}
     }
 }
 
+fn save_history(app_state: &AppState, toast_manager: &mut ToastManager) {
+    if let Err(e) = app_state.save_history() {
+        toast_manager.show(&format!("Could not save history: {}", e), 3000);
+    }
+}
+
 fn handle_filter_input(key: crossterm::event::KeyEvent, app_state: &mut AppState) {
     use crossterm::event::KeyCode;
 
@@ -623,10 +1251,15 @@ fn handle_context_menu_keys(
     key: crossterm::event::KeyEvent,
     app_state: &mut AppState,
     toast_manager: &mut ToastManager,
-    _config: &Config,
+    config: &Config,
 ) {
     use crossterm::event::KeyCode;
 
+    if app_state.context_menu.form.is_some() {
+        handle_prompt_form_keys(key, app_state, toast_manager, config);
+        return;
+    }
+
     match key.code {
         KeyCode::Char('j') | KeyCode::Down => {
             app_state.context_menu.next();
@@ -635,17 +1268,23 @@ fn handle_context_menu_keys(
             app_state.context_menu.previous();
         }
         KeyCode::Enter => {
-            if let Some(command) = app_state.context_menu.execute_selected_action() {
-                // Execute the command in a shell
-                toast_manager.show("Executing action...", 2000);
-
-                std::thread::spawn(move || {
-                    use std::process::Command;
-                    let _ = Command::new("sh").arg("-c").arg(&command).spawn();
-                });
-
-                app_state.context_menu.deactivate();
+            let Some(action) = app_state.context_menu.get_selected_action().cloned() else {
+                return;
+            };
+            let command = match app_state.context_menu.expanded_command() {
+                Some(Ok(command)) => command,
+                Some(Err(reason)) => {
+                    toast_manager.show(&format!("{} unavailable: {}", action.label, reason), 3000);
+                    return;
+                }
+                None => return,
+            };
+            // Actions with prompts ask for their values first
+            if app_state.context_menu.open_form() {
+                return;
             }
+            app_state.context_menu.deactivate();
+            start_context_action(&action, command, app_state, toast_manager, config);
         }
         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('.') => {
             app_state.context_menu.deactivate();
@@ -654,6 +1293,219 @@ fn handle_context_menu_keys(
     }
 }
 
+fn handle_prompt_form_keys(
+    key: crossterm::event::KeyEvent,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &Config,
+) {
+    use crossterm::event::KeyCode;
+
+    let menu = &mut app_state.context_menu;
+    let Some(form) = menu.form.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Tab | KeyCode::Down => form.next_field(),
+        KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+        KeyCode::Backspace => form.pop_char(),
+        KeyCode::Char(c) => form.push_char(c),
+        KeyCode::Esc => menu.form = None,
+        KeyCode::Enter => {
+            let Some(action) = menu.get_selected_action().cloned() else {
+                return;
+            };
+            match menu.submit_form() {
+                Some(Ok(command)) => {
+                    menu.deactivate();
+                    start_context_action(&action, command, app_state, toast_manager, config);
+                }
+                Some(Err(reason)) => {
+                    toast_manager.show(&format!("{} unavailable: {}", action.label, reason), 3000)
+                }
+                None => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Runs a context menu action, first asking in the confirm dialog if the
+/// action is marked `confirm` and the `[confirm]` policy is not "never".
+fn start_context_action(
+    action: &ContextMenuAction,
+    command: String,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+    config: &Config,
+) {
+    if action.confirm && config.confirm.policy != "never" {
+        app_state.confirm_dialog.open(
+            format!("{}?", action.label),
+            vec![command.clone()],
+            PendingAction::ContextAction {
+                label: action.label.clone(),
+                command,
+                run: action.run,
+            },
+        );
+        return;
+    }
+    run_context_action(&action.label, action.run, command, app_state, toast_manager);
+}
+
+fn run_context_action(
+    label: &str,
+    run: ActionRunMode,
+    command: String,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+) {
+    match run {
+        ActionRunMode::Background => {
+            if let Err(e) = app_state.start_action(label, &command) {
+                toast_manager.show(&format!("Error: {}", e), 3000);
+            }
+        }
+        // Run by the main loop, which owns the terminal
+        ActionRunMode::Foreground => app_state.foreground_command = Some(command),
+    }
+}
+
+fn handle_log_pane_keys(key: crossterm::event::KeyEvent, app_state: &mut AppState) {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let pane = &mut app_state.log_pane;
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => pane.scroll_down(),
+        KeyCode::Char('k') | KeyCode::Up => pane.scroll_up(),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => pane.page_down(),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => pane.page_up(),
+        KeyCode::PageDown => pane.page_down(),
+        KeyCode::PageUp => pane.page_up(),
+        KeyCode::Char('g') | KeyCode::Home => pane.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End | KeyCode::Char('F') => pane.enable_follow(),
+        KeyCode::Char('L') => pane.toggle(),
+        KeyCode::Char('Z') | KeyCode::Esc | KeyCode::Char('q') => pane.toggle_maximized(),
+        // Switch the followed container without leaving the pane
+        KeyCode::Char('J') => app_state.next(),
+        KeyCode::Char('K') => app_state.previous(),
+        KeyCode::Char(':') => app_state.enter_command_mode(),
+        _ => {}
+    }
+}
+
+fn handle_output_pager_keys(
+    key: crossterm::event::KeyEvent,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+) {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let pager = &mut app_state.output_pager;
+
+    // Search or save-as input captures all keys until submitted
+    if pager.input.is_some() {
+        match key.code {
+            KeyCode::Enter => pager.submit_input(),
+            KeyCode::Esc => pager.cancel_input(),
+            KeyCode::Backspace => pager.pop_input(),
+            KeyCode::Char(c) => pager.push_input(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => pager.scroll_down(),
+        KeyCode::Char('k') | KeyCode::Up => pager.scroll_up(),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            pager.half_page_down()
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => pager.half_page_up(),
+        KeyCode::PageDown | KeyCode::Char(' ') => pager.page_down(),
+        KeyCode::PageUp => pager.page_up(),
+        KeyCode::Char('g') | KeyCode::Home => pager.scroll_to_top(),
+        KeyCode::Char('G') | KeyCode::End => pager.scroll_to_bottom(),
+        KeyCode::Char('l') | KeyCode::Right => pager.scroll_right(),
+        KeyCode::Char('h') | KeyCode::Left => pager.scroll_left(),
+        KeyCode::Char('/') => pager.begin_search(),
+        KeyCode::Char('n') => pager.next_match(),
+        KeyCode::Char('N') => pager.previous_match(),
+        KeyCode::Char('w') => pager.begin_save(),
+        KeyCode::Char('y') => {
+            if let Some(content) = pager.content() {
+                match actions::copy_to_clipboard(content) {
+                    Ok(_) => toast_manager.show("Output copied to clipboard", 1500),
+                    Err(e) => toast_manager.show(&format!("Error copying output: {}", e), 3000),
+                }
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => pager.close(),
+        _ => {}
+    }
+}
+
+fn handle_json_viewer_keys(
+    key: crossterm::event::KeyEvent,
+    app_state: &mut AppState,
+    toast_manager: &mut ToastManager,
+) {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let viewer = &mut app_state.json_viewer;
+
+    // Search or jump-to-path input captures all keys until submitted
+    if viewer.input.is_some() {
+        match key.code {
+            KeyCode::Enter => viewer.submit_input(),
+            KeyCode::Esc => viewer.cancel_input(),
+            KeyCode::Backspace => viewer.pop_input(),
+            KeyCode::Char(c) => viewer.push_input(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => viewer.next(),
+        KeyCode::Char('k') | KeyCode::Up => viewer.previous(),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => viewer.page_down(),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => viewer.page_up(),
+        KeyCode::PageDown => viewer.page_down(),
+        KeyCode::PageUp => viewer.page_up(),
+        KeyCode::Char('g') | KeyCode::Home => viewer.go_to_first(),
+        KeyCode::Char('G') | KeyCode::End => viewer.go_to_last(),
+        KeyCode::Enter | KeyCode::Char(' ') => viewer.toggle(),
+        KeyCode::Char('l') | KeyCode::Right => viewer.expand(),
+        KeyCode::Char('h') | KeyCode::Left => viewer.collapse(),
+        KeyCode::Char('E') => viewer.expand_all(),
+        KeyCode::Char('C') => viewer.collapse_all(),
+        KeyCode::Char('/') => viewer.begin_search(),
+        KeyCode::Char(':') => viewer.begin_jump(),
+        KeyCode::Char('n') => viewer.next_match(),
+        KeyCode::Char('N') => viewer.previous_match(),
+        KeyCode::Char('y') => {
+            if let Some(value) = viewer.selected_value() {
+                match actions::copy_to_clipboard(&value) {
+                    Ok(_) => toast_manager.show("Value copied to clipboard", 1500),
+                    Err(e) => toast_manager.show(&format!("Error copying value: {}", e), 3000),
+                }
+            }
+        }
+        KeyCode::Char('Y') => {
+            if let Some(path) = viewer.selected_path() {
+                match actions::copy_to_clipboard(&path) {
+                    Ok(_) => toast_manager.show(&format!("Path copied: {}", path), 1500),
+                    Err(e) => toast_manager.show(&format!("Error copying path: {}", e), 3000),
+                }
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => viewer.close(),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
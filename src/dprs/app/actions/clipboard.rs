@@ -0,0 +1,16 @@
+//  Implements a small clipboard helper shared by the actions that copy text
+//  out of the TUI, such as container IP addresses and values yanked from the
+//  inspect viewer.
+
+use copypasta_ext::prelude::*;
+use copypasta_ext::x11_bin::ClipboardContext;
+
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let mut ctx: ClipboardContext =
+        ClipboardContext::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
+
+    ctx.set_contents(text.to_owned())
+        .map_err(|e| format!("Failed to copy to clipboard: {}", e))
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -1,21 +1,24 @@
 // Actions for Docker Compose project operations.
 // These actions allow stopping, restarting, and managing entire compose projects at once.
+// They take rows of the compose view, so a service row acts on the
+// replicas of that service only.
 
 use crate::dprs::app::state_machine::{AppState, ProgressUpdate};
-use crate::dprs::display::compose_view::{group_containers_by_project, ComposeProject};
+use crate::dprs::display::compose_view::{compose_rows, row_containers, rows_at};
 use crate::shared::config::Config;
 use std::io::Error;
 use std::process::Command;
 
 pub fn restart_compose_project(
     app_state: &mut AppState,
-    project_index: usize,
+    row_index: usize,
     config: &Config,
 ) -> Result<(), Error> {
-    let projects = group_containers_by_project(app_state);
+    let projects = &app_state.compose_projects;
 
-    if let Some(project) = projects.get(project_index) {
-        restart_project(project, app_state, config)
+    if let Some(&row) = compose_rows(projects).get(row_index) {
+        let (name, containers) = row_containers(projects, row);
+        restart_project(&name, containers, app_state, config)
     } else {
         Err(Error::other("Invalid project index"))
     }
@@ -23,13 +26,14 @@ pub fn restart_compose_project(
 
 pub fn stop_compose_project(
     app_state: &mut AppState,
-    project_index: usize,
+    row_index: usize,
     config: &Config,
 ) -> Result<(), Error> {
-    let projects = group_containers_by_project(app_state);
+    let projects = &app_state.compose_projects;
 
-    if let Some(project) = projects.get(project_index) {
-        stop_project(project, app_state, config)
+    if let Some(&row) = compose_rows(projects).get(row_index) {
+        let (name, containers) = row_containers(projects, row);
+        stop_project(&name, containers, app_state, config)
     } else {
         Err(Error::other("Invalid project index"))
     }
@@ -39,36 +43,32 @@ pub fn restart_selected_compose_projects(
     app_state: &mut AppState,
     _config: &Config,
 ) -> Result<(), Error> {
-    let projects = group_containers_by_project(app_state);
-    let selected_indices = app_state.get_selected_indices();
+    let projects = &app_state.compose_projects;
+    let targets: Vec<(String, Vec<String>)> = rows_at(projects, &app_state.get_selected_indices())
+        .into_iter()
+        .map(|row| row_containers(projects, row))
+        .collect();
 
-    if selected_indices.is_empty() {
+    if targets.is_empty() {
         return Err(Error::other("No projects selected"));
     }
 
-    let total = selected_indices.len();
+    let total = targets.len();
     let progress_sender = app_state.start_progress(format!("Restarting projects... (0/{})", total));
 
     std::thread::spawn(move || {
-        for (idx, &project_idx) in selected_indices.iter().enumerate() {
-            if let Some(project) = projects.get(project_idx) {
-                let percentage = if total > 0 {
-                    (idx as f32 / total as f32) * 100.0
-                } else {
-                    0.0
-                };
-                let _ = progress_sender.send(ProgressUpdate::Update {
-                    message: format!(
-                        "Restarting {} ({}/{})",
-                        project.project_name,
-                        idx + 1,
-                        total
-                    ),
-                    percentage,
-                });
-
-                restart_project_sync(project);
-            }
+        for (idx, (name, containers)) in targets.iter().enumerate() {
+            let percentage = if total > 0 {
+                (idx as f32 / total as f32) * 100.0
+            } else {
+                0.0
+            };
+            let _ = progress_sender.send(ProgressUpdate::Update {
+                message: format!("Restarting {} ({}/{})", name, idx + 1, total),
+                percentage,
+            });
+
+            restart_project_sync(containers);
         }
 
         let _ = progress_sender.send(ProgressUpdate::Complete);
@@ -81,31 +81,32 @@ pub fn stop_selected_compose_projects(
     app_state: &mut AppState,
     _config: &Config,
 ) -> Result<(), Error> {
-    let projects = group_containers_by_project(app_state);
-    let selected_indices = app_state.get_selected_indices();
+    let projects = &app_state.compose_projects;
+    let targets: Vec<(String, Vec<String>)> = rows_at(projects, &app_state.get_selected_indices())
+        .into_iter()
+        .map(|row| row_containers(projects, row))
+        .collect();
 
-    if selected_indices.is_empty() {
+    if targets.is_empty() {
         return Err(Error::other("No projects selected"));
     }
 
-    let total = selected_indices.len();
+    let total = targets.len();
     let progress_sender = app_state.start_progress(format!("Stopping projects... (0/{})", total));
 
     std::thread::spawn(move || {
-        for (idx, &project_idx) in selected_indices.iter().enumerate() {
-            if let Some(project) = projects.get(project_idx) {
-                let percentage = if total > 0 {
-                    (idx as f32 / total as f32) * 100.0
-                } else {
-                    0.0
-                };
-                let _ = progress_sender.send(ProgressUpdate::Update {
-                    message: format!("Stopping {} ({}/{})", project.project_name, idx + 1, total),
-                    percentage,
-                });
-
-                stop_project_sync(project);
-            }
+        for (idx, (name, containers)) in targets.iter().enumerate() {
+            let percentage = if total > 0 {
+                (idx as f32 / total as f32) * 100.0
+            } else {
+                0.0
+            };
+            let _ = progress_sender.send(ProgressUpdate::Update {
+                message: format!("Stopping {} ({}/{})", name, idx + 1, total),
+                percentage,
+            });
+
+            stop_project_sync(containers);
         }
 
         let _ = progress_sender.send(ProgressUpdate::Complete);
@@ -115,21 +116,19 @@ pub fn stop_selected_compose_projects(
 }
 
 fn restart_project(
-    project: &ComposeProject,
+    name: &str,
+    containers: Vec<String>,
     app_state: &mut AppState,
     config: &Config,
 ) -> Result<(), Error> {
-    let total = project.containers.len();
-    let progress_sender = app_state.start_progress(format!(
-        "Restarting project {} (0/{})",
-        project.project_name, total
-    ));
+    let total = containers.len();
+    let progress_sender =
+        app_state.start_progress(format!("Restarting project {} (0/{})", name, total));
 
-    let project_clone = project.clone();
     let show_progress = config.general.experimental_fx;
 
     std::thread::spawn(move || {
-        for (idx, container_name) in project_clone.containers.iter().enumerate() {
+        for (idx, container_name) in containers.iter().enumerate() {
             if show_progress {
                 let percentage = if total > 0 {
                     (idx as f32 / total as f32) * 100.0
@@ -154,21 +153,19 @@ fn restart_project(
 }
 
 fn stop_project(
-    project: &ComposeProject,
+    name: &str,
+    containers: Vec<String>,
     app_state: &mut AppState,
     config: &Config,
 ) -> Result<(), Error> {
-    let total = project.containers.len();
-    let progress_sender = app_state.start_progress(format!(
-        "Stopping project {} (0/{})",
-        project.project_name, total
-    ));
+    let total = containers.len();
+    let progress_sender =
+        app_state.start_progress(format!("Stopping project {} (0/{})", name, total));
 
-    let project_clone = project.clone();
     let show_progress = config.general.experimental_fx;
 
     std::thread::spawn(move || {
-        for (idx, container_name) in project_clone.containers.iter().enumerate() {
+        for (idx, container_name) in containers.iter().enumerate() {
             if show_progress {
                 let percentage = if total > 0 {
                     (idx as f32 / total as f32) * 100.0
@@ -193,16 +190,16 @@ fn stop_project(
 }
 
 // Synchronous versions for use in threads
-fn restart_project_sync(project: &ComposeProject) {
-    for container_name in &project.containers {
+fn restart_project_sync(containers: &[String]) {
+    for container_name in containers {
         let _ = Command::new("docker")
             .args(["restart", container_name])
             .output();
     }
 }
 
-fn stop_project_sync(project: &ComposeProject) {
-    for container_name in &project.containers {
+fn stop_project_sync(containers: &[String]) {
+    for container_name in containers {
         let _ = Command::new("docker")
             .args(["stop", container_name])
             .output();
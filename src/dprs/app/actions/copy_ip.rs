@@ -1,10 +1,9 @@
 //  Implements clipboard functionality for Docker container IP addresses.
 //  This module contains a function to copy the selected container's IP address
 //  to the system clipboard, allowing users to easily use container IPs in other applications.
+//  With containers marked, the addresses of all of them are copied instead.
 
-use copypasta_ext::prelude::*;
-use copypasta_ext::x11_bin::ClipboardContext;
-
+use crate::dprs::app::actions::clipboard::copy_to_clipboard;
 use crate::dprs::app::state_machine::AppState;
 
 pub fn copy_ip_address(app_state: &AppState) -> Result<String, String> {
@@ -22,14 +21,30 @@ pub fn copy_ip_address(app_state: &AppState) -> Result<String, String> {
     // IP addresses can be concatenated without separators when multiple networks exist
     let first_ip = extract_first_ip(&container.ip_address);
 
-    let mut ctx: ClipboardContext = ClipboardContext::new().unwrap();
-
-    ctx.set_contents(first_ip.to_owned())
-        .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
+    copy_to_clipboard(&first_ip)?;
 
     Ok(first_ip)
 }
 
+/// Copies the first IP address of each marked container, one per line.
+/// Returns how many addresses were copied.
+pub fn copy_marked_ip_addresses(app_state: &AppState) -> Result<usize, String> {
+    let ips: Vec<String> = app_state
+        .marked_containers()
+        .iter()
+        .map(|c| extract_first_ip(&c.ip_address))
+        .filter(|ip| !ip.is_empty())
+        .collect();
+
+    if ips.is_empty() {
+        return Err("No marked container has an IP address".to_string());
+    }
+
+    copy_to_clipboard(&ips.join("\n"))?;
+
+    Ok(ips.len())
+}
+
 /// Extracts the first IP address from a string that may contain multiple IPs
 /// IP addresses may be separated by whitespace, commas, or concatenated directly
 fn extract_first_ip(ip_string: &str) -> String {
@@ -25,6 +25,7 @@ fn test_copy_ip_success() {
         started_at: "2025-01-01T00:00:00Z".to_string(),
         compose_project: None,
         labels: std::collections::HashMap::new(),
+        networks: Vec::new(),
     }];
 
     // Select the container
@@ -57,6 +58,7 @@ fn test_copy_ip_no_selection() {
         started_at: "2025-01-01T00:00:00Z".to_string(),
         compose_project: None,
         labels: std::collections::HashMap::new(),
+        networks: Vec::new(),
     }];
 
     // Clear selection
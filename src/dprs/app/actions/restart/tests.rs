@@ -25,6 +25,7 @@ fn test_restart_container_success() {
         started_at: "2025-01-01T00:00:00Z".to_string(),
         compose_project: None,
         labels: std::collections::HashMap::new(),
+        networks: Vec::new(),
     }];
     app_state.list_state.select(Some(0));
 
@@ -66,6 +67,7 @@ fn test_restart_container_no_selection() {
         started_at: "2025-01-01T00:00:00Z".to_string(),
         compose_project: None,
         labels: std::collections::HashMap::new(),
+        networks: Vec::new(),
     }];
 
     // Ensure no container is selected.
@@ -0,0 +1,106 @@
+// Runs shell commands for context menu actions.
+// A background command runs with its output captured: stdout and stderr are
+// read line by line on their own threads and handed to the UI through a
+// channel, followed by the exit status once both streams close. A
+// foreground command inherits the terminal, for interactive programs such as
+// a shell inside a container; the caller suspends the TUI around it.
+
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellEvent {
+    Output(String),
+    /// The exit code, or None if the command was killed by a signal.
+    Exited(Option<i32>),
+}
+
+pub struct ShellCommand {
+    receiver: Receiver<ShellEvent>,
+}
+
+impl ShellCommand {
+    /// Starts `command` with `sh -c` in the background.
+    pub fn spawn(command: &str) -> Result<Self, String> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Could not run command: {}", e))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let readers = [
+            child
+                .stdout
+                .take()
+                .map(|out| forward_lines(out, sender.clone())),
+            child
+                .stderr
+                .take()
+                .map(|err| forward_lines(err, sender.clone())),
+        ];
+        thread::spawn(move || {
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.join();
+            }
+            let code = child.wait().ok().and_then(|status| status.code());
+            let _ = sender.send(ShellEvent::Exited(code));
+        });
+
+        Ok(Self { receiver })
+    }
+
+    /// Output and exit events received since the last call.
+    pub fn events(&self) -> Vec<ShellEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+fn forward_lines(
+    stream: impl Read + Send + 'static,
+    sender: Sender<ShellEvent>,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(ShellEvent::Output(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Runs `command` with `sh -c` attached to the terminal and waits for it.
+pub fn run_foreground(command: &str) -> Result<Option<i32>, String> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .status()
+        .map_err(|e| format!("Could not run command: {}", e))?;
+    Ok(status.code())
+}
+
+/// Quotes a value for a POSIX shell, so it is passed as one word whatever
+/// characters it holds.
+pub fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c))
+    {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
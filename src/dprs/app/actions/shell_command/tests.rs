@@ -0,0 +1,59 @@
+// The tests module for shell commands checks quoting and that background
+// output and the exit status are collected.
+
+use super::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_shell_quote() {
+    assert_eq!(shell_quote("web-1"), "web-1");
+    assert_eq!(
+        shell_quote("/srv/app/docker-compose.yml"),
+        "/srv/app/docker-compose.yml"
+    );
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote("a b"), "'a b'");
+    assert_eq!(shell_quote("x; rm -rf /"), "'x; rm -rf /'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(shell_quote("$(id)"), "'$(id)'");
+}
+
+#[test]
+fn test_background_output_and_status() {
+    let command = ShellCommand::spawn("echo out; echo err >&2; exit 3").unwrap();
+
+    let mut events = Vec::new();
+    let started = Instant::now();
+    while !events.iter().any(|e| matches!(e, ShellEvent::Exited(_))) {
+        assert!(started.elapsed() < Duration::from_secs(5), "{:?}", events);
+        events.extend(command.events());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    assert!(events.contains(&ShellEvent::Output("out".to_string())));
+    assert!(events.contains(&ShellEvent::Output("err".to_string())));
+    assert_eq!(events.last(), Some(&ShellEvent::Exited(Some(3))));
+}
+
+#[test]
+fn test_quoted_value_is_one_word() {
+    let command = format!("printf '%s\\n' {}", shell_quote("a; echo injected"));
+    let command = ShellCommand::spawn(&command).unwrap();
+
+    let mut events = Vec::new();
+    let started = Instant::now();
+    while !events.iter().any(|e| matches!(e, ShellEvent::Exited(_))) {
+        assert!(started.elapsed() < Duration::from_secs(5));
+        events.extend(command.events());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(
+        events,
+        vec![
+            ShellEvent::Output("a; echo injected".to_string()),
+            ShellEvent::Exited(Some(0)),
+        ]
+    );
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
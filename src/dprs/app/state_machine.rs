@@ -6,8 +6,20 @@
 // lists, select containers, and refresh container data by querying the
 // Docker CLI. This serves as the central data model for the application.
 
+use crate::dprs::app::actions::{ShellCommand, ShellEvent};
+use crate::dprs::commands::completion::CompletionState;
+use crate::dprs::display::compose_view::{
+    compose_rows, group_containers_by_project, ComposeProject,
+};
+use crate::dprs::display::confirm_dialog::ConfirmDialogState;
 use crate::dprs::display::context_menu::ContextMenuState;
+use crate::dprs::display::json_viewer::JsonViewerState;
+use crate::dprs::display::log_pane::LogPaneState;
+use crate::dprs::display::output_pager::OutputPagerState;
+use crate::dprs::modes::history::{History, HistoryFile};
+use crate::dprs::modes::keymap::KeySequence;
 use crate::dprs::modes::{CommandState, Mode, SearchState, VisualSelection};
+use crate::shared::config::Config;
 use ratatui::widgets::{ListState, TableState};
 use std::collections::{HashMap, HashSet};
 use std::io::Error;
@@ -15,7 +27,7 @@ use std::process::Command;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Container {
     pub name: String,
     pub image: String,
@@ -29,6 +41,7 @@ pub struct Container {
     pub started_at: String,
     pub compose_project: Option<String>,
     pub labels: HashMap<String, String>,
+    pub networks: Vec<String>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -46,6 +59,24 @@ impl ContainerFilter {
             ContainerFilter::All => "ALL",
         }
     }
+
+    /// Lowercase name used in the config file and by `:set filter`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContainerFilter::Running => "running",
+            ContainerFilter::Recent => "recent",
+            ContainerFilter::All => "all",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "running" => Some(ContainerFilter::Running),
+            "recent" => Some(ContainerFilter::Recent),
+            "all" => Some(ContainerFilter::All),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -55,6 +86,9 @@ pub struct ProgressModal {
     pub active: bool,
 }
 
+/// Actions that keys can be bound to. The variant names are the action
+/// names used in the `[keybindings]` tables of the config.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AppEvent {
     SelectNext,
     SelectPrevious,
@@ -65,14 +99,16 @@ pub enum AppEvent {
     RestartContainer,
     Quit,
     EnterFilterMode,
-    ExitFilterMode,
     ClearFilter,
+    ToggleRecent,
+    ToggleAll,
 
     // Modal Events
     EnterNormalMode,
     EnterVisualMode,
     EnterCommandMode,
-    EnterSearchMode,
+    EnterSearchForward,
+    EnterSearchBackward,
 
     // Vim-style Navigation
     GoToFirst,
@@ -85,23 +121,107 @@ pub enum AppEvent {
     PreviousSearchResult,
 
     // Visual Mode
-    ExtendSelection,
+    ExtendSelectionNext,
+    ExtendSelectionPrevious,
     StopSelectedContainers,
     RestartSelectedContainers,
+    MarkSelection,
+
+    // Marks
+    ToggleMark,
+    MarkSearchMatches,
+    InvertMarks,
 
     // Additional actions
     ToggleTabular,
+    ToggleComposeView,
+
+    // Log pane
+    ToggleLogPane,
+    MaximizeLogPane,
+    ScrollLogPaneDown,
+    ScrollLogPaneUp,
+    FollowLogPane,
 
     // Command Mode
     ExecuteCommand,
     CancelCommand,
+    TabComplete,
 
     // Context Menu
     OpenContextMenu,
-    CloseContextMenu,
-    ContextMenuNext,
-    ContextMenuPrevious,
-    ContextMenuExecute,
+}
+
+impl AppEvent {
+    /// Looks up an event by its action name, as written in the config.
+    pub fn from_action(action: &str) -> Option<AppEvent> {
+        let event = match action {
+            "SelectNext" => AppEvent::SelectNext,
+            "SelectPrevious" => AppEvent::SelectPrevious,
+            "Refresh" => AppEvent::Refresh,
+            "CopyIp" => AppEvent::CopyIp,
+            "OpenBrowser" => AppEvent::OpenBrowser,
+            "StopContainer" => AppEvent::StopContainer,
+            "RestartContainer" => AppEvent::RestartContainer,
+            "Quit" => AppEvent::Quit,
+            "EnterFilterMode" => AppEvent::EnterFilterMode,
+            "ClearFilter" => AppEvent::ClearFilter,
+            "ToggleRecent" => AppEvent::ToggleRecent,
+            "ToggleAll" => AppEvent::ToggleAll,
+            "EnterNormalMode" => AppEvent::EnterNormalMode,
+            "EnterVisualMode" => AppEvent::EnterVisualMode,
+            "EnterCommandMode" => AppEvent::EnterCommandMode,
+            "EnterSearchForward" => AppEvent::EnterSearchForward,
+            "EnterSearchBackward" => AppEvent::EnterSearchBackward,
+            "GoToFirst" => AppEvent::GoToFirst,
+            "GoToLast" => AppEvent::GoToLast,
+            "WordNext" => AppEvent::WordNext,
+            "WordPrevious" => AppEvent::WordPrevious,
+            "HalfPageUp" => AppEvent::HalfPageUp,
+            "HalfPageDown" => AppEvent::HalfPageDown,
+            "NextSearchResult" => AppEvent::NextSearchResult,
+            "PreviousSearchResult" => AppEvent::PreviousSearchResult,
+            "ExtendSelectionNext" => AppEvent::ExtendSelectionNext,
+            "ExtendSelectionPrevious" => AppEvent::ExtendSelectionPrevious,
+            "StopSelectedContainers" => AppEvent::StopSelectedContainers,
+            "RestartSelectedContainers" => AppEvent::RestartSelectedContainers,
+            "MarkSelection" => AppEvent::MarkSelection,
+            "ToggleMark" => AppEvent::ToggleMark,
+            "MarkSearchMatches" => AppEvent::MarkSearchMatches,
+            "InvertMarks" => AppEvent::InvertMarks,
+            "ToggleTabular" => AppEvent::ToggleTabular,
+            "ToggleComposeView" => AppEvent::ToggleComposeView,
+            "ToggleLogPane" => AppEvent::ToggleLogPane,
+            "MaximizeLogPane" => AppEvent::MaximizeLogPane,
+            "ScrollLogPaneDown" => AppEvent::ScrollLogPaneDown,
+            "ScrollLogPaneUp" => AppEvent::ScrollLogPaneUp,
+            "FollowLogPane" => AppEvent::FollowLogPane,
+            "ExecuteCommand" => AppEvent::ExecuteCommand,
+            "CancelCommand" => AppEvent::CancelCommand,
+            "TabComplete" => AppEvent::TabComplete,
+            "OpenContextMenu" => AppEvent::OpenContextMenu,
+            _ => return None,
+        };
+        Some(event)
+    }
+
+    /// Whether the event only moves the selection, which is all visual mode
+    /// takes from the normal mode bindings.
+    pub fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            AppEvent::SelectNext
+                | AppEvent::SelectPrevious
+                | AppEvent::ExtendSelectionNext
+                | AppEvent::ExtendSelectionPrevious
+                | AppEvent::GoToFirst
+                | AppEvent::GoToLast
+                | AppEvent::WordNext
+                | AppEvent::WordPrevious
+                | AppEvent::HalfPageUp
+                | AppEvent::HalfPageDown
+        )
+    }
 }
 
 pub struct AppState {
@@ -110,6 +230,9 @@ pub struct AppState {
     pub table_state: TableState,
     pub tabular_mode: bool,
     pub compose_view_mode: bool,
+    /// The compose projects of `containers`, grouped when they are
+    /// refreshed.
+    pub compose_projects: Vec<ComposeProject>,
     pub container_filter: ContainerFilter,
     pub filter_mode: bool,
     pub filter_text: String,
@@ -118,13 +241,35 @@ pub struct AppState {
     // Modal state
     pub mode: Mode,
     pub visual_selection: Option<VisualSelection>,
+    pub last_visual_range: Option<(usize, usize)>,
+    pub pending_count: Option<usize>,
+    pub key_sequence: KeySequence,
+    // Marked containers, by name, used by batch actions
+    pub marked: HashSet<String>,
     pub command_state: CommandState,
+    pub completion: CompletionState,
     pub search_state: SearchState,
+    pub history_file: Option<HistoryFile>,
     pub last_normal_position: usize,
 
     // Context menu
     pub context_menu: ContextMenuState,
 
+    // Inspect viewer
+    pub json_viewer: JsonViewerState,
+
+    // Command output pager and its history
+    pub output_pager: OutputPagerState,
+    pub confirm_dialog: ConfirmDialogState,
+
+    // Log pane following the selected container
+    pub log_pane: LogPaneState,
+
+    // Context menu commands running in the background, and one waiting to
+    // take over the terminal
+    pub running_actions: Vec<RunningAction>,
+    pub foreground_command: Option<String>,
+
     // Progress modal
     pub progress_modal: ProgressModal,
     pub progress_receiver: Option<Receiver<ProgressUpdate>>,
@@ -140,6 +285,14 @@ pub struct AppState {
     pub stats_cache: Arc<Mutex<HashMap<String, (String, String)>>>, // container_name -> (cpu, memory)
 }
 
+/// A context menu command running in the background. Its output goes to the
+/// output pager entry `output_id`.
+pub struct RunningAction {
+    pub label: String,
+    pub output_id: usize,
+    pub command: ShellCommand,
+}
+
 #[derive(Clone)]
 pub enum ProgressUpdate {
     Update { message: String, percentage: f32 },
@@ -189,16 +342,29 @@ impl AppState {
             table_state,
             tabular_mode: false,
             compose_view_mode: false,
+            compose_projects: Vec::new(),
             container_filter: ContainerFilter::Running,
             filter_mode: false,
             filter_text: String::new(),
             filtered_containers: Vec::new(),
             mode: Mode::Normal,
             visual_selection: None,
+            last_visual_range: None,
+            pending_count: None,
+            key_sequence: KeySequence::default(),
+            marked: HashSet::new(),
             command_state: CommandState::new(),
+            completion: CompletionState::new(),
+            history_file: None,
             search_state: SearchState::new(),
             last_normal_position: 0,
             context_menu: ContextMenuState::new(),
+            json_viewer: JsonViewerState::new(),
+            output_pager: OutputPagerState::new(),
+            confirm_dialog: ConfirmDialogState::new(),
+            log_pane: LogPaneState::new(),
+            running_actions: Vec::new(),
+            foreground_command: None,
             progress_modal: ProgressModal {
                 message: String::new(),
                 percentage: 0.0,
@@ -345,6 +511,7 @@ impl AppState {
                     started_at: String::new(), // Will be filled by batch inspect
                     compose_project: None,     // Will be filled by batch inspect
                     labels: HashMap::new(),    // Will be filled by batch inspect
+                    networks: Vec::new(),      // Will be filled by batch inspect
                 });
                 if is_new {
                     self.new_container_indices.push(self.containers.len() - 1);
@@ -365,6 +532,7 @@ impl AppState {
                         container.started_at = meta.started_at.clone();
                         container.compose_project = meta.compose_project.clone();
                         container.labels = meta.labels.clone();
+                        container.networks = meta.networks.clone();
                     }
                 }
             }
@@ -377,15 +545,27 @@ impl AppState {
             });
         }
 
+        self.compose_projects = group_containers_by_project(&self.containers);
+
         // Update previous names for next refresh
         self.previous_container_names = self.containers.iter().map(|c| c.name.clone()).collect();
 
-        // Reset selection if the list is empty or the current selection is invalid
-        if self.containers.is_empty() {
+        // Marks on containers that are gone would act on nothing
+        let names = &self.previous_container_names;
+        self.marked.retain(|name| names.contains(name));
+
+        // Reset selection if the list is empty or the current selection is
+        // invalid; the compose view has a row per project and service
+        let rows = if self.compose_view_mode {
+            compose_rows(&self.compose_projects).len()
+        } else {
+            self.containers.len()
+        };
+        if rows == 0 {
             self.list_state.select(None);
             self.table_state.select(None);
         } else if self.list_state.selected().is_none()
-            || self.list_state.selected().unwrap() >= self.containers.len()
+            || self.list_state.selected().unwrap() >= rows
         {
             self.list_state.select(Some(0));
             self.table_state.select(Some(0));
@@ -463,9 +643,8 @@ impl AppState {
 
     pub fn get_displayed_container_count(&self) -> usize {
         if self.compose_view_mode {
-            // In compose view mode, count projects instead of containers
-            use crate::dprs::display::compose_view::group_containers_by_project;
-            group_containers_by_project(self).len()
+            // In compose view mode, count project and service rows instead
+            compose_rows(&self.compose_projects).len()
         } else if self.filter_text.is_empty() {
             self.containers.len()
         } else {
@@ -473,11 +652,45 @@ impl AppState {
         }
     }
 
+    /// Loads command and search history from `history_file` and keeps the
+    /// file for later saves.
+    pub fn load_history(
+        &mut self,
+        history_file: HistoryFile,
+        max_items: usize,
+    ) -> Result<(), Error> {
+        self.set_max_history(max_items);
+        let history = history_file.load(max_items);
+        self.history_file = Some(history_file);
+
+        let history = history?;
+        self.command_state.history = history.commands;
+        self.search_state.history = history.searches;
+        Ok(())
+    }
+
+    /// Writes command and search history to the history file, if one was
+    /// loaded.
+    pub fn save_history(&self) -> Result<(), Error> {
+        match &self.history_file {
+            Some(history_file) => history_file.save(&History {
+                commands: self.command_state.history.clone(),
+                searches: self.search_state.history.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
     // Modal state management
     pub fn enter_normal_mode(&mut self) {
         self.mode = Mode::Normal;
-        self.visual_selection = None;
+        if let Some(selection) = self.visual_selection.take() {
+            self.last_visual_range = Some(selection.range());
+        }
+        self.pending_count = None;
+        self.key_sequence.clear();
         self.command_state.clear();
+        self.completion.clear();
         self.search_state.clear();
         if let Some(selected) = self.list_state.selected() {
             self.last_normal_position = selected;
@@ -494,6 +707,7 @@ impl AppState {
     pub fn enter_command_mode(&mut self) {
         self.mode = Mode::Command;
         self.command_state.clear();
+        self.completion.clear();
     }
 
     pub fn enter_search_mode(&mut self, forward: bool) {
@@ -501,6 +715,32 @@ impl AppState {
         self.search_state.set_query(String::new(), forward);
     }
 
+    /// First and last line of the current or most recent visual selection,
+    /// for the `'<` and `'>` range addresses.
+    pub fn visual_range(&self) -> Option<(usize, usize)> {
+        self.visual_selection
+            .as_ref()
+            .map(|selection| selection.range())
+            .or(self.last_visual_range)
+    }
+
+    /// Adds a digit to the count typed before a normal or visual mode key.
+    /// Returns false for a leading zero, which is not a count.
+    pub fn push_count_digit(&mut self, digit: usize) -> bool {
+        match self.pending_count {
+            None if digit == 0 => false,
+            count => {
+                let count = count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                self.pending_count = Some(count.min(99_999));
+                true
+            }
+        }
+    }
+
+    pub fn take_count(&mut self) -> Option<usize> {
+        self.pending_count.take()
+    }
+
     pub fn is_in_visual_mode(&self) -> bool {
         matches!(self.mode, Mode::Visual)
     }
@@ -514,6 +754,27 @@ impl AppState {
     }
 
     // Vim-style navigation
+
+    /// Moves the selection by `delta` lines without wrapping, as counted
+    /// motions like `5j` do.
+    pub fn move_selection_by(&mut self, delta: isize) {
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        self.go_to_line((current + delta).max(0) as usize);
+    }
+
+    /// Selects a 0-based line, clamped to the list, as `3G` does.
+    pub fn go_to_line(&mut self, index: usize) {
+        let container_count = self.get_displayed_container_count();
+        if container_count > 0 {
+            let index = index.min(container_count - 1);
+            self.list_state.select(Some(index));
+            self.table_state.select(Some(index));
+            if let Some(ref mut selection) = self.visual_selection {
+                selection.extend_to(index);
+            }
+        }
+    }
+
     pub fn go_to_first(&mut self) {
         let container_count = self.get_displayed_container_count();
         if container_count > 0 {
@@ -544,7 +805,7 @@ impl AppState {
         }
 
         if let Some(current) = self.list_state.selected() {
-            let new_pos = current.saturating_sub(container_count / 2).max(0);
+            let new_pos = current.saturating_sub(container_count / 2);
             self.list_state.select(Some(new_pos));
             self.table_state.select(Some(new_pos));
             if let Some(ref mut selection) = self.visual_selection {
@@ -637,7 +898,78 @@ impl AppState {
         }
     }
 
+    pub fn is_marked(&self, container: &Container) -> bool {
+        self.marked.contains(&container.name)
+    }
+
+    /// Marks or unmarks the selected container. Returns whether it is now
+    /// marked.
+    pub fn toggle_mark(&mut self) -> bool {
+        let Some(name) = self.get_selected_container().map(|c| c.name.clone()) else {
+            return false;
+        };
+        if self.marked.remove(&name) {
+            false
+        } else {
+            self.marked.insert(name);
+            true
+        }
+    }
+
+    /// Marks every container matching the current search. Returns how many
+    /// were newly marked.
+    pub fn mark_search_matches(&mut self) -> usize {
+        let displayed = self.get_displayed_containers();
+        let before = self.marked.len();
+        for &index in &self.search_state.matches {
+            if let Some(container) = displayed.get(index) {
+                self.marked.insert(container.name.clone());
+            }
+        }
+        self.marked.len() - before
+    }
+
+    /// Flips the mark on every displayed container.
+    pub fn invert_marks(&mut self) {
+        for container in self.get_displayed_containers() {
+            if !self.marked.remove(&container.name) {
+                self.marked.insert(container.name);
+            }
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Indices of the marked containers in the displayed list.
+    pub fn marked_indices(&self) -> Vec<usize> {
+        self.get_displayed_containers()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| self.is_marked(c))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The marked containers, in list order.
+    pub fn marked_containers(&self) -> Vec<Container> {
+        self.get_displayed_containers()
+            .into_iter()
+            .filter(|c| self.is_marked(c))
+            .collect()
+    }
+
+    /// Lines a batch action applies to: the visual selection, else the
+    /// marked containers, else the selected line. Marks are containers, so
+    /// they do not apply to project rows in the compose view.
     pub fn get_selected_indices(&self) -> Vec<usize> {
+        if self.visual_selection.is_none() && !self.compose_view_mode {
+            let marked = self.marked_indices();
+            if !marked.is_empty() {
+                return marked;
+            }
+        }
         match &self.visual_selection {
             Some(selection) => {
                 let mut indices: Vec<usize> = selection.selected_indices.iter().copied().collect();
@@ -723,6 +1055,72 @@ impl AppState {
         tx
     }
 
+    /// Runs a context menu command in the background, opening the output
+    /// pager on its output.
+    pub fn start_action(&mut self, label: &str, command: &str) -> Result<(), String> {
+        let shell_command = ShellCommand::spawn(command)?;
+        let output_id = self
+            .output_pager
+            .open(format!("{}: {}", label, command), String::new());
+        self.running_actions.push(RunningAction {
+            label: label.to_string(),
+            output_id,
+            command: shell_command,
+        });
+        Ok(())
+    }
+
+    /// Moves output of background commands into the pager. Returns a
+    /// message for each command that finished.
+    pub fn update_running_actions(&mut self) -> Vec<String> {
+        let mut finished = Vec::new();
+        let pager = &mut self.output_pager;
+        self.running_actions.retain(|action| {
+            let mut lines = Vec::new();
+            let mut exit = None;
+            for event in action.command.events() {
+                match event {
+                    ShellEvent::Output(line) => lines.push(line),
+                    ShellEvent::Exited(code) => exit = Some(code),
+                }
+            }
+            let Some(code) = exit else {
+                pager.append(action.output_id, &lines);
+                return true;
+            };
+
+            let status = match code {
+                Some(code) => format!("exit status {}", code),
+                None => "killed by a signal".to_string(),
+            };
+            lines.push(String::new());
+            lines.push(format!("[{}]", status));
+            pager.append(action.output_id, &lines);
+            finished.push(match code {
+                Some(0) => format!("{} finished", action.label),
+                _ => format!("{} failed ({})", action.label, status),
+            });
+            false
+        });
+        finished
+    }
+
+    /// Points the log pane at the selected container and pulls in new lines.
+    /// Project rows in the compose view have no single container to follow.
+    pub fn update_log_pane(&mut self) {
+        if !self.log_pane.visible {
+            return;
+        }
+
+        let selected = if self.compose_view_mode {
+            None
+        } else {
+            self.get_selected_container().map(|c| c.name.clone())
+        };
+        self.log_pane.follow(selected.as_deref());
+        self.log_pane.sync();
+    }
+
     pub fn update_progress(&mut self) {
         let mut should_clear_receiver = false;
         let mut updates = Vec::new();
@@ -788,6 +1186,44 @@ impl AppState {
         }
     }
 
+    pub fn set_container_filter(&mut self, filter: ContainerFilter) {
+        if self.container_filter == filter {
+            return;
+        }
+        self.container_filter = filter;
+        self.load_containers();
+        // Reset selection
+        if !self.containers.is_empty() {
+            self.list_state.select(Some(0));
+            self.table_state.select(Some(0));
+        }
+    }
+
+    /// Applies the startup settings from the general config. The container
+    /// list is not reloaded; the caller refreshes it afterwards.
+    pub fn apply_config(&mut self, config: &Config) {
+        self.tabular_mode = config.general.tabular_mode;
+        if let Some(filter) = ContainerFilter::from_name(&config.general.container_filter) {
+            self.container_filter = filter;
+        }
+        self.set_max_history(config.general.max_history_items);
+    }
+
+    /// Caps command and search history, dropping the oldest entries.
+    pub fn set_max_history(&mut self, max_items: usize) {
+        self.command_state.max_history = max_items;
+        self.search_state.max_history = max_items;
+        for history in [
+            &mut self.command_state.history,
+            &mut self.search_state.history,
+        ] {
+            if history.len() > max_items {
+                let excess = history.len() - max_items;
+                history.drain(..excess);
+            }
+        }
+    }
+
     // Toggle between running and all, with special handling from recent
     pub fn toggle_all(&mut self) {
         self.container_filter = match self.container_filter {
@@ -840,6 +1276,14 @@ impl AppState {
 
                     let ip_address = format_ip_addresses(&ip_addresses.join(" "));
 
+                    let mut networks: Vec<String> = inspect
+                        .network_settings
+                        .as_ref()
+                        .and_then(|ns| ns.networks.as_ref())
+                        .map(|networks| networks.keys().cloned().collect())
+                        .unwrap_or_default();
+                    networks.sort();
+
                     let started_at = inspect
                         .state
                         .as_ref()
@@ -882,6 +1326,7 @@ impl AppState {
                             container_id,
                             compose_project,
                             labels,
+                            networks,
                         },
                     );
                 }
@@ -987,6 +1432,7 @@ struct ContainerMetadata {
     container_id: String,
     compose_project: Option<String>,
     labels: HashMap<String, String>,
+    networks: Vec<String>,
 }
 
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
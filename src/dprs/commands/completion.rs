@@ -0,0 +1,273 @@
+// The completion module implements tab completion for command mode. It
+// completes the word under the cursor in the command being typed, which is
+// the one after the last `;` and past any range. The first word completes
+// to command names and aliases. Arguments of container commands complete to
+// container names and to spec qualifiers such as `status:` and their
+// values. Label keys, projects, images and networks come from the current
+// container list. `:up` arguments complete to the services of the selected
+// compose project, and `:set` arguments to option names. When several
+// candidates match, a popup lists them and repeated Tab presses cycle
+// through them, substituting each one into the command line.
+
+use crate::dprs::app::state_machine::AppState;
+use crate::dprs::commands::options::{OptionKind, SET_OPTIONS};
+use crate::dprs::commands::range::parse_range;
+use crate::dprs::commands::spec::{HEALTH_VALUES, QUALIFIERS, STATUS_VALUES};
+use crate::dprs::commands::{COMMAND_NAMES, CONTAINER_COMMANDS, DRY_RUN_COMMANDS};
+use crate::dprs::display::compose_view::selected_project;
+use crate::dprs::modes::CommandState;
+use crate::shared::config::Config;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Default)]
+pub struct CompletionState {
+    pub candidates: Vec<String>,
+    pub selected: Option<usize>,
+    word_start: usize,
+    original_word: String,
+}
+
+impl CompletionState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.candidates.is_empty()
+    }
+
+    /// Byte offset in the command line of the word being completed.
+    pub fn word_start(&self) -> usize {
+        self.word_start
+    }
+
+    pub fn clear(&mut self) {
+        self.candidates.clear();
+        self.selected = None;
+        self.word_start = 0;
+        self.original_word.clear();
+    }
+
+    /// Closes the popup and puts back the word that was being completed.
+    pub fn cancel(&mut self, command_state: &mut CommandState) {
+        if self.is_active() {
+            let original = std::mem::take(&mut self.original_word);
+            replace_word(command_state, self.word_start, &original);
+        }
+        self.clear();
+    }
+
+    fn cycle(&mut self, command_state: &mut CommandState, forward: bool) {
+        let count = self.candidates.len();
+        let next = match self.selected {
+            Some(i) if forward => (i + 1) % count,
+            Some(i) => (i + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        self.selected = Some(next);
+        replace_word(command_state, self.word_start, &self.candidates[next]);
+    }
+}
+
+/// Completes the word before the cursor, or cycles to the next (or previous)
+/// candidate if a completion popup is already open. Returns false if there
+/// was nothing to complete.
+pub fn tab_complete(app_state: &mut AppState, config: &Config, forward: bool) -> bool {
+    if app_state.completion.is_active() {
+        let AppState {
+            completion,
+            command_state,
+            ..
+        } = app_state;
+        completion.cycle(command_state, forward);
+        return true;
+    }
+
+    let cursor = app_state.command_state.cursor_pos;
+    let before_cursor = app_state.command_state.input[..cursor].to_string();
+    let (word_start, candidates) = completion_candidates(&before_cursor, app_state, config);
+
+    match candidates.len() {
+        0 => false,
+        1 => {
+            let mut completed = candidates[0].clone();
+            // Keep going after a finished word, but not after a partial spec
+            // such as `label:` or `label:key=`.
+            if !completed.ends_with(':') && !completed.ends_with('=') {
+                completed.push(' ');
+            }
+            replace_word(&mut app_state.command_state, word_start, &completed);
+            true
+        }
+        _ => {
+            let completion = &mut app_state.completion;
+            completion.candidates = candidates;
+            completion.selected = None;
+            completion.word_start = word_start;
+            completion.original_word = before_cursor[word_start..].to_string();
+            completion.cycle(&mut app_state.command_state, forward);
+            true
+        }
+    }
+}
+
+/// Returns the byte offset where the word being completed starts and the
+/// sorted candidates for it.
+pub fn completion_candidates(
+    before_cursor: &str,
+    app_state: &AppState,
+    config: &Config,
+) -> (usize, Vec<String>) {
+    let command_start = command_start(before_cursor);
+    let word_start = before_cursor
+        .rfind(char::is_whitespace)
+        .map(|i| i + 1)
+        .unwrap_or(0)
+        .max(command_start);
+    let word = &before_cursor[word_start..];
+    let preceding: Vec<&str> = before_cursor[command_start..word_start]
+        .split_whitespace()
+        .collect();
+
+    let candidates: BTreeSet<String> = match preceding.first() {
+        None => COMMAND_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(config.commands.keys().cloned())
+            .filter(|name| name.starts_with(word))
+            .collect(),
+        Some(&"set") | Some(&"set!") => set_option_candidates(word),
+        // dry takes a container command and then its spec
+        Some(&"dry") if preceding.len() == 1 => DRY_RUN_COMMANDS
+            .iter()
+            .filter(|name| name.starts_with(word))
+            .map(|name| name.to_string())
+            .collect(),
+        Some(&"dry") => container_spec_candidates(word, app_state),
+        // exec takes a single container followed by the command to run
+        Some(&"exec") if preceding.len() > 1 => BTreeSet::new(),
+        Some(&"up") => selected_project(app_state)
+            .map(|project| project.services)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|service| service.name)
+            .filter(|service| service.starts_with(word))
+            .collect(),
+        Some(cmd) if CONTAINER_COMMANDS.contains(cmd) => container_spec_candidates(word, app_state),
+        Some(_) => BTreeSet::new(),
+    };
+
+    (word_start, candidates.into_iter().collect())
+}
+
+/// Where the command being typed starts: after the last `;` outside quotes
+/// and past any range such as `1,5` or `'<,'>`.
+fn command_start(line: &str) -> usize {
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (';', None) => start = i + 1,
+            _ => {}
+        }
+    }
+    let segment = line[start..].trim_start();
+    let segment_start = line.len() - segment.len();
+    match parse_range(segment) {
+        Ok((Some(_), rest)) => line.len() - rest.len(),
+        _ => segment_start,
+    }
+}
+
+fn set_option_candidates(word: &str) -> BTreeSet<String> {
+    SET_OPTIONS
+        .iter()
+        .flat_map(|option| {
+            let negated = (option.kind == OptionKind::Bool).then(|| format!("no{}", option.name));
+            std::iter::once(option.name.to_string()).chain(negated)
+        })
+        .filter(|option| option.starts_with(word))
+        .collect()
+}
+
+fn container_spec_candidates(word: &str, app_state: &AppState) -> BTreeSet<String> {
+    // Negated terms complete like plain ones
+    if let Some(negated) = word.strip_prefix('!') {
+        return container_spec_candidates(negated, app_state)
+            .into_iter()
+            .map(|candidate| format!("!{}", candidate))
+            .collect();
+    }
+
+    let containers = &app_state.containers;
+
+    if let Some(label_spec) = word.strip_prefix("label:") {
+        return match label_spec.split_once('=') {
+            Some((key, value_prefix)) => containers
+                .iter()
+                .filter_map(|c| c.labels.get(key))
+                .filter(|value| value.starts_with(value_prefix))
+                .map(|value| format!("label:{}={}", key, value))
+                .collect(),
+            None => containers
+                .iter()
+                .flat_map(|c| c.labels.keys())
+                .filter(|key| key.starts_with(label_spec))
+                .map(|key| format!("label:{}", key))
+                .collect(),
+        };
+    }
+
+    if let Some((qualifier, prefix)) = word.split_once(':') {
+        let values: Vec<String> = match qualifier {
+            "project" => containers
+                .iter()
+                .filter_map(|c| c.compose_project.clone())
+                .collect(),
+            "name" => containers.iter().map(|c| c.name.clone()).collect(),
+            "image" => containers.iter().map(|c| c.image.clone()).collect(),
+            "network" => containers
+                .iter()
+                .flat_map(|c| c.networks.iter().cloned())
+                .collect(),
+            "status" => STATUS_VALUES.iter().map(|v| v.to_string()).collect(),
+            "health" => HEALTH_VALUES.iter().map(|v| v.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        return values
+            .into_iter()
+            .filter(|value| value.starts_with(prefix))
+            .map(|value| format!("{}:{}", qualifier, value))
+            .collect();
+    }
+
+    let mut candidates: BTreeSet<String> = containers
+        .iter()
+        .map(|c| c.name.clone())
+        .filter(|name| name.starts_with(word))
+        .collect();
+    if !word.is_empty() {
+        for qualifier in QUALIFIERS {
+            let prefix = format!("{}:", qualifier);
+            if prefix.starts_with(word) {
+                candidates.insert(prefix);
+            }
+        }
+    }
+    candidates
+}
+
+fn replace_word(command_state: &mut CommandState, word_start: usize, text: &str) {
+    let cursor = command_state.cursor_pos;
+    command_state.input.replace_range(word_start..cursor, text);
+    command_state.cursor_pos = word_start + text.len();
+    command_state.history_index = None;
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
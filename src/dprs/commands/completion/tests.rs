@@ -0,0 +1,195 @@
+// The tests module for command completion validates candidates for command
+// names, container specs and `:set` options, that the command after the last
+// `;` and past a range is completed, and cycling through them with the popup
+// open.
+
+use super::*;
+use crate::dprs::app::state_machine::Container;
+use std::collections::HashMap;
+
+fn create_test_container(
+    name: &str,
+    project: Option<&str>,
+    labels: Vec<(&str, &str)>,
+) -> Container {
+    Container {
+        name: name.to_string(),
+        image: "nginx".to_string(),
+        status: "Up".to_string(),
+        ip_address: String::new(),
+        ports: String::new(),
+        cpu_usage: String::new(),
+        memory_usage: String::new(),
+        image_hash: String::new(),
+        container_id: "abc123".to_string(),
+        started_at: String::new(),
+        compose_project: project.map(|p| p.to_string()),
+        labels: labels
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+        networks: Vec::new(),
+    }
+}
+
+fn test_app_state() -> AppState {
+    let mut app_state = AppState::new();
+    app_state.containers = vec![
+        create_test_container("web-1", Some("shop"), vec![("role", "frontend")]),
+        create_test_container("web-2", Some("shop"), vec![("role", "frontend")]),
+        create_test_container(
+            "db-1",
+            Some("billing"),
+            vec![("role", "db"), ("env", "shared")],
+        ),
+    ];
+    app_state
+}
+
+fn type_command(app_state: &mut AppState, input: &str) {
+    app_state.enter_command_mode();
+    app_state.command_state.input = input.to_string();
+    app_state.command_state.cursor_pos = input.len();
+}
+
+#[test]
+fn test_command_name_candidates() {
+    let app_state = test_app_state();
+    let (start, candidates) = completion_candidates("re", &app_state, &Config::default());
+    assert_eq!(start, 0);
+    assert_eq!(candidates, vec!["refresh", "reload", "remove", "restart"]);
+
+    let mut config = Config::default();
+    config
+        .commands
+        .insert("reset-db".to_string(), "stop $1; rm $1".to_string());
+    let (_, candidates) = completion_candidates("rese", &app_state, &config);
+    assert_eq!(candidates, vec!["reset-db"]);
+}
+
+#[test]
+fn test_container_candidates() {
+    let app_state = test_app_state();
+    let (start, candidates) = completion_candidates("stop web-1 w", &app_state, &Config::default());
+    assert_eq!(start, 11);
+    assert_eq!(candidates, vec!["web-1", "web-2"]);
+
+    let (_, candidates) = completion_candidates("logs l", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["label:"]);
+
+    // exec completes only its container argument
+    let (_, candidates) = completion_candidates("exec web-1 w", &app_state, &Config::default());
+    assert!(candidates.is_empty());
+}
+
+#[test]
+fn test_chained_and_ranged_commands() {
+    let app_state = test_app_state();
+    let config = Config::default();
+
+    let (start, candidates) = completion_candidates("stop web-1; rm w", &app_state, &config);
+    assert_eq!(start, 15);
+    assert_eq!(candidates, vec!["web-1", "web-2"]);
+
+    let (start, candidates) = completion_candidates("stop web-1;re", &app_state, &config);
+    assert_eq!(start, 11);
+    assert_eq!(candidates, vec!["refresh", "reload", "remove", "restart"]);
+
+    let (start, candidates) = completion_candidates("1,5rest", &app_state, &config);
+    assert_eq!(start, 3);
+    assert_eq!(candidates, vec!["restart"]);
+
+    let (start, candidates) = completion_candidates("'<,'>st", &app_state, &config);
+    assert_eq!(start, 5);
+    assert_eq!(candidates, vec!["start", "stop"]);
+
+    // Separators inside quotes do not start a command
+    let (_, candidates) = completion_candidates("exec web-1 'a; b' w", &app_state, &config);
+    assert!(candidates.is_empty());
+}
+
+#[test]
+fn test_label_and_project_candidates() {
+    let app_state = test_app_state();
+    let (_, candidates) = completion_candidates("stop label:", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["label:env", "label:role"]);
+
+    let (_, candidates) = completion_candidates("stop label:role=", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["label:role=db", "label:role=frontend"]);
+
+    let (_, candidates) = completion_candidates("restart project:", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["project:billing", "project:shop"]);
+}
+
+#[test]
+fn test_qualifier_candidates() {
+    let app_state = test_app_state();
+    let (_, candidates) = completion_candidates("stop status:ex", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["status:exited"]);
+
+    let (_, candidates) = completion_candidates("stop !project:s", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["!project:shop"]);
+
+    let (_, candidates) = completion_candidates("stop n", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["name:", "network:"]);
+}
+
+#[test]
+fn test_set_option_candidates() {
+    let app_state = test_app_state();
+    let (_, candidates) = completion_candidates("set no", &app_state, &Config::default());
+    assert_eq!(
+        candidates,
+        vec!["noborders", "nofx", "noheaders", "notabular"]
+    );
+
+    let (_, candidates) = completion_candidates("set ta", &app_state, &Config::default());
+    assert_eq!(candidates, vec!["tabular"]);
+}
+
+#[test]
+fn test_single_candidate_is_inserted() {
+    let mut app_state = test_app_state();
+    type_command(&mut app_state, "insp");
+    assert!(tab_complete(&mut app_state, &Config::default(), true));
+    assert_eq!(app_state.command_state.input, "inspect ");
+    assert!(!app_state.completion.is_active());
+
+    type_command(&mut app_state, "stop proj");
+    tab_complete(&mut app_state, &Config::default(), true);
+    assert_eq!(app_state.command_state.input, "stop project:");
+
+    type_command(&mut app_state, "zzz");
+    assert!(!tab_complete(&mut app_state, &Config::default(), true));
+}
+
+#[test]
+fn test_cycling_and_cancel() {
+    let mut app_state = test_app_state();
+    type_command(&mut app_state, "stop web");
+
+    tab_complete(&mut app_state, &Config::default(), true);
+    assert!(app_state.completion.is_active());
+    assert_eq!(app_state.command_state.input, "stop web-1");
+
+    tab_complete(&mut app_state, &Config::default(), true);
+    assert_eq!(app_state.command_state.input, "stop web-2");
+
+    tab_complete(&mut app_state, &Config::default(), true);
+    assert_eq!(app_state.command_state.input, "stop web-1");
+
+    tab_complete(&mut app_state, &Config::default(), false);
+    assert_eq!(app_state.command_state.input, "stop web-2");
+
+    let AppState {
+        completion,
+        command_state,
+        ..
+    } = &mut app_state;
+    completion.cancel(command_state);
+    assert_eq!(app_state.command_state.input, "stop web");
+    assert_eq!(app_state.command_state.cursor_pos, 8);
+    assert!(!app_state.completion.is_active());
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
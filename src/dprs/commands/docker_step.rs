@@ -0,0 +1,84 @@
+// The docker_step module runs the docker operations of one step of a
+// command chain on a worker thread. The worker waits for every process and
+// checks its exit status, so a chain only moves on to its next step once
+// the current one has succeeded, and stops with Docker's own error message
+// when it has not.
+
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// `<program> <operation> <name>` for each of a step's containers, waited
+/// for on a worker thread.
+pub struct DockerStep {
+    receiver: Receiver<Result<(), String>>,
+}
+
+impl DockerStep {
+    /// Starts the step. `program` is `docker` outside of tests.
+    pub fn spawn(program: &str, operation: &str, names: &[String]) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let program = program.to_string();
+        let operation = operation.to_string();
+        let names = names.to_vec();
+        thread::spawn(move || {
+            let _ = sender.send(run_step(&program, &operation, &names));
+        });
+        Self { receiver }
+    }
+
+    /// The outcome of the step once every process has exited, or None while
+    /// any is still running.
+    pub fn try_finish(&self) -> Option<Result<(), String>> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("The docker worker stopped".to_string())),
+        }
+    }
+}
+
+fn run_step(program: &str, operation: &str, names: &[String]) -> Result<(), String> {
+    // Every process starts before any is waited for, so a step over many
+    // containers takes as long as its slowest one rather than their sum.
+    // stdout is discarded and stderr kept for the error message; neither
+    // may reach the terminal the TUI owns.
+    let children: Vec<_> = names
+        .iter()
+        .map(|name| {
+            let child = Command::new(program)
+                .args([operation, name])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn();
+            (name, child)
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    for (name, child) in children {
+        match child.and_then(|child| child.wait_with_output()) {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => errors.push(format!("{} {}: {}", operation, name, failure(&output))),
+            Err(e) => errors.push(format!("Failed to execute docker {}: {}", operation, e)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Docker's error message, or the exit status if it printed none.
+fn failure(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.trim() {
+        "" => format!("exited with {}", output.status),
+        message => message.to_string(),
+    }
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -2,24 +2,118 @@
 // for executing Docker operations and navigation commands. It supports
 // various container operations like stop, restart, logs, exec, and inspect,
 // with flexible container specification including regex patterns,
-// wildcards, and container ID matching.
-
+// wildcards, container ID matching and the qualifiers of the spec module.
+// Commands can be chained with `;`. Aliases from the `[commands]` config
+// table expand into such chains, with `$1`..`$9` and `$@` standing for the
+// alias arguments. A chain waits for the docker operations of each step
+// and stops at the first one that fails. Destructive operations return
+// `Confirm` when the `[confirm]` policy asks for it. `:dry <command>`
+// previews what a command would act on. Commands may start with an ex
+// range such as `1,5` or `'<,'>` to act on lines of the container list.
+// `:up` brings up services of a compose project, including ones that were
+// never started.
+
+use crate::dprs::app::actions::shell_quote;
 use crate::dprs::app::state_machine::{AppState, Container};
-use regex::Regex;
+use crate::dprs::commands::docker_step::DockerStep;
+use crate::dprs::commands::options::{
+    find_option, option_value, set_option, sync_runtime_options, OptionKind, SetOption, SET_OPTIONS,
+};
+use crate::dprs::commands::range::{parse_range, LineRange, RangeContext};
+use crate::dprs::commands::spec::{parse_spec, SpecError};
+use crate::dprs::display::compose_view::selected_project;
+use crate::shared::config::Config;
+use std::io::Read;
 use std::process::{Command, Stdio};
 use std::thread;
 
+/// Every command name `execute` understands, used for tab completion.
+pub const COMMAND_NAMES: &[&str] = &[
+    "build", "config", "dry", "exec", "inspect", "kill", "logs", "output", "outputs", "pause",
+    "ps", "q", "quit", "refresh", "reload", "remove", "restart", "rm", "set", "start", "stop",
+    "unpause", "up",
+];
+
+/// Commands whose arguments are container specs.
+pub const CONTAINER_COMMANDS: &[&str] = &[
+    "build", "exec", "inspect", "kill", "logs", "pause", "remove", "restart", "rm", "start",
+    "stop", "unpause",
+];
+
 #[derive(Debug, Clone)]
 pub enum CommandResult {
     Success(String),
     Error(String),
     Navigation(usize),
+    Output {
+        title: String,
+        content: String,
+    },
+    Inspect {
+        container_name: String,
+        data: serde_json::Value,
+    },
+    /// A malformed container spec; `position` is the byte offset in the
+    /// command line of the token the message refers to.
+    ParseError {
+        message: String,
+        position: usize,
+    },
+    /// A destructive operation waiting for the user to confirm it. `then`
+    /// is the rest of the command chain, run after the operation.
+    Confirm {
+        operation: String,
+        containers: Vec<String>,
+        then: String,
+    },
+    /// A chain step whose docker operations are still running. The rest of
+    /// the chain runs from `CommandExecutor::resume` once they succeed.
+    Running(String),
     Quit,
     ConfigReload(Box<crate::shared::config::Config>),
 }
 
+/// Commands `:dry` can preview.
+pub const DRY_RUN_COMMANDS: &[&str] = &[
+    "kill", "pause", "remove", "restart", "rm", "start", "stop", "unpause",
+];
+
+/// Operations that go through the `[confirm]` policy.
+pub const CONFIRMED_OPERATIONS: &[&str] = &["stop", "kill", "rm"];
+
+/// Whether running `operation` on `targets` needs confirmation first.
+pub fn needs_confirmation(operation: &str, targets: &[Container], config: &Config) -> bool {
+    let labels: Vec<_> = targets.iter().map(|c| &c.labels).collect();
+    CONFIRMED_OPERATIONS.contains(&operation) && config.confirm.requires_confirmation(&labels)
+}
+
+// Aliases may refer to other aliases; this bounds the expansion so a
+// recursive alias fails instead of overflowing the stack.
+const MAX_ALIAS_DEPTH: usize = 10;
+
+// Command history is kept by `CommandState`, which persists it across
+// sessions; the executor only holds a chain waiting on its docker step.
 pub struct CommandExecutor {
-    command_history: Vec<String>,
+    /// The program container operations run, `docker` outside of tests.
+    docker: String,
+    /// Set while the steps of a chain or alias run, so their docker
+    /// operations are waited for instead of dispatched.
+    in_chain: bool,
+    /// The docker operations started by the step that just ran.
+    started: Option<DockerStep>,
+    pending: Option<PendingChain>,
+}
+
+/// A chain waiting for the docker operations of one of its steps.
+struct PendingChain {
+    /// The command line the chain came from.
+    line: String,
+    step: String,
+    docker: DockerStep,
+    /// The steps left to run, `;`-separated.
+    then: String,
+    messages: Vec<String>,
+    depth: usize,
 }
 
 impl Default for CommandExecutor {
@@ -31,38 +125,248 @@ impl Default for CommandExecutor {
 impl CommandExecutor {
     pub fn new() -> Self {
         Self {
-            command_history: Vec::new(),
+            docker: "docker".to_string(),
+            in_chain: false,
+            started: None,
+            pending: None,
+        }
+    }
+
+    /// Executes a command line, which may be a `;`-separated chain of
+    /// commands and may use aliases defined in `config.commands`.
+    pub fn execute(
+        &mut self,
+        command: &str,
+        app_state: &mut AppState,
+        config: &mut Config,
+    ) -> CommandResult {
+        let result = self.execute_chain(command, app_state, config, 0);
+        if let (CommandResult::Running(_), Some(pending)) = (&result, &mut self.pending) {
+            pending.line = command.to_string();
+        }
+        result
+    }
+
+    /// Continues the chain waiting on its docker step once that step has
+    /// finished: the next steps run if every operation succeeded, otherwise
+    /// the chain stops with Docker's error. Returns the chain's command
+    /// line with the result, or None while nothing is ready.
+    pub fn resume(
+        &mut self,
+        app_state: &mut AppState,
+        config: &mut Config,
+    ) -> Option<(String, CommandResult)> {
+        let outcome = self.pending.as_ref()?.docker.try_finish()?;
+        let PendingChain {
+            line,
+            step,
+            then,
+            mut messages,
+            depth,
+            ..
+        } = self.pending.take()?;
+
+        let result = match outcome {
+            Err(e) if then.is_empty() => {
+                CommandResult::Error(format!("Stopped at {}: {}", step, e))
+            }
+            Err(e) => {
+                CommandResult::Error(format!("Stopped at {}: {} (skipped: {})", step, e, then))
+            }
+            Ok(()) if then.is_empty() => CommandResult::Success(messages.join("\n")),
+            Ok(()) => {
+                let in_chain = std::mem::replace(&mut self.in_chain, true);
+                let result = self.execute_chain(&then, app_state, config, depth);
+                self.in_chain = in_chain;
+                match result {
+                    CommandResult::Success(msg) => {
+                        messages.push(msg);
+                        CommandResult::Success(messages.join("\n"))
+                    }
+                    CommandResult::Running(msg) => {
+                        if let Some(next) = &mut self.pending {
+                            next.line = line.clone();
+                            messages.append(&mut next.messages);
+                            next.messages = messages;
+                        }
+                        CommandResult::Running(msg)
+                    }
+                    // Positions in the rest of the chain mean nothing now
+                    CommandResult::ParseError { message, .. } => CommandResult::Error(message),
+                    other => other,
+                }
+            }
+        };
+        Some((line, result))
+    }
+
+    /// Runs an operation the user confirmed. If it came from a chain, the
+    /// rest of the chain in `then` runs once the operation has succeeded.
+    pub fn run_confirmed(
+        &mut self,
+        operation: &str,
+        names: &[String],
+        then: &str,
+    ) -> CommandResult {
+        if then.is_empty() {
+            return self.run_container_operation(operation, names);
+        }
+
+        let in_chain = std::mem::replace(&mut self.in_chain, true);
+        let result = self.run_container_operation(operation, names);
+        self.in_chain = in_chain;
+        match (result, self.started.take()) {
+            (CommandResult::Success(msg), Some(docker)) => {
+                let step = format!("{} {}", operation, names.join(" "));
+                self.pending = Some(PendingChain {
+                    line: then.to_string(),
+                    step: step.clone(),
+                    docker,
+                    then: then.to_string(),
+                    messages: vec![msg],
+                    depth: 0,
+                });
+                CommandResult::Running(format!("Running {}", step))
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Runs the commands of a chain in order, stopping at the first error.
+    /// A step that starts docker operations returns `Running`, leaving the
+    /// rest of the chain to `resume`. Success messages are collected; any
+    /// other result (output to page, navigation, ...) from the chain is
+    /// returned in their place.
+    fn execute_chain(
+        &mut self,
+        command: &str,
+        app_state: &mut AppState,
+        config: &mut Config,
+        depth: usize,
+    ) -> CommandResult {
+        let steps = split_command_chain(command);
+        // A lone command, typed rather than reached through an alias or the
+        // rest of a chain, dispatches its docker operations without waiting
+        if steps.len() <= 1 && depth == 0 && !self.in_chain {
+            return self.execute_single(command, app_state, config, depth);
+        }
+
+        let in_chain = std::mem::replace(&mut self.in_chain, true);
+        let result = self.execute_steps(command, &steps, app_state, config, depth);
+        self.in_chain = in_chain;
+        result
+    }
+
+    fn execute_steps(
+        &mut self,
+        command: &str,
+        steps: &[String],
+        app_state: &mut AppState,
+        config: &mut Config,
+        depth: usize,
+    ) -> CommandResult {
+        let mut messages = Vec::new();
+        let mut last_result = None;
+        let mut step_end = 0;
+        for (i, step) in steps.iter().enumerate() {
+            // Steps are trimmed slices of the line, so each is found after
+            // the previous one; parse error positions are step-relative.
+            let step_start = step_end + command[step_end..].find(step.as_str()).unwrap_or(0);
+            step_end = step_start + step.len();
+            match self.execute_single(step, app_state, config, depth) {
+                CommandResult::Success(msg) => {
+                    messages.push(msg);
+                    if let Some(docker) = self.started.take() {
+                        self.pending = Some(PendingChain {
+                            line: command.to_string(),
+                            step: step.clone(),
+                            docker,
+                            then: steps[i + 1..].join("; "),
+                            messages,
+                            depth,
+                        });
+                        return CommandResult::Running(format!("Running {}", step));
+                    }
+                }
+                // An alias step is waiting on docker; the rest of this chain
+                // runs after the rest of the alias
+                CommandResult::Running(msg) => {
+                    if let Some(pending) = &mut self.pending {
+                        let rest = steps[i + 1..].join("; ");
+                        if pending.then.is_empty() {
+                            pending.then = rest;
+                        } else if !rest.is_empty() {
+                            pending.then = format!("{}; {}", pending.then, rest);
+                        }
+                        messages.append(&mut pending.messages);
+                        pending.messages = messages;
+                    }
+                    return CommandResult::Running(msg);
+                }
+                CommandResult::ParseError { message, position } => {
+                    return CommandResult::ParseError {
+                        message: format!(
+                            "Stopped at step {} of {}: {}",
+                            i + 1,
+                            steps.len(),
+                            message
+                        ),
+                        position: step_start + position,
+                    }
+                }
+                CommandResult::Error(msg) => {
+                    return CommandResult::Error(format!(
+                        "Stopped at step {} of {} ({}): {}",
+                        i + 1,
+                        steps.len(),
+                        step,
+                        msg
+                    ))
+                }
+                CommandResult::Confirm {
+                    operation,
+                    containers,
+                    ..
+                } => {
+                    return CommandResult::Confirm {
+                        operation,
+                        containers,
+                        then: steps[i + 1..].join("; "),
+                    }
+                }
+                CommandResult::Quit => return CommandResult::Quit,
+                other => last_result = Some(other),
+            }
         }
+
+        last_result.unwrap_or_else(|| CommandResult::Success(messages.join("\n")))
     }
 
-    pub fn execute(&mut self, command: &str, app_state: &mut AppState) -> CommandResult {
+    fn execute_single(
+        &mut self,
+        command: &str,
+        app_state: &mut AppState,
+        config: &mut Config,
+        depth: usize,
+    ) -> CommandResult {
+        let leading_space = command.len() - command.trim_start().len();
         let command = command.trim();
 
         if command.is_empty() {
             return CommandResult::Error("Empty command".to_string());
         }
 
-        self.add_to_history(command.to_string());
-
         if command == "q" || command == "quit" {
             return CommandResult::Quit;
         }
 
-        // Handle numeric navigation (e.g., :5, :10, :$)
-        if command == "$" {
-            let count = app_state.get_displayed_container_count();
-            if count > 0 {
-                return CommandResult::Navigation(count - 1);
+        // Ex-style ranges: `:5` and `:$` navigate, `:1,5stop` acts on lines
+        match parse_range(command) {
+            Ok((Some(range), rest)) => {
+                return self.execute_range_command(&range, rest, app_state, config)
             }
-            return CommandResult::Error("No containers available".to_string());
-        }
-
-        if let Ok(line_num) = command.parse::<usize>() {
-            let count = app_state.get_displayed_container_count();
-            if line_num > 0 && line_num <= count {
-                return CommandResult::Navigation(line_num - 1);
-            }
-            return CommandResult::Error(format!("Invalid line number: {}", line_num));
+            Ok((None, _)) => {}
+            Err(e) => return CommandResult::Error(e),
         }
 
         // Parse command and arguments
@@ -74,61 +378,260 @@ impl CommandExecutor {
         let cmd = parts[0];
         let args = &parts[1..];
 
+        // Container specs are parsed from the raw argument text, since
+        // regexes may contain spaces
+        let after_cmd = &command[cmd.len()..];
+        let spec = after_cmd.trim_start();
+        let spec_offset = leading_space + cmd.len() + (after_cmd.len() - spec.len());
+        let container_operation = match cmd {
+            "stop" | "start" | "restart" | "kill" | "pause" | "unpause" => Some(cmd),
+            "rm" | "remove" => Some("rm"),
+            _ => None,
+        };
+        if let Some(operation) = container_operation {
+            return self.execute_container_command(operation, spec, spec_offset, app_state, config);
+        }
+
         match cmd {
-            "stop" => self.execute_container_command("stop", args, app_state),
-            "start" => self.execute_container_command("start", args, app_state),
-            "restart" => self.execute_container_command("restart", args, app_state),
-            "kill" => self.execute_container_command("kill", args, app_state),
-            "pause" => self.execute_container_command("pause", args, app_state),
-            "unpause" => self.execute_container_command("unpause", args, app_state),
-            "rm" | "remove" => self.execute_container_command("rm", args, app_state),
+            "dry" => self.execute_dry_run(spec, spec_offset, app_state, config),
             "logs" => self.execute_logs_command(args, app_state),
             "exec" => self.execute_exec_command(args, app_state),
             "inspect" => self.execute_inspect_command(args, app_state),
             "build" => self.execute_build_command(args, app_state),
+            "up" => self.execute_up_command(args, app_state),
             "ps" | "refresh" => match app_state.refresh_containers() {
                 Ok(_) => CommandResult::Success("Containers refreshed".to_string()),
                 Err(e) => CommandResult::Error(format!("Failed to refresh: {}", e)),
             },
-            "set" => self.execute_set_command(args, app_state),
+            "set" => self.execute_set_command(args, app_state, config, false),
+            "set!" => self.execute_set_command(args, app_state, config, true),
+            "output" => self.execute_output_command(args, app_state),
+            "outputs" => self.execute_outputs_command(app_state),
             "reload" | "config" => self.execute_reload_command(),
-            _ => CommandResult::Error(format!("Unknown command: {}", cmd)),
+            _ => match config.commands.get(cmd).cloned() {
+                Some(body) => self.execute_alias(cmd, &body, args, app_state, config, depth),
+                None => CommandResult::Error(format!("Unknown command: {}", cmd)),
+            },
+        }
+    }
+
+    fn execute_alias(
+        &mut self,
+        name: &str,
+        body: &str,
+        args: &[&str],
+        app_state: &mut AppState,
+        config: &mut Config,
+        depth: usize,
+    ) -> CommandResult {
+        if depth >= MAX_ALIAS_DEPTH {
+            return CommandResult::Error(format!(
+                "Alias {} nests more than {} levels deep",
+                name, MAX_ALIAS_DEPTH
+            ));
+        }
+
+        match expand_alias(body, args) {
+            // Positions in the expansion mean nothing on the typed line
+            Ok(expanded) => match self.execute_chain(&expanded, app_state, config, depth + 1) {
+                CommandResult::ParseError { message, .. } => {
+                    CommandResult::Error(format!("Alias {}: {}", name, message))
+                }
+                result => result,
+            },
+            Err(e) => CommandResult::Error(format!("Alias {}: {}", name, e)),
         }
     }
 
+    /// Runs a docker operation on every container matched by `spec`, or on
+    /// the selected container if the spec is empty. `spec_offset` is where
+    /// the spec starts in the command line, for parse error positions.
+    /// Destructive operations return `Confirm` when the `[confirm]` policy
+    /// asks for it.
     fn execute_container_command(
-        &self,
+        &mut self,
         operation: &str,
-        args: &[&str],
+        spec: &str,
+        spec_offset: usize,
         app_state: &AppState,
+        config: &Config,
     ) -> CommandResult {
-        if args.is_empty() {
-            // Use currently selected container
-            if let Some(container) = app_state.get_selected_container() {
-                return self.docker_operation(operation, &container.name);
-            } else {
-                return CommandResult::Error("No container selected".to_string());
+        let targets = match self.resolve_targets(spec, spec_offset, app_state) {
+            Ok(targets) => targets,
+            Err(result) => return result,
+        };
+
+        self.act_on_targets(operation, &targets, config)
+    }
+
+    /// Runs a command over a range of lines, or jumps to the end of the
+    /// range if there is no command.
+    fn execute_range_command(
+        &mut self,
+        range: &LineRange,
+        command: &str,
+        app_state: &AppState,
+        config: &Config,
+    ) -> CommandResult {
+        let context = RangeContext {
+            current: app_state.list_state.selected(),
+            line_count: app_state.get_displayed_container_count(),
+            visual: app_state.visual_range(),
+        };
+        if context.line_count == 0 {
+            return CommandResult::Error("No containers available".to_string());
+        }
+        let (start, end) = match range.resolve(&context) {
+            Ok(lines) => lines,
+            Err(e) => return CommandResult::Error(e),
+        };
+
+        let mut parts = command.split_whitespace();
+        let operation = match parts.next() {
+            None => return CommandResult::Navigation(end),
+            Some("remove") => "rm",
+            Some(operation) if DRY_RUN_COMMANDS.contains(&operation) => operation,
+            Some(other) => return CommandResult::Error(format!("{} does not take a range", other)),
+        };
+        if parts.next().is_some() {
+            return CommandResult::Error(
+                "A range cannot be combined with a container spec".to_string(),
+            );
+        }
+        if app_state.compose_view_mode {
+            return CommandResult::Error("Ranges apply to the container list".to_string());
+        }
+
+        let targets = app_state.get_displayed_containers()[start..=end].to_vec();
+        self.act_on_targets(operation, &targets, config)
+    }
+
+    /// Runs the operation on the targets, or asks for confirmation first if
+    /// the `[confirm]` policy calls for it.
+    fn act_on_targets(
+        &mut self,
+        operation: &str,
+        targets: &[Container],
+        config: &Config,
+    ) -> CommandResult {
+        let names: Vec<String> = targets.iter().map(|c| c.name.clone()).collect();
+        if needs_confirmation(operation, targets, config) {
+            return CommandResult::Confirm {
+                operation: operation.to_string(),
+                containers: names,
+                then: String::new(),
+            };
+        }
+        self.run_container_operation(operation, &names)
+    }
+
+    /// Shows what a container command would act on without running it:
+    /// `:dry stop project:shop`.
+    fn execute_dry_run(
+        &self,
+        command: &str,
+        command_offset: usize,
+        app_state: &AppState,
+        config: &Config,
+    ) -> CommandResult {
+        let operation = command.split_whitespace().next().unwrap_or("");
+        let operation = match operation {
+            "" => return CommandResult::Error("Usage: :dry <command> [spec]".to_string()),
+            "remove" => "rm",
+            _ if DRY_RUN_COMMANDS.contains(&operation) => operation,
+            other => {
+                return CommandResult::Error(format!(
+                    "Dry runs apply to container commands, not {}",
+                    other
+                ))
             }
+        };
+
+        let after_operation =
+            &command[command.find(char::is_whitespace).unwrap_or(command.len())..];
+        let spec = after_operation.trim_start();
+        let spec_offset = command_offset + command.len() - spec.len();
+        let targets = match self.resolve_targets(spec, spec_offset, app_state) {
+            Ok(targets) => targets,
+            Err(result) => return result,
+        };
+
+        let mut content = format!(
+            "{} would act on {} container{}",
+            operation,
+            targets.len(),
+            if targets.len() == 1 { "" } else { "s" }
+        );
+        if needs_confirmation(operation, &targets, config) {
+            content.push_str(" (confirmation required)");
+        }
+        content.push_str(":\n");
+        for container in &targets {
+            content.push_str(&format!("  {}  {}\n", container.name, container.status));
         }
 
-        let containers = app_state.get_displayed_containers();
-        let mut results = Vec::new();
-        let mut errors = Vec::new();
+        CommandResult::Output {
+            title: format!("Dry run: {}", command),
+            content,
+        }
+    }
 
-        for arg in args {
-            let matched_containers = self.resolve_container_spec(arg, &containers);
+    /// Resolves the containers a command acts on: the spec's matches, or
+    /// when there is no spec the marked containers or else the selected one.
+    fn resolve_targets(
+        &self,
+        spec: &str,
+        spec_offset: usize,
+        app_state: &AppState,
+    ) -> Result<Vec<Container>, CommandResult> {
+        if spec.is_empty() {
+            let marked = app_state.marked_containers();
+            if !marked.is_empty() {
+                return Ok(marked);
+            }
+            return match app_state.get_selected_container() {
+                Some(container) => Ok(vec![container.clone()]),
+                None => Err(CommandResult::Error("No container selected".to_string())),
+            };
+        }
 
-            if matched_containers.is_empty() {
-                errors.push(format!("No containers found matching: {}", arg));
-                continue;
+        let matched = self
+            .resolve_container_spec(spec, &app_state.get_displayed_containers())
+            .map_err(|e| CommandResult::ParseError {
+                message: e.message,
+                position: spec_offset + e.position,
+            })?;
+        if matched.is_empty() {
+            return Err(CommandResult::Error(format!(
+                "No containers found matching: {}",
+                spec
+            )));
+        }
+        Ok(matched)
+    }
+
+    /// Runs `docker <operation>` on each named container. Within a chain
+    /// the operations start on a `DockerStep` the chain then waits for.
+    pub fn run_container_operation(&mut self, operation: &str, names: &[String]) -> CommandResult {
+        if self.in_chain {
+            if self.pending.is_some() {
+                return CommandResult::Error("Another command chain is still running".to_string());
             }
+            self.started = Some(DockerStep::spawn(&self.docker, operation, names));
+            let messages: Vec<_> = names
+                .iter()
+                .map(|name| format!("{} {}", operation, name))
+                .collect();
+            return CommandResult::Success(messages.join("; "));
+        }
 
-            for container in matched_containers {
-                match self.docker_operation(operation, &container.name) {
-                    CommandResult::Success(msg) => results.push(msg),
-                    CommandResult::Error(err) => errors.push(err),
-                    _ => {}
-                }
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        for name in names {
+            match self.docker_operation(operation, name) {
+                CommandResult::Success(msg) => results.push(msg),
+                CommandResult::Error(err) => errors.push(err),
+                _ => {}
             }
         }
 
@@ -143,6 +646,10 @@ impl CommandExecutor {
 
     fn execute_logs_command(&self, args: &[&str], app_state: &AppState) -> CommandResult {
         let container_name = if args.is_empty() {
+            let marked = app_state.marked_containers();
+            if !marked.is_empty() {
+                return self.marked_logs(&marked);
+            }
             if let Some(container) = app_state.get_selected_container() {
                 container.name.clone()
             } else {
@@ -150,27 +657,45 @@ impl CommandExecutor {
             }
         } else {
             let containers = app_state.get_displayed_containers();
-            let matched = self.resolve_container_spec(args[0], &containers);
-            if matched.is_empty() {
-                return CommandResult::Error(format!("No container found matching: {}", args[0]));
+            match self.resolve_container_spec(args[0], &containers) {
+                Ok(matched) if !matched.is_empty() => matched[0].name.clone(),
+                Ok(_) => {
+                    return CommandResult::Error(format!(
+                        "No container found matching: {}",
+                        args[0]
+                    ))
+                }
+                Err(e) => return CommandResult::Error(e.to_string()),
             }
-            matched[0].name.clone()
         };
 
-        match Command::new("docker")
-            .args(["logs", "--tail", "100", &container_name])
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    let logs = String::from_utf8_lossy(&output.stdout);
-                    CommandResult::Success(format!("Logs for {}:\n{}", container_name, logs))
-                } else {
-                    let error = String::from_utf8_lossy(&output.stderr);
-                    CommandResult::Error(format!("Failed to get logs: {}", error))
-                }
+        match fetch_logs(&self.docker, &container_name) {
+            Ok(logs) => CommandResult::Output {
+                title: format!("Logs for {}", container_name),
+                content: logs,
+            },
+            Err(e) => CommandResult::Error(e),
+        }
+    }
+
+    /// Logs of every marked container, one after another under `==> name <==`
+    /// headers as `tail` prints several files.
+    fn marked_logs(&self, marked: &[Container]) -> CommandResult {
+        let mut content = String::new();
+        for container in marked {
+            let logs = match fetch_logs(&self.docker, &container.name) {
+                Ok(logs) => logs,
+                Err(e) => return CommandResult::Error(e),
+            };
+            if !content.is_empty() {
+                content.push('\n');
             }
-            Err(e) => CommandResult::Error(format!("Failed to execute docker logs: {}", e)),
+            content.push_str(&format!("==> {} <==\n", container.name));
+            content.push_str(&logs);
+        }
+        CommandResult::Output {
+            title: format!("Logs for {} marked containers", marked.len()),
+            content,
         }
     }
 
@@ -180,11 +705,13 @@ impl CommandExecutor {
         }
 
         let containers = app_state.get_displayed_containers();
-        let matched = self.resolve_container_spec(args[0], &containers);
-
-        if matched.is_empty() {
-            return CommandResult::Error(format!("No container found matching: {}", args[0]));
-        }
+        let matched = match self.resolve_container_spec(args[0], &containers) {
+            Ok(matched) if !matched.is_empty() => matched,
+            Ok(_) => {
+                return CommandResult::Error(format!("No container found matching: {}", args[0]))
+            }
+            Err(e) => return CommandResult::Error(e.to_string()),
+        };
 
         let container_name = &matched[0].name;
         let exec_cmd = if args.len() > 1 {
@@ -208,11 +735,16 @@ impl CommandExecutor {
             }
         } else {
             let containers = app_state.get_displayed_containers();
-            let matched = self.resolve_container_spec(args[0], &containers);
-            if matched.is_empty() {
-                return CommandResult::Error(format!("No container found matching: {}", args[0]));
+            match self.resolve_container_spec(args[0], &containers) {
+                Ok(matched) if !matched.is_empty() => matched[0].name.clone(),
+                Ok(_) => {
+                    return CommandResult::Error(format!(
+                        "No container found matching: {}",
+                        args[0]
+                    ))
+                }
+                Err(e) => return CommandResult::Error(e.to_string()),
             }
-            matched[0].name.clone()
         };
 
         match Command::new("docker")
@@ -221,8 +753,23 @@ impl CommandExecutor {
         {
             Ok(output) => {
                 if output.status.success() {
-                    let inspect_data = String::from_utf8_lossy(&output.stdout);
-                    CommandResult::Success(format!("Inspect {}:\n{}", container_name, inspect_data))
+                    // `docker inspect` prints an array with one entry per
+                    // object; unwrap it so the viewer opens on the container.
+                    match serde_json::from_slice::<serde_json::Value>(&output.stdout) {
+                        Ok(serde_json::Value::Array(mut entries)) if entries.len() == 1 => {
+                            CommandResult::Inspect {
+                                container_name,
+                                data: entries.remove(0),
+                            }
+                        }
+                        Ok(data) => CommandResult::Inspect {
+                            container_name,
+                            data,
+                        },
+                        Err(e) => {
+                            CommandResult::Error(format!("Failed to parse inspect output: {}", e))
+                        }
+                    }
                 } else {
                     let error = String::from_utf8_lossy(&output.stderr);
                     CommandResult::Error(format!("Failed to inspect: {}", error))
@@ -269,107 +816,208 @@ impl CommandExecutor {
         }
     }
 
-    fn execute_set_command(&self, args: &[&str], _app_state: &mut AppState) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::Success("Available settings: tabular".to_string());
+    /// `:up [service...]` brings up services of the selected compose project
+    /// in the background, or the whole project without arguments.
+    fn execute_up_command(&self, args: &[&str], app_state: &mut AppState) -> CommandResult {
+        let Some(project) = selected_project(app_state) else {
+            return CommandResult::Error("No compose project selected".to_string());
+        };
+        if let Some(unknown) = args
+            .iter()
+            .find(|service| project.service(service).is_none())
+        {
+            return CommandResult::Error(format!(
+                "Project {} has no service {}",
+                project.project_name, unknown
+            ));
         }
 
-        match args[0] {
-            "tabular" => {
-                CommandResult::Success("Tabular mode settings not yet implemented".to_string())
+        let mut up = "up -d".to_string();
+        for service in args {
+            up.push(' ');
+            up.push_str(&shell_quote(service));
+        }
+        let command = project.compose_command(&up);
+        match app_state.start_action("Up", &command) {
+            Ok(()) if args.is_empty() => {
+                CommandResult::Success(format!("Bringing up {}", project.project_name))
             }
-            _ => CommandResult::Error(format!("Unknown setting: {}", args[0])),
+            Ok(()) => CommandResult::Success(format!(
+                "Bringing up {} in {}",
+                args.join(", "),
+                project.project_name
+            )),
+            Err(e) => CommandResult::Error(e),
         }
     }
 
-    fn execute_reload_command(&self) -> CommandResult {
-        let config = crate::shared::config::Config::load();
-        CommandResult::ConfigReload(Box::new(config))
-    }
-
-    fn resolve_container_spec(&self, spec: &str, containers: &[Container]) -> Vec<Container> {
-        // Handle * to match all containers
-        if spec == "*" {
-            return containers.to_vec();
-        }
-
-        // Handle label matching: label:value or label:/regex/
-        if let Some(label_spec) = spec.strip_prefix("label:") {
-            // Remove "label:" prefix
-
-            // Check if it's a regex pattern: label:/regex/
-            if label_spec.starts_with('/') && label_spec.ends_with('/') && label_spec.len() > 2 {
-                let pattern = &label_spec[1..label_spec.len() - 1];
-                if let Ok(re) = Regex::new(pattern) {
-                    return containers
-                        .iter()
-                        .filter(|c| {
-                            c.labels.values().any(|v| re.is_match(v))
-                                || c.labels.keys().any(|k| re.is_match(k))
-                        })
-                        .cloned()
-                        .collect();
-                }
-            } else {
-                // Exact label match (key or key=value)
-                if let Some((key, value)) = label_spec.split_once('=') {
-                    // Match label with specific value
-                    return containers
-                        .iter()
-                        .filter(|c| c.labels.get(key).is_some_and(|v| v == value))
-                        .cloned()
-                        .collect();
-                } else {
-                    // Match any container with this label key
-                    return containers
-                        .iter()
-                        .filter(|c| c.labels.contains_key(label_spec))
-                        .cloned()
-                        .collect();
-                }
+    /// Handles `:set` in its vim forms: `opt` (enable, or show a non-boolean
+    /// option), `noopt`, `invopt`, `opt=value` and `opt?`. Several arguments
+    /// may be given at once. `:set!` also saves the config file.
+    fn execute_set_command(
+        &self,
+        args: &[&str],
+        app_state: &mut AppState,
+        config: &mut Config,
+        persist: bool,
+    ) -> CommandResult {
+        if args.is_empty() && !persist {
+            let content = SET_OPTIONS
+                .iter()
+                .map(|option| {
+                    format!(
+                        "{:<12} {:<8} {}",
+                        option.name,
+                        option_value(option, config, app_state),
+                        option.description
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            return CommandResult::Output {
+                title: "Options (:set)".to_string(),
+                content,
+            };
+        }
+
+        let mut messages = Vec::new();
+        for arg in args {
+            match self.apply_set_argument(arg, app_state, config) {
+                Ok(message) => messages.push(message),
+                Err(e) => return CommandResult::Error(e),
             }
         }
 
-        // Handle regex patterns (enclosed in //)
-        if spec.starts_with('/') && spec.ends_with('/') && spec.len() > 2 {
-            let pattern = &spec[1..spec.len() - 1];
-            if let Ok(re) = Regex::new(pattern) {
-                return containers
-                    .iter()
-                    .filter(|c| {
-                        re.is_match(&c.name)
-                            || re.is_match(&c.image)
-                            || re.is_match(&c.status)
-                            || c.labels.values().any(|v| re.is_match(v))
-                    })
-                    .cloned()
-                    .collect();
+        if persist {
+            sync_runtime_options(config, app_state);
+            match config.save() {
+                Ok(overridden) if overridden.is_empty() => messages.push("(saved)".to_string()),
+                // Saved, but the project config's value wins when it is read
+                Ok(overridden) => messages.push(format!(
+                    "(saved; .dprs.toml overrides {})",
+                    overridden.join(", ")
+                )),
+                Err(e) => return CommandResult::Error(format!("Could not save config: {}", e)),
             }
         }
 
-        // Handle wildcards (contains * or ?)
-        if spec.contains('*') || spec.contains('?') {
-            let pattern = spec.replace('*', ".*").replace('?', ".");
-            if let Ok(re) = Regex::new(&format!("^{}$", pattern)) {
-                return containers
-                    .iter()
-                    .filter(|c| re.is_match(&c.name))
-                    .cloned()
-                    .collect();
+        CommandResult::Success(messages.join(" "))
+    }
+
+    /// Applies one `:set` argument and describes the resulting value.
+    fn apply_set_argument(
+        &self,
+        arg: &str,
+        app_state: &mut AppState,
+        config: &mut Config,
+    ) -> Result<String, String> {
+        let unknown = |name: &str| format!("Unknown option: {}", name);
+        let describe = |option: &SetOption, config: &Config, app_state: &AppState| {
+            format!(
+                "{}={}",
+                option.name,
+                option_value(option, config, app_state)
+            )
+        };
+
+        if let Some(name) = arg.strip_suffix('?') {
+            let option = find_option(name).ok_or_else(|| unknown(name))?;
+            return Ok(describe(option, config, app_state));
+        }
+
+        if let Some((name, value)) = arg.split_once('=') {
+            let option = find_option(name).ok_or_else(|| unknown(name))?;
+            set_option(option, value, config, app_state)?;
+            return Ok(describe(option, config, app_state));
+        }
+
+        if let Some(option) = find_option(arg) {
+            if option.kind == OptionKind::Bool {
+                set_option(option, "true", config, app_state)?;
             }
+            return Ok(describe(option, config, app_state));
+        }
+
+        let boolean = |name: &str| find_option(name).filter(|o| o.kind == OptionKind::Bool);
+        if let Some(option) = arg.strip_prefix("no").and_then(boolean) {
+            set_option(option, "false", config, app_state)?;
+            return Ok(describe(option, config, app_state));
+        }
+        if let Some(option) = arg.strip_prefix("inv").and_then(boolean) {
+            let current = option_value(option, config, app_state) == "true";
+            set_option(option, &(!current).to_string(), config, app_state)?;
+            return Ok(describe(option, config, app_state));
+        }
+
+        Err(unknown(arg))
+    }
+
+    /// Reopens a previous command output in the pager. `:output` shows the
+    /// most recent one and `:output N` the Nth most recent.
+    fn execute_output_command(&self, args: &[&str], app_state: &mut AppState) -> CommandResult {
+        let n = match args.first() {
+            None => 1,
+            Some(arg) => match arg.parse::<usize>() {
+                Ok(n) => n,
+                Err(_) => return CommandResult::Error(format!("Invalid output number: {}", arg)),
+            },
+        };
+
+        match app_state.output_pager.reopen(n) {
+            Ok(()) => CommandResult::Success(format!("Showing output {}", n)),
+            Err(e) => CommandResult::Error(e),
+        }
+    }
+
+    /// Lists the command outputs that can be reopened with `:output N`.
+    fn execute_outputs_command(&self, app_state: &AppState) -> CommandResult {
+        let history = app_state.output_pager.history();
+        if history.is_empty() {
+            return CommandResult::Error("No previous output".to_string());
         }
 
-        // Exact container name or ID match
-        containers
+        let content = history
             .iter()
-            .filter(|c| {
-                c.name == spec || c.name.starts_with(spec) || c.container_id.starts_with(spec)
+            .rev()
+            .enumerate()
+            .map(|(i, entry)| {
+                format!(
+                    "{:>3}  {}  {} ({} lines)",
+                    i + 1,
+                    entry.created_at.format("%H:%M:%S"),
+                    entry.title,
+                    entry.content.lines().count()
+                )
             })
-            .cloned()
-            .collect()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        CommandResult::Output {
+            title: "Output history (:output N to reopen)".to_string(),
+            content,
+        }
+    }
+
+    fn execute_reload_command(&self) -> CommandResult {
+        // A config that does not parse leaves the current one in place
+        match crate::shared::config::Config::read() {
+            Ok(config) => CommandResult::ConfigReload(Box::new(config)),
+            Err(e) => CommandResult::Error(e),
+        }
+    }
+
+    /// Resolves a single-container argument such as the one `:logs` takes.
+    /// Errors are reported with their position within `spec`.
+    fn resolve_container_spec(
+        &self,
+        spec: &str,
+        containers: &[Container],
+    ) -> Result<Vec<Container>, SpecError> {
+        parse_spec(spec).map(|query| query.select(containers))
     }
 
-    /// Runs `docker <operation> <container>` on a background thread.
+    /// Runs `docker <operation> <container>` on a background thread, for a
+    /// command typed on its own. Chains wait on a `DockerStep` instead.
     ///
     /// This does not wait for the command to finish. `docker stop` and
     /// `docker restart` send SIGTERM and then wait out a grace period (10
@@ -387,7 +1035,7 @@ impl CommandExecutor {
 
         // stdout/stderr are discarded rather than inherited: the TUI owns the
         // terminal, and anything docker printed would corrupt the display.
-        match Command::new("docker")
+        match Command::new(&self.docker)
             .args([&operation, &name])
             .stdin(Stdio::null())
             .stdout(Stdio::null())
@@ -408,19 +1056,115 @@ impl CommandExecutor {
             }
         }
     }
+}
+
+/// The last lines of a container's log. docker logs writes the container's
+/// stderr to its own stderr, so both streams go into one pipe to keep the
+/// lines in the order they were logged.
+fn fetch_logs(docker: &str, container_name: &str) -> Result<String, String> {
+    let failed = |e: std::io::Error| format!("Failed to execute docker logs: {}", e);
+    let (mut reader, writer) = std::io::pipe().map_err(failed)?;
+    let mut command = Command::new(docker);
+    command
+        .args(["logs", "--tail", "100", container_name])
+        .stdin(Stdio::null())
+        .stdout(writer.try_clone().map_err(failed)?)
+        .stderr(writer);
+    let mut child = command.spawn().map_err(failed)?;
+    // The command holds the write ends; reading only ends once they close
+    drop(command);
+
+    let mut output = Vec::new();
+    reader.read_to_end(&mut output).map_err(failed)?;
+    let status = child.wait().map_err(failed)?;
+    let logs = String::from_utf8_lossy(&output).into_owned();
+    if status.success() {
+        Ok(logs)
+    } else {
+        Err(format!("Failed to get logs: {}", logs.trim()))
+    }
+}
+
+/// Splits a command line on `;`, ignoring separators inside single or
+/// double quotes. Empty commands are dropped.
+pub fn split_command_chain(command: &str) -> Vec<String> {
+    let mut steps = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+
+    for c in command.chars() {
+        match (c, quote) {
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (';', None) => steps.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    steps.push(current);
+
+    steps
+        .into_iter()
+        .map(|step| step.trim().to_string())
+        .filter(|step| !step.is_empty())
+        .collect()
+}
+
+/// Substitutes alias arguments into an alias body: `$1`..`$9` are single
+/// arguments, `$@` is all of them and `$$` is a literal `$`. A body without
+/// any parameters gets the arguments appended, so `l = "logs"` makes `:l web`
+/// run `logs web`.
+pub fn expand_alias(body: &str, args: &[&str]) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut uses_params = false;
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
 
-    fn add_to_history(&mut self, command: String) {
-        if !self.command_history.contains(&command) {
-            self.command_history.push(command);
-            if self.command_history.len() > 100 {
-                self.command_history.remove(0);
+        match chars.peek().copied() {
+            Some('$') => {
+                chars.next();
+                expanded.push('$');
             }
+            Some('@') => {
+                chars.next();
+                uses_params = true;
+                expanded.push_str(&args.join(" "));
+            }
+            Some(d @ '1'..='9') => {
+                chars.next();
+                uses_params = true;
+                let index = d.to_digit(10).unwrap_or(1) as usize;
+                match args.get(index - 1) {
+                    Some(arg) => expanded.push_str(arg),
+                    None => {
+                        return Err(format!(
+                            "missing argument ${} ({} given)",
+                            index,
+                            args.len()
+                        ))
+                    }
+                }
+            }
+            _ => expanded.push('$'),
         }
     }
 
-    pub fn get_history(&self) -> &[String] {
-        &self.command_history
+    if !uses_params && !args.is_empty() {
+        expanded.push(' ');
+        expanded.push_str(&args.join(" "));
     }
+
+    Ok(expanded)
 }
 
 #[cfg(test)]
@@ -447,9 +1191,392 @@ mod tests {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             compose_project: Some("test-project".to_string()),
             labels: label_map,
+            networks: Vec::new(),
         }
     }
 
+    #[test]
+    fn test_split_command_chain() {
+        assert_eq!(
+            split_command_chain("stop web; rm web ;; ps"),
+            vec!["stop web", "rm web", "ps"]
+        );
+        assert_eq!(
+            split_command_chain("exec web sh -c 'a; b'; ps"),
+            vec!["exec web sh -c 'a; b'", "ps"]
+        );
+        assert!(split_command_chain(" ; ").is_empty());
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        assert_eq!(
+            expand_alias("stop label:role=$1; rm label:role=$1", &["db"]).unwrap(),
+            "stop label:role=db; rm label:role=db"
+        );
+        assert_eq!(
+            expand_alias("restart $@", &["web", "api"]).unwrap(),
+            "restart web api"
+        );
+        assert_eq!(expand_alias("logs", &["web"]).unwrap(), "logs web");
+        assert_eq!(
+            expand_alias("exec $1 echo $$HOME", &["web"]).unwrap(),
+            "exec web echo $HOME"
+        );
+        assert!(expand_alias("logs $2", &["web"]).is_err());
+    }
+
+    #[test]
+    fn test_chain_stops_on_error() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        let mut config = Config::default();
+
+        match executor.execute(
+            "set tabular?; output 1; set fx?",
+            &mut app_state,
+            &mut config,
+        ) {
+            CommandResult::Error(msg) => {
+                assert!(
+                    msg.starts_with("Stopped at step 2 of 3 (output 1)"),
+                    "{}",
+                    msg
+                )
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    /// Runs `executor.resume` until the waiting chain has finished.
+    fn finish_chain(
+        executor: &mut CommandExecutor,
+        app_state: &mut AppState,
+        config: &mut Config,
+    ) -> CommandResult {
+        for _ in 0..500 {
+            if let Some((_, result)) = executor.resume(app_state, config) {
+                match result {
+                    CommandResult::Running(_) => continue,
+                    result => return result,
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the chain did not finish");
+    }
+
+    /// Writes an executable `docker` script running `body` into `dir`.
+    fn fake_docker(dir: &std::path::Path, body: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::create_dir_all(dir).unwrap();
+        let script = dir.join("docker");
+        std::fs::write(&script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script.display().to_string()
+    }
+
+    #[test]
+    fn test_logs_keep_stdout_and_stderr_in_order() {
+        let dir = std::env::temp_dir().join(format!("dprs-logs-{}", std::process::id()));
+        let script = fake_docker(&dir, "echo one; echo two >&2; echo three");
+
+        let mut executor = CommandExecutor::new();
+        executor.docker = script;
+        let mut app_state = AppState::new();
+        app_state.containers = vec![create_test_container("web-1", "nginx", vec![])];
+        let mut config = Config::default();
+        match executor.execute("logs web-1", &mut app_state, &mut config) {
+            CommandResult::Output { content, .. } => assert_eq!(content, "one\ntwo\nthree\n"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_chain_waits_for_docker_and_stops_on_failure() {
+        // A stand-in for docker that records its calls and fails on pause
+        let dir = std::env::temp_dir().join(format!("dprs-chain-{}", std::process::id()));
+        let calls = dir.join("calls");
+        let script = fake_docker(
+            &dir,
+            &format!(
+                "echo \"$@\" >> {}\n[ \"$1\" != pause ] || {{ echo 'no such container' >&2; exit 1; }}",
+                calls.display()
+            ),
+        );
+
+        let mut executor = CommandExecutor::new();
+        executor.docker = script;
+        let mut app_state = AppState::new();
+        app_state.containers = vec![create_test_container("web-1", "nginx", vec![])];
+        let mut config = Config::default();
+
+        let result = executor.execute("start web-1; restart web-1", &mut app_state, &mut config);
+        assert!(matches!(result, CommandResult::Running(_)), "{:?}", result);
+        match finish_chain(&mut executor, &mut app_state, &mut config) {
+            CommandResult::Success(msg) => assert_eq!(msg, "start web-1\nrestart web-1"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(
+            std::fs::read_to_string(&calls).unwrap(),
+            "start web-1\nrestart web-1\n"
+        );
+
+        std::fs::remove_file(&calls).unwrap();
+        executor.execute(
+            "start web-1; pause web-1; restart web-1",
+            &mut app_state,
+            &mut config,
+        );
+        match finish_chain(&mut executor, &mut app_state, &mut config) {
+            CommandResult::Error(msg) => assert_eq!(
+                msg,
+                "Stopped at pause web-1: pause web-1: no such container (skipped: restart web-1)"
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        // restart never ran
+        assert_eq!(
+            std::fs::read_to_string(&calls).unwrap(),
+            "start web-1\npause web-1\n"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_alias_execution() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        let mut config = Config::default();
+        config
+            .commands
+            .insert("both".to_string(), "set fx?; set tabular?".to_string());
+        config
+            .commands
+            .insert("loop".to_string(), "loop".to_string());
+
+        match executor.execute("both", &mut app_state, &mut config) {
+            CommandResult::Success(msg) => assert_eq!(msg.lines().count(), 2),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match executor.execute("loop", &mut app_state, &mut config) {
+            CommandResult::Error(msg) => assert!(msg.contains("nests more than"), "{}", msg),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_confirmation_and_dry_run() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        app_state.containers = vec![
+            create_test_container("web-1", "nginx", vec![("env", "shared")]),
+            create_test_container("web-2", "nginx", vec![]),
+        ];
+        let mut config = Config::default();
+
+        match executor.execute("stop web-*; set tabular", &mut app_state, &mut config) {
+            CommandResult::Confirm {
+                operation,
+                containers,
+                then,
+            } => {
+                assert_eq!(operation, "stop");
+                assert_eq!(containers, vec!["web-1", "web-2"]);
+                assert_eq!(then, "set tabular");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        match executor.execute("dry rm web-2", &mut app_state, &mut config) {
+            CommandResult::Output { title, content } => {
+                assert_eq!(title, "Dry run: rm web-2");
+                assert_eq!(
+                    content,
+                    "rm would act on 1 container (confirmation required):\n  web-2  Up\n"
+                );
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // Only containers carrying the label need confirming
+        config.confirm.policy = "label".to_string();
+        config.confirm.label = "env=shared".to_string();
+        assert!(needs_confirmation(
+            "kill",
+            &app_state.containers[..1],
+            &config
+        ));
+        assert!(!needs_confirmation(
+            "kill",
+            &app_state.containers[1..],
+            &config
+        ));
+        assert!(!needs_confirmation(
+            "restart",
+            &app_state.containers,
+            &config
+        ));
+
+        match executor.execute("dry build", &mut app_state, &mut config) {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "Dry runs apply to container commands, not build")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_range_commands() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        app_state.containers = (1..=5)
+            .map(|i| create_test_container(&format!("web-{}", i), "nginx", vec![]))
+            .collect();
+        app_state.list_state.select(Some(2));
+        app_state.last_visual_range = Some((0, 1));
+        let mut config = Config::default();
+
+        let mut targets =
+            |command: &str| match executor.execute(command, &mut app_state, &mut config) {
+                CommandResult::Confirm { containers, .. } => Ok(containers),
+                CommandResult::Navigation(line) => Ok(vec![line.to_string()]),
+                CommandResult::Error(msg) => Err(msg),
+                other => panic!("unexpected result: {:?}", other),
+            };
+
+        assert_eq!(targets("2,3stop"), Ok(vec!["web-2".into(), "web-3".into()]));
+        assert_eq!(
+            targets(".,$rm"),
+            Ok(vec!["web-3".into(), "web-4".into(), "web-5".into()])
+        );
+        assert_eq!(
+            targets("'<,'>kill"),
+            Ok(vec!["web-1".into(), "web-2".into()])
+        );
+        assert_eq!(targets("$"), Ok(vec!["4".into()]));
+        assert_eq!(targets("3"), Ok(vec!["2".into()]));
+        assert_eq!(targets("9"), Err("Line 9 is out of range (1-5)".into()));
+        assert_eq!(targets("1,2ps"), Err("ps does not take a range".into()));
+        assert_eq!(
+            targets("1,2stop web-4"),
+            Err("A range cannot be combined with a container spec".into())
+        );
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        app_state.containers = (1..=4)
+            .map(|i| create_test_container(&format!("web-{}", i), "nginx", vec![]))
+            .collect();
+        let mut config = Config::default();
+
+        app_state.list_state.select(Some(0));
+        assert!(app_state.toggle_mark());
+        app_state.list_state.select(Some(2));
+        assert!(app_state.toggle_mark());
+        app_state.list_state.select(Some(3));
+        assert_eq!(app_state.get_selected_indices(), vec![0, 2]);
+
+        // Without a spec, container commands act on the marks
+        match executor.execute("stop", &mut app_state, &mut config) {
+            CommandResult::Confirm { containers, .. } => {
+                assert_eq!(containers, vec!["web-1", "web-3"])
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        app_state.invert_marks();
+        assert_eq!(app_state.marked_indices(), vec![1, 3]);
+
+        app_state.list_state.select(Some(1));
+        assert!(!app_state.toggle_mark());
+        app_state.clear_marks();
+        assert_eq!(app_state.get_selected_indices(), vec![1]);
+    }
+
+    #[test]
+    fn test_spec_parse_error_position() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        let mut config = Config::default();
+
+        match executor.execute("stop  project:shop !nmae:db", &mut app_state, &mut config) {
+            CommandResult::ParseError { position, .. } => assert_eq!(position, 20),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match executor.execute("set tabular; kill status:gone", &mut app_state, &mut config) {
+            CommandResult::ParseError { message, position } => {
+                assert!(message.starts_with("Stopped at step 2 of 2: unknown status"));
+                assert_eq!(position, 25);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_set_command_forms() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        let mut config = Config::default();
+
+        let mut run = |command: &str| match executor.execute(command, &mut app_state, &mut config) {
+            CommandResult::Success(msg) => Ok(msg),
+            CommandResult::Error(msg) => Err(msg),
+            other => panic!("unexpected result: {:?}", other),
+        };
+
+        assert_eq!(run("set tabular"), Ok("tabular=true".to_string()));
+        assert_eq!(run("set notabular"), Ok("tabular=false".to_string()));
+        assert_eq!(run("set invtabular"), Ok("tabular=true".to_string()));
+        assert_eq!(run("set tabular?"), Ok("tabular=true".to_string()));
+        assert_eq!(
+            run("set noheaders autorefresh=3"),
+            Ok("headers=false autorefresh=3".to_string())
+        );
+        // Non-boolean options are shown rather than set
+        assert_eq!(run("set autorefresh"), Ok("autorefresh=3".to_string()));
+        assert_eq!(
+            run("set noautorefresh"),
+            Err("Unknown option: noautorefresh".to_string())
+        );
+        assert_eq!(run("set bogus=1"), Err("Unknown option: bogus".to_string()));
+    }
+
+    #[test]
+    fn test_up_needs_a_project() {
+        let mut executor = CommandExecutor::new();
+        let mut app_state = AppState::new();
+        let mut config = Config::default();
+        let result = executor.execute("up web", &mut app_state, &mut config);
+        assert!(
+            matches!(result, CommandResult::Error(ref e) if e == "No compose project selected"),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_resolve_project() {
+        let executor = CommandExecutor::new();
+        let mut other = create_test_container("db-1", "postgres", vec![]);
+        other.compose_project = Some("other".to_string());
+        let containers = vec![create_test_container("web-1", "nginx", vec![]), other];
+
+        let result = executor
+            .resolve_container_spec("project:other", &containers)
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "db-1");
+    }
+
     #[test]
     fn test_resolve_wildcard_all() {
         let executor = CommandExecutor::new();
@@ -459,7 +1586,7 @@ mod tests {
             create_test_container("db-1", "postgres", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("*", &containers);
+        let result = executor.resolve_container_spec("*", &containers).unwrap();
         assert_eq!(result.len(), 3);
     }
 
@@ -472,7 +1599,9 @@ mod tests {
             create_test_container("db-1", "postgres", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("label:env", &containers);
+        let result = executor
+            .resolve_container_spec("label:env", &containers)
+            .unwrap();
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|c| c.name == "web-1"));
         assert!(result.iter().any(|c| c.name == "api-1"));
@@ -487,7 +1616,9 @@ mod tests {
             create_test_container("db-1", "postgres", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("label:env=prod", &containers);
+        let result = executor
+            .resolve_container_spec("label:env=prod", &containers)
+            .unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "web-1");
     }
@@ -501,7 +1632,9 @@ mod tests {
             create_test_container("db-1", "postgres", vec![("env", "test")]),
         ];
 
-        let result = executor.resolve_container_spec("label:/prod/", &containers);
+        let result = executor
+            .resolve_container_spec("label:/prod/", &containers)
+            .unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "web-1");
     }
@@ -515,7 +1648,9 @@ mod tests {
             create_test_container("api-1", "node", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("/^web-/", &containers);
+        let result = executor
+            .resolve_container_spec("/^web-/", &containers)
+            .unwrap();
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|c| c.name == "web-1"));
         assert!(result.iter().any(|c| c.name == "web-2"));
@@ -530,7 +1665,9 @@ mod tests {
             create_test_container("api-1", "node", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("web-*", &containers);
+        let result = executor
+            .resolve_container_spec("web-*", &containers)
+            .unwrap();
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|c| c.name == "web-1"));
         assert!(result.iter().any(|c| c.name == "web-2"));
@@ -544,7 +1681,9 @@ mod tests {
             create_test_container("api-1", "node", vec![]),
         ];
 
-        let result = executor.resolve_container_spec("web-1", &containers);
+        let result = executor
+            .resolve_container_spec("web-1", &containers)
+            .unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "web-1");
     }
@@ -555,7 +1694,9 @@ mod tests {
         let mut containers = vec![create_test_container("web-1", "nginx", vec![])];
         containers[0].container_id = "abc123def456".to_string();
 
-        let result = executor.resolve_container_spec("abc123", &containers);
+        let result = executor
+            .resolve_container_spec("abc123", &containers)
+            .unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].name, "web-1");
     }
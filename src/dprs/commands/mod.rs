@@ -2,6 +2,11 @@
 
 pub use handler::*;
 
+pub mod completion;
+mod docker_step;
 mod handler;
+pub mod options;
+pub mod range;
+pub mod spec;
 
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
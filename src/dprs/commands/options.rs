@@ -0,0 +1,211 @@
+// The options module defines the runtime settings available through `:set`.
+// Each option maps onto a GeneralConfig, LayoutConfig, ColorConfig or
+// ConfirmConfig field. Options that also have live state in AppState (the
+// tabular view and the container filter) update both, so a change takes
+// effect immediately and is written out by `:set!` through Config::save.
+
+use crate::dprs::app::state_machine::{AppState, ContainerFilter};
+use crate::shared::config::{Config, COLOR_DEPTHS, CONFIRM_POLICIES, THEMES};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptionKind {
+    Bool,
+    Number,
+    Choice(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SetOption {
+    pub name: &'static str,
+    pub kind: OptionKind,
+    pub description: &'static str,
+}
+
+pub const SET_OPTIONS: &[SetOption] = &[
+    SetOption {
+        name: "autorefresh",
+        kind: OptionKind::Number,
+        description: "seconds between container refreshes (0 = default)",
+    },
+    SetOption {
+        name: "borders",
+        kind: OptionKind::Bool,
+        description: "draw borders around the container list",
+    },
+    SetOption {
+        name: "colordepth",
+        kind: OptionKind::Choice(COLOR_DEPTHS),
+        description: "colours the terminal can show (auto detects)",
+    },
+    SetOption {
+        name: "confirm",
+        kind: OptionKind::Choice(CONFIRM_POLICIES),
+        description: "when :stop, :kill, :rm and visual batches ask first",
+    },
+    SetOption {
+        name: "filter",
+        kind: OptionKind::Choice(&["running", "recent", "all"]),
+        description: "which containers are listed",
+    },
+    SetOption {
+        name: "fx",
+        kind: OptionKind::Bool,
+        description: "experimental animations",
+    },
+    SetOption {
+        name: "headers",
+        kind: OptionKind::Bool,
+        description: "column headers in the tabular view",
+    },
+    SetOption {
+        name: "history",
+        kind: OptionKind::Number,
+        description: "command and search history entries kept",
+    },
+    SetOption {
+        name: "logpane",
+        kind: OptionKind::Choice(&["bottom", "right"]),
+        description: "log pane placement",
+    },
+    SetOption {
+        name: "logpanesize",
+        kind: OptionKind::Number,
+        description: "percentage of the main area used by the log pane",
+    },
+    SetOption {
+        name: "tabular",
+        kind: OptionKind::Bool,
+        description: "tabular container view",
+    },
+    SetOption {
+        name: "theme",
+        kind: OptionKind::Choice(THEMES),
+        description: "colour theme",
+    },
+];
+
+pub fn find_option(name: &str) -> Option<&'static SetOption> {
+    SET_OPTIONS.iter().find(|option| option.name == name)
+}
+
+pub fn option_value(option: &SetOption, config: &Config, app_state: &AppState) -> String {
+    match option.name {
+        "autorefresh" => config.general.auto_refresh_interval.to_string(),
+        "borders" => config.layout.show_borders.to_string(),
+        "colordepth" => config.colors.color_depth.clone(),
+        "confirm" => config.confirm.policy.clone(),
+        "filter" => app_state.container_filter.name().to_string(),
+        "fx" => config.general.experimental_fx.to_string(),
+        "headers" => config.layout.show_headers.to_string(),
+        "history" => config.general.max_history_items.to_string(),
+        "logpane" => config.layout.log_pane_position.clone(),
+        "logpanesize" => config.layout.log_pane_size.to_string(),
+        "tabular" => app_state.tabular_mode.to_string(),
+        "theme" => config.colors.theme.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Parses `value` for the option and applies it to the config and to any
+/// live state that mirrors it.
+pub fn set_option(
+    option: &SetOption,
+    value: &str,
+    config: &mut Config,
+    app_state: &mut AppState,
+) -> Result<(), String> {
+    match option.name {
+        "autorefresh" => config.general.auto_refresh_interval = parse_number(option, value)?,
+        "borders" => config.layout.show_borders = parse_bool(option, value)?,
+        "colordepth" => {
+            parse_choice(option, value)?;
+            config.colors.color_depth = value.to_string();
+            config.detect_color_depth();
+        }
+        "confirm" => {
+            parse_choice(option, value)?;
+            config.confirm.policy = value.to_string();
+        }
+        "filter" => {
+            let filter =
+                ContainerFilter::from_name(value).ok_or_else(|| invalid_value(option, value))?;
+            config.general.container_filter = filter.name().to_string();
+            app_state.set_container_filter(filter);
+        }
+        "fx" => config.general.experimental_fx = parse_bool(option, value)?,
+        "headers" => config.layout.show_headers = parse_bool(option, value)?,
+        "history" => {
+            let max_items = parse_number(option, value)?;
+            if max_items == 0 {
+                return Err(invalid_value(option, value));
+            }
+            config.general.max_history_items = max_items;
+            app_state.set_max_history(max_items);
+        }
+        "logpane" => {
+            parse_choice(option, value)?;
+            config.layout.log_pane_position = value.to_string();
+        }
+        "logpanesize" => {
+            let size: u16 = parse_number(option, value)?;
+            if !(10..=90).contains(&size) {
+                return Err(format!("{} must be between 10 and 90", option.name));
+            }
+            config.layout.log_pane_size = size;
+        }
+        "tabular" => {
+            let tabular = parse_bool(option, value)?;
+            config.general.tabular_mode = tabular;
+            app_state.tabular_mode = tabular;
+        }
+        "theme" => {
+            parse_choice(option, value)?;
+            config.colors.theme = value.to_string();
+        }
+        _ => return Err(format!("Unknown option: {}", option.name)),
+    }
+    Ok(())
+}
+
+/// Copies live state that can also change outside `:set` (the `t` key and
+/// the filter toggles) into the config before it is saved.
+pub fn sync_runtime_options(config: &mut Config, app_state: &AppState) {
+    config.general.tabular_mode = app_state.tabular_mode;
+    config.general.container_filter = app_state.container_filter.name().to_string();
+}
+
+fn parse_bool(option: &SetOption, value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "on" | "yes" | "1" => Ok(true),
+        "false" | "off" | "no" | "0" => Ok(false),
+        _ => Err(invalid_value(option, value)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(option: &SetOption, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| invalid_value(option, value))
+}
+
+fn parse_choice(option: &SetOption, value: &str) -> Result<(), String> {
+    match option.kind {
+        OptionKind::Choice(choices) if choices.contains(&value) => Ok(()),
+        _ => Err(invalid_value(option, value)),
+    }
+}
+
+fn invalid_value(option: &SetOption, value: &str) -> String {
+    let expected = match option.kind {
+        OptionKind::Bool => "true or false".to_string(),
+        OptionKind::Number => "a number".to_string(),
+        OptionKind::Choice(choices) => choices.join(", "),
+    };
+    format!(
+        "Invalid value for {}: {} (expected {})",
+        option.name, value, expected
+    )
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,81 @@
+// The tests module for `:set` options validates reading and writing each
+// kind of option, value validation, and keeping live state in step with the
+// config.
+
+use super::*;
+
+#[test]
+fn test_bool_option() {
+    let mut config = Config::default();
+    let mut app_state = AppState::new();
+    let option = find_option("borders").unwrap();
+
+    set_option(option, "off", &mut config, &mut app_state).unwrap();
+    assert!(!config.layout.show_borders);
+    assert_eq!(option_value(option, &config, &app_state), "false");
+
+    let err = set_option(option, "maybe", &mut config, &mut app_state).unwrap_err();
+    assert_eq!(
+        err,
+        "Invalid value for borders: maybe (expected true or false)"
+    );
+}
+
+#[test]
+fn test_tabular_updates_live_state() {
+    let mut config = Config::default();
+    let mut app_state = AppState::new();
+    let option = find_option("tabular").unwrap();
+
+    set_option(option, "true", &mut config, &mut app_state).unwrap();
+    assert!(app_state.tabular_mode);
+    assert!(config.general.tabular_mode);
+
+    // Toggled outside :set, then synced before saving
+    app_state.tabular_mode = false;
+    assert_eq!(option_value(option, &config, &app_state), "false");
+    sync_runtime_options(&mut config, &app_state);
+    assert!(!config.general.tabular_mode);
+}
+
+#[test]
+fn test_number_and_choice_options() {
+    let mut config = Config::default();
+    let mut app_state = AppState::new();
+
+    set_option(
+        find_option("autorefresh").unwrap(),
+        "5",
+        &mut config,
+        &mut app_state,
+    )
+    .unwrap();
+    assert_eq!(config.general.auto_refresh_interval, 5);
+
+    let size = find_option("logpanesize").unwrap();
+    assert!(set_option(size, "95", &mut config, &mut app_state).is_err());
+    set_option(size, "30", &mut config, &mut app_state).unwrap();
+    assert_eq!(config.layout.log_pane_size, 30);
+
+    let position = find_option("logpane").unwrap();
+    assert!(set_option(position, "left", &mut config, &mut app_state).is_err());
+    set_option(position, "right", &mut config, &mut app_state).unwrap();
+    assert_eq!(config.layout.log_pane_position, "right");
+}
+
+#[test]
+fn test_history_option_caps_history() {
+    let mut config = Config::default();
+    let mut app_state = AppState::new();
+    for command in ["a", "b", "c"] {
+        app_state.command_state.add_to_history(command.to_string());
+    }
+
+    let option = find_option("history").unwrap();
+    assert!(set_option(option, "0", &mut config, &mut app_state).is_err());
+    set_option(option, "2", &mut config, &mut app_state).unwrap();
+    assert_eq!(config.general.max_history_items, 2);
+    assert_eq!(app_state.command_state.history, vec!["b", "c"]);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
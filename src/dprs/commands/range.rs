@@ -0,0 +1,167 @@
+// The range module parses ex-style line ranges at the start of a command,
+// as in `:1,5stop`, `:.,$restart` or `:'<,'>kill`. An address is a line
+// number, `.` for the selected line, `$` for the last line, or `'<` and `'>`
+// for the first and last line of the most recent visual selection, each
+// optionally followed by `+N` or `-N` offsets. `%` stands for every line.
+// Lines are 1-based in the command line and resolved to 0-based indices
+// into the displayed container list.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressBase {
+    Line(usize),
+    Current,
+    Last,
+    VisualStart,
+    VisualEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Address {
+    pub base: AddressBase,
+    pub offset: isize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineRange {
+    pub start: Address,
+    pub end: Address,
+}
+
+/// What addresses resolve against: the selected line, the number of lines
+/// and the last visual selection, all 0-based.
+#[derive(Debug, Clone, Copy)]
+pub struct RangeContext {
+    pub current: Option<usize>,
+    pub line_count: usize,
+    pub visual: Option<(usize, usize)>,
+}
+
+impl LineRange {
+    /// Resolves the range to inclusive 0-based indices. A backwards range
+    /// is swapped, as vim offers to do.
+    pub fn resolve(&self, context: &RangeContext) -> Result<(usize, usize), String> {
+        let start = self.start.resolve(context)?;
+        let end = self.end.resolve(context)?;
+        Ok((start.min(end), start.max(end)))
+    }
+}
+
+impl Address {
+    fn resolve(&self, context: &RangeContext) -> Result<usize, String> {
+        let line = match self.base {
+            AddressBase::Line(line) => line as isize,
+            AddressBase::Current => match context.current {
+                Some(current) => current as isize + 1,
+                None => return Err("No container selected".to_string()),
+            },
+            AddressBase::Last => context.line_count as isize,
+            AddressBase::VisualStart | AddressBase::VisualEnd => {
+                let Some((start, end)) = context.visual else {
+                    return Err("No visual selection".to_string());
+                };
+                let line = if self.base == AddressBase::VisualStart {
+                    start
+                } else {
+                    end
+                };
+                line as isize + 1
+            }
+        } + self.offset;
+
+        if line < 1 || line as usize > context.line_count {
+            return Err(format!(
+                "Line {} is out of range (1-{})",
+                line, context.line_count
+            ));
+        }
+        Ok(line as usize - 1)
+    }
+}
+
+/// Splits a leading range off a command. Returns `None` for the range if
+/// the command does not start with one.
+pub fn parse_range(command: &str) -> Result<(Option<LineRange>, &str), String> {
+    if let Some(rest) = command.strip_prefix('%') {
+        let range = LineRange {
+            start: Address {
+                base: AddressBase::Line(1),
+                offset: 0,
+            },
+            end: Address {
+                base: AddressBase::Last,
+                offset: 0,
+            },
+        };
+        return Ok((Some(range), rest.trim_start()));
+    }
+
+    let Some((start, rest)) = parse_address(command)? else {
+        return Ok((None, command));
+    };
+    let (end, rest) = match rest.strip_prefix(',') {
+        Some(after_comma) => match parse_address(after_comma)? {
+            Some((end, rest)) => (end, rest),
+            None => return Err(format!("Missing address after ',': {}", command)),
+        },
+        None => (start, rest),
+    };
+
+    Ok((Some(LineRange { start, end }), rest.trim_start()))
+}
+
+fn parse_address(text: &str) -> Result<Option<(Address, &str)>, String> {
+    let (base, mut rest) = if let Some(rest) = text.strip_prefix('.') {
+        (AddressBase::Current, rest)
+    } else if let Some(rest) = text.strip_prefix('$') {
+        (AddressBase::Last, rest)
+    } else if let Some(rest) = text.strip_prefix("'<") {
+        (AddressBase::VisualStart, rest)
+    } else if let Some(rest) = text.strip_prefix("'>") {
+        (AddressBase::VisualEnd, rest)
+    } else if let Some(mark) = text.strip_prefix('\'') {
+        let mark = mark.chars().next().map(String::from).unwrap_or_default();
+        return Err(format!("Unknown mark: '{}", mark));
+    } else {
+        match split_number(text) {
+            Some((line, rest)) => (AddressBase::Line(line), rest),
+            None => return Ok(None),
+        }
+    };
+
+    let mut offset = 0isize;
+    loop {
+        let sign = if rest.starts_with('+') {
+            1
+        } else if rest.starts_with('-') {
+            -1
+        } else {
+            break;
+        };
+        rest = &rest[1..];
+        // A bare `+` or `-` means one line, as in vim
+        let amount = match split_number(rest) {
+            Some((amount, after)) => {
+                rest = after;
+                amount as isize
+            }
+            None => 1,
+        };
+        offset += sign * amount;
+    }
+
+    Ok(Some((Address { base, offset }, rest)))
+}
+
+fn split_number(text: &str) -> Option<(usize, &str)> {
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let number = text[..digits].parse().ok()?;
+    Some((number, &text[digits..]))
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
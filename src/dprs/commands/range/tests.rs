@@ -0,0 +1,64 @@
+// The tests module for ex ranges validates parsing addresses, offsets and
+// `%`, and resolving them against the container list.
+
+use super::*;
+
+fn context() -> RangeContext {
+    RangeContext {
+        current: Some(2),
+        line_count: 6,
+        visual: Some((1, 3)),
+    }
+}
+
+fn resolve(command: &str) -> Result<((usize, usize), String), String> {
+    let (range, rest) = parse_range(command)?;
+    let range = range.ok_or("no range")?;
+    Ok((range.resolve(&context())?, rest.to_string()))
+}
+
+#[test]
+fn test_ranges() {
+    assert_eq!(resolve("1,5stop"), Ok(((0, 4), "stop".to_string())));
+    assert_eq!(resolve(".,$restart"), Ok(((2, 5), "restart".to_string())));
+    assert_eq!(resolve("'<,'>kill"), Ok(((1, 3), "kill".to_string())));
+    assert_eq!(resolve("%rm"), Ok(((0, 5), "rm".to_string())));
+    assert_eq!(resolve(".+1,$-1 stop"), Ok(((3, 4), "stop".to_string())));
+    assert_eq!(resolve("4"), Ok(((3, 3), String::new())));
+    // Backwards ranges are swapped
+    assert_eq!(resolve("5,2stop"), Ok(((1, 4), "stop".to_string())));
+}
+
+#[test]
+fn test_no_range() {
+    assert_eq!(parse_range("stop web"), Ok((None, "stop web")));
+}
+
+#[test]
+fn test_range_errors() {
+    assert_eq!(
+        resolve("0,3stop"),
+        Err("Line 0 is out of range (1-6)".to_string())
+    );
+    assert_eq!(
+        resolve("1,9stop"),
+        Err("Line 9 is out of range (1-6)".to_string())
+    );
+    assert_eq!(resolve("'a,'bstop"), Err("Unknown mark: 'a".to_string()));
+    assert_eq!(
+        resolve("1,stop"),
+        Err("Missing address after ',': 1,stop".to_string())
+    );
+
+    let (range, _) = parse_range("'<,'>stop").unwrap();
+    let no_visual = RangeContext {
+        visual: None,
+        ..context()
+    };
+    assert_eq!(
+        range.unwrap().resolve(&no_visual),
+        Err("No visual selection".to_string())
+    );
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
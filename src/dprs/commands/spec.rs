@@ -0,0 +1,598 @@
+// The spec module parses and evaluates the container specs accepted by
+// container commands such as `:stop`. A spec is a list of terms: bare names,
+// name prefixes and IDs, `*` globs, `/regex/` patterns and qualified terms
+// like `status:exited`, `image:postgres*`, `project:shop`, `health:unhealthy`,
+// `port:5432`, `network:backend`, `label:env=prod` and `id:abc123`. Terms may
+// be negated with `!`, combined with `and` and `or` (`and` binds tighter) and
+// grouped with parentheses. Terms listed side by side add to the selection,
+// while negated ones remove from it, so `project:shop !name:*db*` selects the
+// shop containers other than the databases. Parse errors carry the byte
+// offset of the offending token so the command line can point at it.
+
+use crate::dprs::app::state_machine::Container;
+use regex::Regex;
+use std::fmt;
+
+pub const QUALIFIERS: &[&str] = &[
+    "health", "id", "image", "label", "name", "network", "port", "project", "status",
+];
+
+pub const STATUS_VALUES: &[&str] = &[
+    "running",
+    "stopped",
+    "exited",
+    "paused",
+    "created",
+    "restarting",
+    "removing",
+    "dead",
+];
+
+pub const HEALTH_VALUES: &[&str] = &["healthy", "unhealthy", "starting", "none"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecError {
+    pub message: String,
+    /// Byte offset into the spec of the token the error refers to.
+    pub position: usize,
+}
+
+impl SpecError {
+    fn new(message: impl Into<String>, position: usize) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.position + 1)
+    }
+}
+
+/// A name, image, project or network pattern: an exact value, a `*`/`?` glob
+/// or a `/regex/`.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn parse(value: &str, position: usize) -> Result<Self, SpecError> {
+        if let Some(pattern) = regex_body(value) {
+            return compile_regex(pattern, position).map(Pattern::Regex);
+        }
+        if value.contains('*') || value.contains('?') {
+            return Ok(Pattern::Regex(glob_to_regex(value)));
+        }
+        Ok(Pattern::Exact(value.to_string()))
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            Pattern::Exact(value) => text == value,
+            Pattern::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Term {
+    All,
+    /// A bare word: the exact name, a name prefix or an ID prefix.
+    Word(String),
+    /// A bare `/regex/`, matched against name, image, status and labels.
+    Regex(Regex),
+    Name(Pattern),
+    Image(Pattern),
+    Project(Pattern),
+    Network(Pattern),
+    Status(String),
+    Health(String),
+    Port(u16),
+    Id(String),
+    LabelKey(String),
+    LabelValue(String, Pattern),
+    LabelRegex(Regex),
+}
+
+#[derive(Debug, Clone)]
+pub enum SpecExpr {
+    Term(Term),
+    Not(Box<SpecExpr>),
+    And(Vec<SpecExpr>),
+    Or(Vec<SpecExpr>),
+    /// Terms listed side by side: the union of the plain ones minus the
+    /// union of the negated ones.
+    List(Vec<SpecExpr>),
+}
+
+impl SpecExpr {
+    /// Selects the matching containers, keeping their list order.
+    pub fn select(&self, containers: &[Container]) -> Vec<Container> {
+        containers
+            .iter()
+            .filter(|c| self.matches(c))
+            .cloned()
+            .collect()
+    }
+
+    pub fn matches(&self, container: &Container) -> bool {
+        match self {
+            SpecExpr::Term(term) => term.matches(container),
+            SpecExpr::Not(inner) => !inner.matches(container),
+            SpecExpr::And(items) => items.iter().all(|item| item.matches(container)),
+            SpecExpr::Or(items) => items.iter().any(|item| item.matches(container)),
+            SpecExpr::List(items) => {
+                let mut has_positive = false;
+                let mut included = false;
+                for item in items {
+                    match item {
+                        SpecExpr::Not(inner) => {
+                            if inner.matches(container) {
+                                return false;
+                            }
+                        }
+                        _ => {
+                            has_positive = true;
+                            included = included || item.matches(container);
+                        }
+                    }
+                }
+                // A list of only negated terms starts from every container.
+                included || !has_positive
+            }
+        }
+    }
+}
+
+impl Term {
+    pub fn matches(&self, c: &Container) -> bool {
+        match self {
+            Term::All => true,
+            Term::Word(word) => {
+                c.name == *word || c.name.starts_with(word) || c.container_id.starts_with(word)
+            }
+            Term::Regex(re) => {
+                re.is_match(&c.name)
+                    || re.is_match(&c.image)
+                    || re.is_match(&c.status)
+                    || c.labels.values().any(|v| re.is_match(v))
+            }
+            Term::Name(pattern) => pattern.matches(&c.name),
+            Term::Image(pattern) => {
+                pattern.matches(&c.image) || pattern.matches(image_without_tag(&c.image))
+            }
+            Term::Project(pattern) => c
+                .compose_project
+                .as_deref()
+                .is_some_and(|project| pattern.matches(project)),
+            Term::Network(pattern) => c.networks.iter().any(|n| pattern.matches(n)),
+            Term::Status(status) => match status.as_str() {
+                "stopped" => container_state(&c.status) != "running",
+                state => container_state(&c.status) == state,
+            },
+            Term::Health(health) => container_health(&c.status) == health,
+            Term::Port(port) => container_ports(&c.ports).contains(port),
+            Term::Id(prefix) => c.container_id.starts_with(prefix),
+            Term::LabelKey(key) => c.labels.contains_key(key),
+            Term::LabelValue(key, pattern) => c.labels.get(key).is_some_and(|v| pattern.matches(v)),
+            Term::LabelRegex(re) => {
+                c.labels.values().any(|v| re.is_match(v)) || c.labels.keys().any(|k| re.is_match(k))
+            }
+        }
+    }
+}
+
+/// Parses a container spec.
+pub fn parse_spec(spec: &str) -> Result<SpecExpr, SpecError> {
+    let tokens = tokenize(spec)?;
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: spec.len(),
+    };
+    let expr = parser.parse_list()?;
+    match parser.peek() {
+        Some(token) => Err(SpecError::new("unmatched ')'", token.position)),
+        None => Ok(expr),
+    }
+}
+
+/// Derives the docker state from a `docker ps` status string such as
+/// "Up 2 hours (Paused)" or "Exited (0) 3 minutes ago".
+pub fn container_state(status: &str) -> &'static str {
+    if status.starts_with("Up") {
+        if status.contains("(Paused)") {
+            "paused"
+        } else {
+            "running"
+        }
+    } else if status.starts_with("Exited") {
+        "exited"
+    } else if status.starts_with("Created") {
+        "created"
+    } else if status.starts_with("Restarting") {
+        "restarting"
+    } else if status.starts_with("Removal") {
+        "removing"
+    } else if status.starts_with("Dead") {
+        "dead"
+    } else {
+        "unknown"
+    }
+}
+
+/// Derives the health check state from a `docker ps` status string.
+pub fn container_health(status: &str) -> &'static str {
+    if status.contains("(healthy)") {
+        "healthy"
+    } else if status.contains("(unhealthy)") {
+        "unhealthy"
+    } else if status.contains("(health: starting)") {
+        "starting"
+    } else {
+        "none"
+    }
+}
+
+/// Collects the host and container ports from a `docker ps` ports string
+/// such as "0.0.0.0:8080->80/tcp, 5432/tcp".
+pub fn container_ports(ports: &str) -> Vec<u16> {
+    let mut result = Vec::new();
+    for (host, container) in port_mappings(ports) {
+        result.extend(host);
+        result.push(container);
+    }
+    result
+}
+
+/// The (host port, container port) pairs of a `docker ps` ports string. The
+/// host port is None for ports that are exposed but not published.
+pub fn port_mappings(ports: &str) -> Vec<(Option<u16>, u16)> {
+    let mut result = Vec::new();
+    for mapping in ports.split(',') {
+        let mapping = mapping.trim();
+        let (host, container) = match mapping.split_once("->") {
+            Some((host, container)) => (Some(host), container),
+            None => (None, mapping),
+        };
+        let host = host
+            .and_then(|h| h.rsplit(':').next())
+            .and_then(|p| p.parse().ok());
+        if let Some(port) = container.split('/').next().and_then(|p| p.parse().ok()) {
+            result.push((host, port));
+        }
+    }
+    result
+}
+
+fn image_without_tag(image: &str) -> &str {
+    match image.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => name,
+        _ => image,
+    }
+}
+
+fn regex_body(value: &str) -> Option<&str> {
+    if value.len() > 2 && value.starts_with('/') && value.ends_with('/') {
+        Some(&value[1..value.len() - 1])
+    } else {
+        None
+    }
+}
+
+fn compile_regex(pattern: &str, position: usize) -> Result<Regex, SpecError> {
+    Regex::new(pattern).map_err(|e| SpecError::new(format!("invalid regex: {}", e), position))
+}
+
+fn glob_to_regex(glob: &str) -> Regex {
+    let pattern = regex::escape(glob).replace("\\*", ".*").replace("\\?", ".");
+    Regex::new(&format!("^{}$", pattern)).expect("escaped glob is a valid regex")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+}
+
+fn tokenize(spec: &str) -> Result<Vec<Token>, SpecError> {
+    let mut tokens = Vec::new();
+    let mut chars = spec.char_indices().peekable();
+
+    while let Some(&(start, ch)) = chars.peek() {
+        match ch {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | '!' => {
+                chars.next();
+                let kind = match ch {
+                    '(' => TokenKind::Open,
+                    ')' => TokenKind::Close,
+                    _ => TokenKind::Not,
+                };
+                tokens.push(Token {
+                    kind,
+                    position: start,
+                });
+            }
+            _ => {
+                // A '/' at the start of a word or right after a qualifier's
+                // ':' opens a regex, which may contain spaces and parentheses
+                // up to the closing '/'. Elsewhere, as in a label value that
+                // is a path, it is an ordinary character.
+                let mut word = String::new();
+                let mut in_regex = false;
+                while let Some(&(_, c)) = chars.peek() {
+                    if in_regex {
+                        word.push(c);
+                        chars.next();
+                        if c == '\\' {
+                            if let Some((_, escaped)) = chars.next() {
+                                word.push(escaped);
+                            }
+                        } else if c == '/' {
+                            in_regex = false;
+                        }
+                        continue;
+                    }
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    let after_qualifier = word
+                        .strip_suffix(':')
+                        .is_some_and(|qualifier| QUALIFIERS.contains(&qualifier));
+                    if c == '/' && (word.is_empty() || after_qualifier) {
+                        in_regex = true;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if in_regex {
+                    return Err(SpecError::new("unterminated regex", start));
+                }
+                let kind = match word.as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    _ => TokenKind::Word(word),
+                };
+                tokens.push(Token {
+                    kind,
+                    position: start,
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn position(&self) -> usize {
+        self.peek().map(|t| t.position).unwrap_or(self.end)
+    }
+
+    fn parse_list(&mut self) -> Result<SpecExpr, SpecError> {
+        let mut items = Vec::new();
+        while let Some(token) = self.peek() {
+            match token.kind {
+                TokenKind::Close => break,
+                TokenKind::And | TokenKind::Or => {
+                    let keyword = if token.kind == TokenKind::And {
+                        "and"
+                    } else {
+                        "or"
+                    };
+                    return Err(SpecError::new(
+                        format!("'{}' needs a term before it", keyword),
+                        token.position,
+                    ));
+                }
+                _ => items.push(self.parse_or()?),
+            }
+        }
+        match items.len() {
+            0 => Err(SpecError::new("expected a container spec", self.position())),
+            1 => Ok(items.remove(0)),
+            _ => Ok(SpecExpr::List(items)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<SpecExpr, SpecError> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.next();
+            self.expect_term("or")?;
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            SpecExpr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<SpecExpr, SpecError> {
+        let mut items = vec![self.parse_unary()?];
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.next();
+            self.expect_term("and")?;
+            items.push(self.parse_unary()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            SpecExpr::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<SpecExpr, SpecError> {
+        if self.peek().is_some_and(|t| t.kind == TokenKind::Not) {
+            self.next();
+            self.expect_term("!")?;
+            return Ok(SpecExpr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<SpecExpr, SpecError> {
+        let position = self.position();
+        match self.next().map(|t| t.kind) {
+            Some(TokenKind::Open) => {
+                let inner = self.parse_list()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::Close,
+                        ..
+                    }) => Ok(inner),
+                    _ => Err(SpecError::new("unclosed '('", position)),
+                }
+            }
+            Some(TokenKind::Word(word)) => parse_term(&word, position).map(SpecExpr::Term),
+            Some(TokenKind::Close) => Err(SpecError::new("unexpected ')'", position)),
+            _ => Err(SpecError::new("expected a container spec", position)),
+        }
+    }
+
+    /// Reports an operator with nothing usable after it.
+    fn expect_term(&self, operator: &str) -> Result<(), SpecError> {
+        match self.peek().map(|t| &t.kind) {
+            None | Some(TokenKind::Close) | Some(TokenKind::And) | Some(TokenKind::Or) => {
+                Err(SpecError::new(
+                    format!("expected a term after '{}'", operator),
+                    self.position(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_term(word: &str, position: usize) -> Result<Term, SpecError> {
+    if word == "*" {
+        return Ok(Term::All);
+    }
+    if let Some(pattern) = regex_body(word) {
+        return compile_regex(pattern, position).map(Term::Regex);
+    }
+
+    let Some((qualifier, value)) = word.split_once(':') else {
+        if word.contains('*') || word.contains('?') {
+            return Ok(Term::Name(Pattern::Regex(glob_to_regex(word))));
+        }
+        return Ok(Term::Word(word.to_string()));
+    };
+
+    if !QUALIFIERS.contains(&qualifier) {
+        return Err(SpecError::new(
+            format!(
+                "unknown qualifier '{}' (expected {})",
+                qualifier,
+                QUALIFIERS.join(", ")
+            ),
+            position,
+        ));
+    }
+    let value_position = position + qualifier.len() + 1;
+    if value.is_empty() {
+        return Err(SpecError::new(
+            format!("missing value after '{}:'", qualifier),
+            value_position,
+        ));
+    }
+
+    match qualifier {
+        "name" => Pattern::parse(value, value_position).map(Term::Name),
+        "image" => Pattern::parse(value, value_position).map(Term::Image),
+        "project" => Pattern::parse(value, value_position).map(Term::Project),
+        "network" => Pattern::parse(value, value_position).map(Term::Network),
+        "status" => {
+            let status = value.to_lowercase();
+            if status == "up" {
+                Ok(Term::Status("running".to_string()))
+            } else if STATUS_VALUES.contains(&status.as_str()) {
+                Ok(Term::Status(status))
+            } else {
+                Err(SpecError::new(
+                    format!(
+                        "unknown status '{}' (expected {})",
+                        value,
+                        STATUS_VALUES.join(", ")
+                    ),
+                    value_position,
+                ))
+            }
+        }
+        "health" => {
+            let health = value.to_lowercase();
+            if HEALTH_VALUES.contains(&health.as_str()) {
+                Ok(Term::Health(health))
+            } else {
+                Err(SpecError::new(
+                    format!(
+                        "unknown health '{}' (expected {})",
+                        value,
+                        HEALTH_VALUES.join(", ")
+                    ),
+                    value_position,
+                ))
+            }
+        }
+        "port" => value
+            .parse()
+            .map(Term::Port)
+            .map_err(|_| SpecError::new(format!("invalid port '{}'", value), value_position)),
+        "id" => Ok(Term::Id(value.to_string())),
+        "label" => {
+            if let Some(pattern) = regex_body(value) {
+                return compile_regex(pattern, value_position).map(Term::LabelRegex);
+            }
+            // The value after '=' is matched literally, since label values
+            // are often paths or URLs
+            match value.split_once('=') {
+                Some((key, label_value)) => Ok(Term::LabelValue(
+                    key.to_string(),
+                    Pattern::Exact(label_value.to_string()),
+                )),
+                None => Ok(Term::LabelKey(value.to_string())),
+            }
+        }
+        _ => unreachable!("qualifier checked against QUALIFIERS"),
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,159 @@
+// The tests module for container specs covers qualified terms, literal
+// label values, negation and the `and`/`or` combinators, and the positions
+// reported for parse errors.
+
+use super::*;
+use std::collections::HashMap;
+
+fn container(name: &str, project: &str, status: &str, ports: &str) -> Container {
+    Container {
+        name: name.to_string(),
+        image: format!("{}:latest", name.trim_start_matches("shop-")),
+        status: status.to_string(),
+        ports: ports.to_string(),
+        container_id: format!("{}-id", name),
+        compose_project: Some(project.to_string()),
+        labels: HashMap::from([("env".to_string(), "prod".to_string())]),
+        networks: vec![format!("{}_default", project)],
+        ..Default::default()
+    }
+}
+
+fn containers() -> Vec<Container> {
+    vec![
+        container(
+            "shop-web",
+            "shop",
+            "Up 2 hours (healthy)",
+            "0.0.0.0:8080->80/tcp",
+        ),
+        container("shop-db", "shop", "Up 2 hours (unhealthy)", "5432/tcp"),
+        container("blog-web", "blog", "Exited (0) 3 minutes ago", ""),
+        container("blog-db", "blog", "Up 1 hour (Paused)", "3306/tcp"),
+    ]
+}
+
+fn names(spec: &str) -> Vec<String> {
+    parse_spec(spec)
+        .unwrap()
+        .select(&containers())
+        .into_iter()
+        .map(|c| c.name)
+        .collect()
+}
+
+#[test]
+fn test_qualified_terms() {
+    assert_eq!(names("status:exited"), vec!["blog-web"]);
+    assert_eq!(names("status:paused"), vec!["blog-db"]);
+    assert_eq!(names("status:running"), vec!["shop-web", "shop-db"]);
+    assert_eq!(names("health:unhealthy"), vec!["shop-db"]);
+    assert_eq!(names("port:8080"), vec!["shop-web"]);
+    assert_eq!(names("port:5432"), vec!["shop-db"]);
+    assert_eq!(names("image:db"), vec!["shop-db"]);
+    assert_eq!(names("image:blog-*"), vec!["blog-web", "blog-db"]);
+    assert_eq!(names("network:blog_default"), vec!["blog-web", "blog-db"]);
+    assert_eq!(names("label:env=prod"), names("*"));
+}
+
+#[test]
+fn test_label_values_are_literal() {
+    let mut web = container("shop-web", "shop", "Up 2 hours", "");
+    web.labels.insert(
+        "com.docker.compose.project.working_dir".to_string(),
+        "/srv/app".to_string(),
+    );
+    let select = |spec: &str| -> Vec<String> {
+        parse_spec(spec)
+            .unwrap()
+            .select(std::slice::from_ref(&web))
+            .into_iter()
+            .map(|c| c.name)
+            .collect()
+    };
+
+    assert_eq!(
+        select("label:com.docker.compose.project.working_dir=/srv/app"),
+        vec!["shop-web"]
+    );
+    assert!(select("label:com.docker.compose.project.working_dir=/srv/*").is_empty());
+    assert!(select("label:env=pr*").is_empty());
+    // A regex still opens right after the qualifier
+    assert_eq!(select("label:/^com\\.docker/"), vec!["shop-web"]);
+}
+
+#[test]
+fn test_negation_and_lists() {
+    assert_eq!(names("project:shop !name:*db*"), vec!["shop-web"]);
+    assert_eq!(names("!project:shop"), vec!["blog-web", "blog-db"]);
+    assert_eq!(names("shop-web blog-web"), vec!["shop-web", "blog-web"]);
+    assert_eq!(names("*db !blog-db"), vec!["shop-db"]);
+}
+
+#[test]
+fn test_and_or() {
+    assert_eq!(names("project:blog and name:*web"), vec!["blog-web"]);
+    assert_eq!(
+        names("health:healthy or status:exited"),
+        vec!["shop-web", "blog-web"]
+    );
+    // `and` binds tighter than `or`
+    assert_eq!(
+        names("project:shop and name:*db or status:exited"),
+        vec!["shop-db", "blog-web"]
+    );
+    assert_eq!(
+        names("project:shop and !(name:*db or name:*web)"),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_regex_with_spaces() {
+    assert_eq!(names("/Up 1 hour/"), vec!["blog-db"]);
+}
+
+#[test]
+fn test_parse_error_positions() {
+    let err = parse_spec("project:shop !nmae:*db*").unwrap_err();
+    assert!(err.message.starts_with("unknown qualifier 'nmae'"));
+    assert_eq!(err.position, 14);
+
+    let err = parse_spec("status:sleeping").unwrap_err();
+    assert!(err.message.starts_with("unknown status 'sleeping'"));
+    assert_eq!(err.position, 7);
+
+    let err = parse_spec("name:web and").unwrap_err();
+    assert_eq!(err.message, "expected a term after 'and'");
+    assert_eq!(err.position, 12);
+
+    let err = parse_spec("(name:web or name:db").unwrap_err();
+    assert_eq!(err.message, "unclosed '('");
+    assert_eq!(err.position, 0);
+
+    let err = parse_spec("port:http").unwrap_err();
+    assert_eq!(err.position, 5);
+
+    let err = parse_spec("web )").unwrap_err();
+    assert_eq!(err.message, "unmatched ')'");
+    assert_eq!(err.position, 4);
+}
+
+#[test]
+fn test_container_ports() {
+    assert_eq!(
+        container_ports("0.0.0.0:8080->80/tcp, :::8080->80/tcp, 5432/tcp"),
+        vec![8080, 80, 8080, 80, 5432]
+    );
+}
+
+#[test]
+fn test_port_mappings() {
+    assert_eq!(
+        port_mappings("0.0.0.0:15432->5432/tcp, 6379/tcp"),
+        vec![(Some(15432), 5432), (None, 6379)]
+    );
+    assert_eq!(port_mappings(""), vec![]);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
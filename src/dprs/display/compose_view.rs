@@ -1,6 +1,13 @@
-// The compose_view module implements a view for displaying and managing Docker Compose projects.
-// It groups containers by their compose project using the com.docker.compose.project.working_dir label,
-// allowing project-level operations like restarting or stopping entire projects at once.
+// The compose_view module implements a view for displaying and managing
+// Docker Compose projects. It groups containers by their compose project
+// using the com.docker.compose.project.working_dir label, allowing
+// project-level operations like restarting or stopping entire projects at
+// once. The grouping reads the labels already fetched with the container
+// list and is done once per refresh. Each project is followed by a row per
+// service, grouped by the com.docker.compose.service label, with its
+// replica count. The services a project declares are read from its compose
+// files, so services that are stopped or were never created are listed as
+// not running.
 
 use ratatui::{
     backend::Backend,
@@ -11,83 +18,339 @@ use ratatui::{
     Frame,
 };
 
-use crate::dprs::app::state_machine::AppState;
+use crate::dprs::app::actions::shell_quote;
+use crate::dprs::app::state_machine::{AppState, Container};
+use crate::dprs::commands::spec::container_state;
 use crate::shared::config::Config;
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub(crate) const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+pub(crate) const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+pub(crate) const COMPOSE_WORKING_DIR_LABEL: &str = "com.docker.compose.project.working_dir";
+pub(crate) const COMPOSE_CONFIG_FILES_LABEL: &str = "com.docker.compose.project.config_files";
+
+/// The files `docker compose` looks for when none is given.
+const DEFAULT_COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
 
 #[derive(Clone, Debug)]
 pub struct ComposeProject {
     pub project_name: String,
     pub working_dir: String,
+    /// The compose files the project was started from, per the
+    /// com.docker.compose.project.config_files label.
+    pub config_files: Vec<String>,
+    pub containers: Vec<String>,
+    /// The services declared in the compose files, then any others its
+    /// containers belong to.
+    pub services: Vec<ComposeService>,
+}
+
+/// A service of a compose project and its replicas.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposeService {
+    pub name: String,
     pub containers: Vec<String>,
+    pub running: usize,
+}
+
+impl ComposeService {
+    pub fn replicas(&self) -> usize {
+        self.containers.len()
+    }
+}
+
+/// A line of the compose view: a project, or a service of the project.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ComposeRow {
+    Project(usize),
+    Service(usize, usize),
 }
 
 impl ComposeProject {
     pub fn container_count(&self) -> usize {
         self.containers.len()
     }
-}
 
-pub fn group_containers_by_project(app_state: &AppState) -> Vec<ComposeProject> {
-    use std::process::Command;
+    /// The `docker compose` options naming the project.
+    pub fn compose_args(&self) -> String {
+        compose_args(&self.project_name, &self.working_dir, &self.config_files)
+    }
+
+    /// A `docker compose` command line for the project, quoted for `sh`.
+    pub fn compose_command(&self, args: &str) -> String {
+        format!("docker compose {} {}", self.compose_args(), args)
+    }
 
+    pub fn service(&self, name: &str) -> Option<&ComposeService> {
+        self.services.iter().find(|service| service.name == name)
+    }
+}
+
+/// Groups containers into compose projects by their compose labels, read
+/// from the labels gathered when the container list was refreshed. The
+/// services each project declares are read from its compose files. This is
+/// run once per refresh; the view and the project actions use the result
+/// kept in `AppState::compose_projects`.
+pub fn group_containers_by_project(containers: &[Container]) -> Vec<ComposeProject> {
     let mut projects: HashMap<String, ComposeProject> = HashMap::new();
 
-    for container in &app_state.containers {
-        // Get compose project labels from docker inspect
-        let output = Command::new("docker")
-            .args([
-                "inspect",
-                "--format",
-                "{{index .Config.Labels \"com.docker.compose.project.working_dir\"}}|{{index .Config.Labels \"com.docker.compose.project\"}}",
-                &container.name,
-            ])
-            .output();
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                let output_str = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = output_str.trim().split('|').collect();
-
-                if parts.len() == 2 {
-                    let working_dir = parts[0].to_string();
-                    let project_name = parts[1].to_string();
-
-                    // Only include if both labels exist (not empty)
-                    if !working_dir.is_empty() && !project_name.is_empty() {
-                        projects
-                            .entry(working_dir.clone())
-                            .or_insert_with(|| ComposeProject {
-                                project_name: project_name.clone(),
-                                working_dir: working_dir.clone(),
-                                containers: Vec::new(),
-                            })
-                            .containers
-                            .push(container.name.clone());
-                    }
-                }
-            }
+    for container in containers {
+        let label = |key: &str| container.labels.get(key).cloned().unwrap_or_default();
+        let working_dir = label(COMPOSE_WORKING_DIR_LABEL);
+        let project_name = label(COMPOSE_PROJECT_LABEL);
+
+        // Only include if both labels exist (not empty)
+        if !working_dir.is_empty() && !project_name.is_empty() {
+            projects
+                .entry(working_dir.clone())
+                .or_insert_with(|| ComposeProject {
+                    project_name,
+                    working_dir,
+                    config_files: split_config_files(&label(COMPOSE_CONFIG_FILES_LABEL)),
+                    containers: Vec::new(),
+                    services: Vec::new(),
+                })
+                .containers
+                .push(container.name.clone());
         }
     }
 
     let mut project_list: Vec<ComposeProject> = projects.into_values().collect();
+    for project in &mut project_list {
+        let declared = declared_services(&project.config_files, &project.working_dir);
+        let members: Vec<&Container> = containers
+            .iter()
+            .filter(|c| project.containers.contains(&c.name))
+            .collect();
+        project.services = group_services(declared, &members);
+    }
     project_list.sort_by(|a, b| a.project_name.cmp(&b.project_name));
     project_list
 }
 
+/// Groups a project's containers by the com.docker.compose.service label,
+/// with the declared services first so those without containers are kept.
+pub fn group_services(declared: Vec<String>, containers: &[&Container]) -> Vec<ComposeService> {
+    let mut services: Vec<ComposeService> = declared
+        .into_iter()
+        .map(|name| ComposeService {
+            name,
+            containers: Vec::new(),
+            running: 0,
+        })
+        .collect();
+    for container in containers {
+        let Some(name) = container.labels.get(COMPOSE_SERVICE_LABEL) else {
+            continue;
+        };
+        let index = match services.iter().position(|s| &s.name == name) {
+            Some(index) => index,
+            None => {
+                services.push(ComposeService {
+                    name: name.clone(),
+                    containers: Vec::new(),
+                    running: 0,
+                });
+                services.len() - 1
+            }
+        };
+        let service = &mut services[index];
+        service.containers.push(container.name.clone());
+        if container_state(&container.status) == "running" {
+            service.running += 1;
+        }
+    }
+    services
+}
+
+/// The lines of the compose view: each project followed by its services.
+pub fn compose_rows(projects: &[ComposeProject]) -> Vec<ComposeRow> {
+    projects
+        .iter()
+        .enumerate()
+        .flat_map(|(p, project)| {
+            std::iter::once(ComposeRow::Project(p))
+                .chain((0..project.services.len()).map(move |s| ComposeRow::Service(p, s)))
+        })
+        .collect()
+}
+
+/// The name a row goes by in messages, and the containers it covers.
+pub fn row_containers(projects: &[ComposeProject], row: ComposeRow) -> (String, Vec<String>) {
+    match row {
+        ComposeRow::Project(p) => (
+            projects[p].project_name.clone(),
+            projects[p].containers.clone(),
+        ),
+        ComposeRow::Service(p, s) => {
+            let service = &projects[p].services[s];
+            (
+                format!("{}/{}", projects[p].project_name, service.name),
+                service.containers.clone(),
+            )
+        }
+    }
+}
+
+/// The rows at `indices`, leaving out services whose project row is
+/// among them.
+pub fn rows_at(projects: &[ComposeProject], indices: &[usize]) -> Vec<ComposeRow> {
+    let rows = compose_rows(projects);
+    let picked: Vec<ComposeRow> = indices
+        .iter()
+        .filter_map(|&i| rows.get(i).copied())
+        .collect();
+    picked
+        .iter()
+        .copied()
+        .filter(|row| match row {
+            ComposeRow::Service(p, _) => !picked.contains(&ComposeRow::Project(*p)),
+            ComposeRow::Project(_) => true,
+        })
+        .collect()
+}
+
+/// The project and service under the cursor in the compose view, or
+/// elsewhere the project of the selected container.
+pub fn selected_target(app_state: &AppState) -> Option<(ComposeProject, Option<ComposeService>)> {
+    let projects = &app_state.compose_projects;
+    if app_state.compose_view_mode {
+        let index = app_state.list_state.selected()?;
+        match compose_rows(projects).get(index)? {
+            ComposeRow::Project(p) => Some((projects[*p].clone(), None)),
+            ComposeRow::Service(p, s) => Some((
+                projects[*p].clone(),
+                Some(projects[*p].services[*s].clone()),
+            )),
+        }
+    } else {
+        let name = &app_state.get_selected_container()?.name;
+        projects
+            .iter()
+            .find(|project| project.containers.contains(name))
+            .map(|project| (project.clone(), None))
+    }
+}
+
+/// The project under the cursor in the compose view, or elsewhere the
+/// project of the selected container.
+pub fn selected_project(app_state: &AppState) -> Option<ComposeProject> {
+    selected_target(app_state).map(|(project, _)| project)
+}
+
+/// The `docker compose` options naming a project, quoted for `sh`. Without
+/// config files compose finds the file in the project directory.
+pub fn compose_args(project_name: &str, working_dir: &str, config_files: &[String]) -> String {
+    let mut args = format!("-p {}", shell_quote(project_name));
+    if config_files.is_empty() {
+        args.push_str(&format!(
+            " --project-directory {}",
+            shell_quote(working_dir)
+        ));
+    }
+    for file in config_files {
+        args.push_str(&format!(" -f {}", shell_quote(file)));
+    }
+    args
+}
+
+/// Splits the comma-separated com.docker.compose.project.config_files label.
+pub fn split_config_files(label: &str) -> Vec<String> {
+    label
+        .split(',')
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The services declared across the project's compose files, in the order
+/// they first appear. Relative paths are taken from `working_dir`, and
+/// without config files the default file names there are tried.
+pub fn declared_services(config_files: &[String], working_dir: &str) -> Vec<String> {
+    let dir = Path::new(working_dir);
+    let files: Vec<_> = if config_files.is_empty() {
+        DEFAULT_COMPOSE_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+            .take(1)
+            .collect()
+    } else {
+        config_files.iter().map(|file| dir.join(file)).collect()
+    };
+
+    let mut services = Vec::new();
+    for file in files {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for service in parse_compose_services(&content) {
+            if !services.contains(&service) {
+                services.push(service);
+            }
+        }
+    }
+    services
+}
+
+/// Reads the keys of the top-level `services:` mapping of a compose file.
+/// This is a line-based reading of the block style compose files are
+/// written in, not a full YAML parser.
+pub fn parse_compose_services(content: &str) -> Vec<String> {
+    let mut services = Vec::new();
+    let mut in_services = false;
+    let mut service_indent = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_end();
+        let text = trimmed.trim_start();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let indent = trimmed.len() - text.len();
+
+        if indent == 0 {
+            in_services = text.strip_suffix(':').map(str::trim_end) == Some("services");
+            service_indent = None;
+            continue;
+        }
+        if !in_services || *service_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+
+        let Some((key, _)) = text.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+        if !key.is_empty() && !key.starts_with('-') {
+            services.push(key.to_string());
+        }
+    }
+    services
+}
+
 pub fn render_compose_view<B: Backend>(
     f: &mut Frame,
     app_state: &mut AppState,
     area: Rect,
     config: &Config,
 ) {
-    let projects = group_containers_by_project(app_state);
+    let projects = &app_state.compose_projects;
+    let rows = compose_rows(projects);
 
-    let items: Vec<ListItem> = projects
+    let items: Vec<ListItem> = rows
         .iter()
         .enumerate()
-        .map(|(index, project)| {
-            // Check if this project is visually selected
+        .map(|(index, row)| {
+            // Check if this row is visually selected
             let is_visual_selected = app_state
                 .visual_selection
                 .as_ref()
@@ -99,44 +362,37 @@ pub fn render_compose_view<B: Backend>(
                 base_style = base_style.bg(config.get_color("background_selection_orange"));
             }
 
-            let header = Line::from(vec![
-                Span::styled(
-                    &project.project_name,
-                    base_style
-                        .fg(config.get_color("container_name"))
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(" (", base_style),
-                Span::styled(
-                    format!("{} containers", project.container_count()),
-                    base_style.fg(config.get_color("container_image")),
-                ),
-                Span::styled(")", base_style),
-            ]);
-
-            let working_dir = Line::from(vec![
-                Span::styled("Dir:    ", base_style),
-                Span::styled(
-                    &project.working_dir,
-                    base_style.fg(config.get_color("container_status")),
-                ),
-            ]);
-
-            let containers_label = Line::from(vec![Span::styled(
-                format!("Services: {}", project.containers.join(", ")),
-                base_style.fg(config.get_color("container_ip")),
-            )]);
-
-            let blank = Line::from(vec![Span::styled(" ", base_style)]);
-
-            ListItem::new(vec![header, working_dir, containers_label, blank]).style(base_style)
+            let mut lines = match *row {
+                ComposeRow::Project(p) => project_lines(&projects[p], base_style, config),
+                ComposeRow::Service(p, s) => {
+                    let last = s + 1 == projects[p].services.len();
+                    vec![service_line(
+                        &projects[p].services[s],
+                        last,
+                        base_style,
+                        config,
+                    )]
+                }
+            };
+
+            // A blank line closes each project
+            let next_is_project = !matches!(rows.get(index + 1), Some(ComposeRow::Service(..)));
+            if next_is_project {
+                lines.push(Line::from(vec![Span::styled(" ", base_style)]));
+            }
+
+            ListItem::new(lines).style(base_style)
         })
         .collect();
 
     let list = List::new(items)
         .block(
             Block::default()
-                .borders(Borders::ALL)
+                .borders(if config.layout.show_borders {
+                    Borders::ALL
+                } else {
+                    Borders::NONE
+                })
                 .title("Docker Compose Projects")
                 .border_type(BorderType::Rounded)
                 .border_style(
@@ -157,4 +413,71 @@ pub fn render_compose_view<B: Backend>(
     f.render_stateful_widget(list, area, &mut app_state.list_state);
 }
 
+#[cfg(test)]
+mod tests;
+
+fn project_lines<'a>(
+    project: &'a ComposeProject,
+    base_style: Style,
+    config: &Config,
+) -> Vec<Line<'a>> {
+    let header = Line::from(vec![
+        Span::styled(
+            &project.project_name,
+            base_style
+                .fg(config.get_color("container_name"))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(" (", base_style),
+        Span::styled(
+            format!(
+                "{} containers, {} services",
+                project.container_count(),
+                project.services.len()
+            ),
+            base_style.fg(config.get_color("container_image")),
+        ),
+        Span::styled(")", base_style),
+    ]);
+
+    let working_dir = Line::from(vec![
+        Span::styled("Dir:    ", base_style),
+        Span::styled(
+            &project.working_dir,
+            base_style.fg(config.get_color("container_status")),
+        ),
+    ]);
+
+    vec![header, working_dir]
+}
+
+/// A service row: its name and how many of its replicas are running.
+fn service_line<'a>(
+    service: &'a ComposeService,
+    last: bool,
+    base_style: Style,
+    config: &Config,
+) -> Line<'a> {
+    let (state, color) = if service.running == 0 {
+        ("not running".to_string(), "hotkey_red")
+    } else {
+        (
+            format!("{}/{} running", service.running, service.replicas()),
+            if service.running < service.replicas() {
+                "hotkey_yellow"
+            } else {
+                "container_ip"
+            },
+        )
+    };
+    Line::from(vec![
+        Span::styled(if last { "  └ " } else { "  ├ " }, base_style),
+        Span::styled(
+            format!("{:<24} ", service.name),
+            base_style.fg(config.get_color("container_name")),
+        ),
+        Span::styled(state, base_style.fg(config.get_color(color))),
+    ])
+}
+
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,219 @@
+// The tests module for the compose view checks that declared services are
+// read from compose files, that containers are grouped into projects by
+// their labels and into services with their running replicas counted, and
+// the rows the view shows.
+
+use super::*;
+
+const COMPOSE_FILE: &str = r#"
+# The shop stack
+name: shop
+
+services:
+  web:
+    image: nginx
+    ports:
+      - "8080:80"
+    environment:
+      services: not-a-service
+  "db":
+    image: postgres
+
+  # Started by hand
+  worker:
+    build: .
+
+volumes:
+  data:
+"#;
+
+#[test]
+fn test_parse_compose_services() {
+    assert_eq!(
+        parse_compose_services(COMPOSE_FILE),
+        vec!["web", "db", "worker"]
+    );
+    assert!(parse_compose_services("version: '3'\n").is_empty());
+}
+
+#[test]
+fn test_declared_services() {
+    let dir = std::env::temp_dir().join(format!("dprs-compose-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("compose.yml"), COMPOSE_FILE).unwrap();
+    fs::write(
+        dir.join("override.yml"),
+        "services:\n  web:\n    restart: always\n  sidecar:\n    image: busybox\n",
+    )
+    .unwrap();
+    let working_dir = dir.to_str().unwrap();
+
+    assert_eq!(
+        declared_services(&[], working_dir),
+        vec!["web", "db", "worker"]
+    );
+    let files = vec![
+        dir.join("compose.yml").to_string_lossy().into_owned(),
+        "override.yml".to_string(),
+        "missing.yml".to_string(),
+    ];
+    assert_eq!(
+        declared_services(&files, working_dir),
+        vec!["web", "db", "worker", "sidecar"]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn container(name: &str, service: &str, status: &str) -> Container {
+    Container {
+        name: name.to_string(),
+        status: status.to_string(),
+        labels: HashMap::from([(
+            "com.docker.compose.service".to_string(),
+            service.to_string(),
+        )]),
+        ..Default::default()
+    }
+}
+
+fn service(name: &str, containers: &[&str], running: usize) -> ComposeService {
+    ComposeService {
+        name: name.to_string(),
+        containers: containers.iter().map(|c| c.to_string()).collect(),
+        running,
+    }
+}
+
+#[test]
+fn test_group_services() {
+    let containers = [
+        container("shop-web-1", "web", "Up 2 hours"),
+        container("shop-web-2", "web", "Exited (0) 1 minute ago"),
+        container("shop-db-1", "db", "Up 2 hours"),
+        container("shop-cron-1", "cron", "Up 2 hours"),
+    ];
+    let containers: Vec<&Container> = containers.iter().collect();
+    let declared = vec!["db".to_string(), "web".to_string(), "worker".to_string()];
+    assert_eq!(
+        group_services(declared, &containers),
+        vec![
+            service("db", &["shop-db-1"], 1),
+            service("web", &["shop-web-1", "shop-web-2"], 1),
+            service("worker", &[], 0),
+            service("cron", &["shop-cron-1"], 1),
+        ]
+    );
+}
+
+#[test]
+fn test_group_containers_by_project() {
+    let compose = |name: &str, project: &str, service: &str| {
+        let mut c = container(name, service, "Up 1 hour");
+        let dir = format!("/nonexistent/{}", project);
+        c.labels.extend([
+            (
+                "com.docker.compose.project".to_string(),
+                project.to_string(),
+            ),
+            (
+                "com.docker.compose.project.working_dir".to_string(),
+                dir.clone(),
+            ),
+            (
+                "com.docker.compose.project.config_files".to_string(),
+                format!("{}/compose.yml", dir),
+            ),
+        ]);
+        c
+    };
+    let containers = vec![
+        compose("shop-web-1", "shop", "web"),
+        Container {
+            name: "standalone".to_string(),
+            ..Default::default()
+        },
+        compose("blog-app-1", "blog", "app"),
+        compose("shop-db-1", "shop", "db"),
+    ];
+
+    let projects = group_containers_by_project(&containers);
+    let names: Vec<&str> = projects.iter().map(|p| p.project_name.as_str()).collect();
+    assert_eq!(names, vec!["blog", "shop"]);
+    let shop = &projects[1];
+    assert_eq!(shop.working_dir, "/nonexistent/shop");
+    assert_eq!(shop.config_files, vec!["/nonexistent/shop/compose.yml"]);
+    assert_eq!(shop.containers, vec!["shop-web-1", "shop-db-1"]);
+    assert_eq!(
+        shop.services,
+        vec![
+            service("web", &["shop-web-1"], 1),
+            service("db", &["shop-db-1"], 1)
+        ]
+    );
+}
+
+#[test]
+fn test_compose_rows() {
+    let project = |name: &str, services: Vec<ComposeService>| ComposeProject {
+        project_name: name.to_string(),
+        working_dir: format!("/srv/{}", name),
+        config_files: Vec::new(),
+        containers: services.iter().flat_map(|s| s.containers.clone()).collect(),
+        services,
+    };
+    let projects = vec![
+        project("blog", vec![service("app", &["blog-app-1"], 1)]),
+        project(
+            "shop",
+            vec![
+                service("web", &["shop-web-1", "shop-web-2"], 2),
+                service("db", &["shop-db-1"], 1),
+            ],
+        ),
+    ];
+    assert_eq!(
+        compose_rows(&projects),
+        vec![
+            ComposeRow::Project(0),
+            ComposeRow::Service(0, 0),
+            ComposeRow::Project(1),
+            ComposeRow::Service(1, 0),
+            ComposeRow::Service(1, 1),
+        ]
+    );
+    assert_eq!(
+        row_containers(&projects, ComposeRow::Service(1, 0)),
+        (
+            "shop/web".to_string(),
+            vec!["shop-web-1".to_string(), "shop-web-2".to_string()]
+        )
+    );
+    // A selected project covers its services
+    assert_eq!(
+        rows_at(&projects, &[1, 2, 4, 9]),
+        vec![ComposeRow::Service(0, 0), ComposeRow::Project(1)]
+    );
+}
+
+#[test]
+fn test_compose_command() {
+    let mut project = ComposeProject {
+        project_name: "shop".to_string(),
+        working_dir: "/srv/my shop".to_string(),
+        config_files: vec!["/srv/my shop/compose.yml".to_string()],
+        containers: Vec::new(),
+        services: Vec::new(),
+    };
+    assert_eq!(
+        project.compose_command("up -d web"),
+        "docker compose -p shop -f '/srv/my shop/compose.yml' up -d web"
+    );
+    project.config_files.clear();
+    assert_eq!(
+        project.compose_command("ps"),
+        "docker compose -p shop --project-directory '/srv/my shop' ps"
+    );
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,170 @@
+// The confirm_dialog module implements the confirmation popup shown before
+// destructive batch operations and context menu actions marked `confirm`.
+// It lists the containers an action resolved to, or the command a menu
+// action runs, and holds the pending action until the user accepts with `y`
+// or Enter, or backs out with `n` or Esc. Whether a confirmation is needed
+// at all is decided by the `[confirm]` policy in the config.
+
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+    Frame,
+};
+
+use crate::shared::config::{ActionRunMode, Config};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PendingAction {
+    /// A container command from the command line. `then` holds the rest of
+    /// its `;` chain, which runs once the operation is confirmed.
+    Command {
+        operation: String,
+        containers: Vec<String>,
+        then: String,
+    },
+    BatchStop,
+    BatchRestart,
+    /// A context menu action marked `confirm`, with its expanded command.
+    ContextAction {
+        label: String,
+        command: String,
+        run: ActionRunMode,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConfirmDialogState {
+    pub active: bool,
+    pub title: String,
+    pub items: Vec<String>,
+    action: Option<PendingAction>,
+}
+
+impl ConfirmDialogState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, title: String, items: Vec<String>, action: PendingAction) {
+        self.active = true;
+        self.title = title;
+        self.items = items;
+        self.action = Some(action);
+    }
+
+    /// Closes the dialog and hands back the action to run.
+    pub fn accept(&mut self) -> Option<PendingAction> {
+        self.active = false;
+        self.items.clear();
+        self.action.take()
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = false;
+        self.items.clear();
+        self.action = None;
+    }
+}
+
+/// Title for a confirmation, e.g. "Stop 3 containers?".
+pub fn confirm_title(operation: &str, count: usize) -> String {
+    let verb = match operation {
+        "rm" | "remove" => "Remove",
+        "kill" => "Kill",
+        "stop" => "Stop",
+        "restart" => "Restart",
+        other => other,
+    };
+    format!(
+        "{} {} container{}?",
+        verb,
+        count,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+pub fn render_confirm_dialog<B: Backend>(
+    f: &mut Frame,
+    dialog: &ConfirmDialogState,
+    config: &Config,
+) {
+    if !dialog.active {
+        return;
+    }
+
+    // Borders, padding, the blank line and the footer take 6 rows
+    let max_rows = f.area().height.saturating_sub(8).max(1) as usize;
+    let height = (dialog.items.len().min(max_rows) + 6) as u16;
+    let area = centered_rect(60, height, f.area());
+    f.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let shown = if dialog.items.len() > max_rows {
+        max_rows.saturating_sub(1)
+    } else {
+        dialog.items.len()
+    };
+    for item in &dialog.items[..shown] {
+        lines.push(Line::from(format!("  {}", item)));
+    }
+    if shown < dialog.items.len() {
+        lines.push(Line::from(Span::styled(
+            format!("  ... and {} more", dialog.items.len() - shown),
+            Style::default().fg(config.get_color("text_dim")),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            "y",
+            Style::default()
+                .fg(config.get_color("message_warning"))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("/Enter: confirm   "),
+        Span::styled(
+            "n",
+            Style::default()
+                .fg(config.get_color("message_warning"))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("/Esc: cancel"),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", dialog.title))
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.get_color("message_error")))
+            .style(Style::default().bg(config.get_color("background_dark")))
+            .padding(Padding::uniform(1)),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn centered_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
+    let height = height.min(r.height);
+    let vertical = Layout::vertical([
+        Constraint::Length((r.height - height) / 2),
+        Constraint::Length(height),
+        Constraint::Min(0),
+    ])
+    .split(r);
+
+    Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
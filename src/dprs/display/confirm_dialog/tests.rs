@@ -0,0 +1,31 @@
+// The tests module for the confirmation dialog validates opening, accepting
+// and cancelling a pending action.
+
+use super::*;
+
+#[test]
+fn test_accept_and_cancel() {
+    let mut dialog = ConfirmDialogState::new();
+    dialog.open(
+        confirm_title("rm", 2),
+        vec!["web".to_string(), "db".to_string()],
+        PendingAction::BatchStop,
+    );
+    assert!(dialog.active);
+    assert_eq!(dialog.title, "Remove 2 containers?");
+    assert_eq!(dialog.accept(), Some(PendingAction::BatchStop));
+    assert!(!dialog.active);
+    assert_eq!(dialog.accept(), None);
+
+    dialog.open(
+        confirm_title("stop", 1),
+        vec![],
+        PendingAction::BatchRestart,
+    );
+    assert_eq!(dialog.title, "Stop 1 container?");
+    dialog.cancel();
+    assert!(!dialog.active);
+    assert_eq!(dialog.accept(), None);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -1,20 +1,40 @@
 // The context_menu module implements a popup context menu for container and
 // compose project actions. The menu is triggered by pressing '.' on a selected
-// item and displays available actions based on matchers and state.
+// item and displays the actions whose matchers accept the target. Matchers
+// test the name, image, labels, status, health, ports and networks of a
+// container. Others test that the target is a compose project or a service
+// row, or match the project's name or working directory. Matchers can be
+// combined with `all`, `any` and `not`. `enabled_when` takes a state or a
+// container spec such as `status:exited or health:unhealthy`. The actions'
+// commands have their placeholders, such as `{name}`, `{service}`,
+// `{label:KEY}` or `{port:5432}`, filled in with shell-quoted values when
+// the menu opens. An action whose placeholder has no value is shown
+// disabled with the reason. An action may declare prompts, which open a
+// small form before it runs and fill placeholders of the same name. The
+// chosen command runs in the background or the foreground, as configured.
 
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, Padding},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, Padding, Paragraph},
     Frame,
 };
 
+use crate::dprs::app::actions::shell_quote;
 use crate::dprs::app::state_machine::Container;
-use crate::dprs::display::compose_view::ComposeProject;
-use crate::shared::config::{Config, ContextMenuAction, ContextMenuMatcher};
+use crate::dprs::commands::spec::{
+    container_health, container_ports, parse_spec, port_mappings, SpecExpr, Term, HEALTH_VALUES,
+    STATUS_VALUES,
+};
+use crate::dprs::display::compose_view::{
+    compose_args, split_config_files, ComposeProject, ComposeService, COMPOSE_CONFIG_FILES_LABEL,
+    COMPOSE_SERVICE_LABEL, COMPOSE_WORKING_DIR_LABEL,
+};
+use crate::shared::config::{ActionPrompt, Config, ContextMenuAction, ContextMenuMatcher};
 use regex::Regex;
+use std::collections::HashMap;
 use std::process::Command;
 
 #[derive(Clone, Debug)]
@@ -24,6 +44,71 @@ pub struct ContextMenuState {
     pub actions: Vec<ContextMenuAction>,
     pub target_container: Option<Container>,
     pub target_project: Option<ComposeProject>,
+    /// The service, when the menu is opened on a service row of the
+    /// compose view.
+    pub target_service: Option<ComposeService>,
+    /// Each action's expanded command, or why it is disabled.
+    pub commands: Vec<Result<String, String>>,
+    /// The target container's environment, read only when an action uses
+    /// an `{env:KEY}` placeholder.
+    env: Option<HashMap<String, String>>,
+    cache: MatcherCache,
+    /// The form asking for the selected action's prompts, while open.
+    pub form: Option<PromptForm>,
+}
+
+/// A form with one text field per prompt of an action.
+#[derive(Clone, Debug)]
+pub struct PromptForm {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+    pub focused: usize,
+}
+
+impl PromptForm {
+    pub fn new(title: &str, prompts: &[ActionPrompt]) -> Self {
+        Self {
+            title: title.to_string(),
+            fields: prompts
+                .iter()
+                .map(|prompt| (prompt.name.clone(), prompt.default.clone()))
+                .collect(),
+            focused: 0,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.focused = (self.focused + 1) % self.fields.len().max(1);
+    }
+
+    pub fn previous_field(&mut self) {
+        let count = self.fields.len().max(1);
+        self.focused = (self.focused + count - 1) % count;
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if let Some((_, value)) = self.fields.get_mut(self.focused) {
+            value.push(c);
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        if let Some((_, value)) = self.fields.get_mut(self.focused) {
+            value.pop();
+        }
+    }
+
+    pub fn values(&self) -> HashMap<String, String> {
+        self.fields.iter().cloned().collect()
+    }
+}
+
+fn prompt_defaults(action: &ContextMenuAction) -> HashMap<String, String> {
+    action
+        .prompts
+        .iter()
+        .map(|prompt| (prompt.name.clone(), prompt.default.clone()))
+        .collect()
 }
 
 impl Default for ContextMenuState {
@@ -40,6 +125,11 @@ impl ContextMenuState {
             actions: Vec::new(),
             target_container: None,
             target_project: None,
+            target_service: None,
+            commands: Vec::new(),
+            env: None,
+            cache: MatcherCache::default(),
+            form: None,
         }
     }
 
@@ -48,28 +138,85 @@ impl ContextMenuState {
         container: Option<Container>,
         project: Option<ComposeProject>,
         config: &Config,
+    ) {
+        self.open(container, project, None, config);
+    }
+
+    /// Opens the menu on a service of a compose project.
+    pub fn activate_service(
+        &mut self,
+        project: ComposeProject,
+        service: ComposeService,
+        config: &Config,
+    ) {
+        self.open(None, Some(project), Some(service), config);
+    }
+
+    fn open(
+        &mut self,
+        container: Option<Container>,
+        project: Option<ComposeProject>,
+        service: Option<ComposeService>,
+        config: &Config,
     ) {
         self.active = true;
         self.selected_index = 0;
-        self.target_container = container.clone();
-        self.target_project = project.clone();
+        self.target_container = container;
+        self.target_project = project;
+        self.target_service = service;
 
         // Filter actions based on matchers and enabled_when conditions
+        let target = Target {
+            container: self.target_container.as_ref(),
+            project: self.target_project.as_ref(),
+            service: self.target_service.as_ref(),
+        };
+        let cache = &mut self.cache;
         self.actions = config
             .context_menu
             .actions
             .iter()
-            .filter(|action| self.matches_action(action, &container, &project))
+            .filter(|action| cache.matches_action(action, target))
             .cloned()
             .collect();
+
+        self.env = match &self.target_container {
+            Some(c) if self.actions.iter().any(|a| a.command.contains("{env:")) => {
+                container_env(&c.name).ok()
+            }
+            _ => None,
+        };
+        // Prompted values are not known yet, so their defaults stand in
+        self.commands = self
+            .actions
+            .iter()
+            .map(|action| self.expand_command(&action.command, &prompt_defaults(action)))
+            .collect();
+    }
+
+    /// The menu's title, naming what it was opened on.
+    pub fn title(&self) -> &'static str {
+        if self.target_service.is_some() {
+            "Context Menu - Compose Service"
+        } else if self.target_project.is_some() {
+            "Context Menu - Compose Project"
+        } else if self.target_container.is_some() {
+            "Context Menu - Container"
+        } else {
+            "Context Menu"
+        }
     }
 
     pub fn deactivate(&mut self) {
         self.active = false;
         self.selected_index = 0;
         self.actions.clear();
+        self.commands.clear();
+        self.form = None;
+        self.env = None;
         self.target_container = None;
         self.target_project = None;
+        self.target_service = None;
     }
 
     pub fn next(&mut self) {
@@ -92,137 +239,465 @@ impl ContextMenuState {
         self.actions.get(self.selected_index)
     }
 
-    fn matches_action(
+    /// The selected action's command with its placeholders filled in, or
+    /// why it cannot run.
+    pub fn expanded_command(&self) -> Option<Result<String, String>> {
+        self.commands.get(self.selected_index).cloned()
+    }
+
+    /// Opens the form for the selected action's prompts, filled with their
+    /// defaults. Returns false if the action has none.
+    pub fn open_form(&mut self) -> bool {
+        let Some(action) = self.get_selected_action() else {
+            return false;
+        };
+        if action.prompts.is_empty() {
+            return false;
+        }
+        self.form = Some(PromptForm::new(&action.label, &action.prompts));
+        true
+    }
+
+    /// The selected action's command with the form's values filled in.
+    pub fn submit_form(&mut self) -> Option<Result<String, String>> {
+        let form = self.form.take()?;
+        let action = self.get_selected_action()?;
+        Some(self.expand_command(&action.command, &form.values()))
+    }
+
+    /// Fills in an action's placeholders, taking prompted values first.
+    /// Every value is shell-quoted, so a container name cannot inject shell
+    /// syntax. A placeholder without a value gives the reason instead.
+    fn expand_command(
         &self,
-        action: &ContextMenuAction,
-        container: &Option<Container>,
-        project: &Option<ComposeProject>,
-    ) -> bool {
-        // If no matchers, action is available for all
-        if action.matchers.is_empty() {
-            // Check enabled_when condition for general actions
-            if let Some(ref condition) = action.enabled_when {
-                if let Some(ref c) = container {
-                    return self.check_enabled_condition(condition, c);
-                }
-                return false;
-            }
-            return true;
+        command: &str,
+        inputs: &HashMap<String, String>,
+    ) -> Result<String, String> {
+        expand_placeholders(command, |name| match inputs.get(name) {
+            Some(value) => Some(Ok(shell_quote(value))),
+            None => self.placeholder(name),
+        })
+    }
+
+    /// The shell words for a placeholder, or None if `name` is not one.
+    fn placeholder(&self, name: &str) -> Option<Result<String, String>> {
+        let container = self.target_container.as_ref();
+        let needs_container = || format!("{{{}}} needs a container", name);
+        let quoted = |value: Option<String>, missing: String| match value {
+            Some(value) if !value.is_empty() => Ok(shell_quote(&value)),
+            _ => Err(missing),
+        };
+
+        if let Some(key) = name.strip_prefix("label:") {
+            let value = container.map(|c| c.labels.get(key).cloned());
+            return Some(match value {
+                Some(value) => quoted(value, format!("no label {}", key)),
+                None => Err(needs_container()),
+            });
+        }
+        if let Some(key) = name.strip_prefix("env:") {
+            let value = container.map(|_| self.env.as_ref().map(|env| env.get(key).cloned()));
+            return Some(match value {
+                Some(Some(value)) => quoted(value, format!("no environment variable {}", key)),
+                Some(None) => Err("could not read the container's environment".to_string()),
+                None => Err(needs_container()),
+            });
+        }
+        if let Some(port) = name.strip_prefix("port:") {
+            let Ok(port) = port.parse::<u16>() else {
+                return Some(Err(format!("{{{}}} is not a port", name)));
+            };
+            let host_port = container.map(|c| {
+                port_mappings(&c.ports)
+                    .into_iter()
+                    .find(|(_, container_port)| *container_port == port)
+                    .and_then(|(host, _)| host)
+            });
+            return Some(match host_port {
+                Some(host_port) => quoted(
+                    host_port.map(|p| p.to_string()),
+                    format!("port {} is not published", port),
+                ),
+                None => Err(needs_container()),
+            });
         }
 
-        // Check if any matcher matches
-        for matcher in &action.matchers {
-            match matcher {
-                ContextMenuMatcher::NamePattern { pattern } => {
-                    if let Some(ref c) = container {
-                        if let Ok(re) = Regex::new(pattern) {
-                            if re.is_match(&c.name) {
-                                // Check enabled_when condition
-                                if let Some(ref condition) = action.enabled_when {
-                                    return self.check_enabled_condition(condition, c);
-                                }
-                                return true;
-                            }
-                        }
-                    }
+        let project = self.target_project.as_ref();
+        let value = match name {
+            "name" | "image" | "ip" | "id" | "short_id" | "url" => {
+                let Some(c) = container else {
+                    return Some(Err(needs_container()));
+                };
+                match name {
+                    "name" => quoted(Some(c.name.clone()), "no name".to_string()),
+                    "image" => quoted(Some(c.image.clone()), "no image".to_string()),
+                    "ip" => quoted(Some(c.ip_address.clone()), "no IP address".to_string()),
+                    "id" => quoted(Some(c.container_id.clone()), "no container id".to_string()),
+                    "short_id" => quoted(
+                        Some(c.container_id.chars().take(12).collect()),
+                        "no container id".to_string(),
+                    ),
+                    _ => quoted(
+                        container_url(c),
+                        "no published port or IP address".to_string(),
+                    ),
                 }
-                ContextMenuMatcher::ImagePattern { pattern } => {
-                    if let Some(ref c) = container {
-                        if let Ok(re) = Regex::new(pattern) {
-                            if re.is_match(&c.image) {
-                                // Check enabled_when condition
-                                if let Some(ref condition) = action.enabled_when {
-                                    return self.check_enabled_condition(condition, c);
-                                }
-                                return true;
-                            }
-                        }
-                    }
-                }
-                ContextMenuMatcher::LabelPattern { label, value } => {
-                    if let Some(ref c) = container {
-                        if self.check_container_label(&c.name, label, value.as_deref()) {
-                            // Check enabled_when condition
-                            if let Some(ref condition) = action.enabled_when {
-                                return self.check_enabled_condition(condition, c);
-                            }
-                            return true;
-                        }
-                    }
+            }
+            "service" => quoted(
+                self.target_service
+                    .as_ref()
+                    .map(|s| s.name.clone())
+                    .or_else(|| {
+                        container.and_then(|c| c.labels.get(COMPOSE_SERVICE_LABEL).cloned())
+                    }),
+                "not a compose service".to_string(),
+            ),
+            "project" => quoted(
+                project
+                    .map(|p| p.project_name.clone())
+                    .or_else(|| container.and_then(|c| c.compose_project.clone())),
+                "not part of a compose project".to_string(),
+            ),
+            "dir" => quoted(
+                project.map(|p| p.working_dir.clone()).or_else(|| {
+                    container.and_then(|c| c.labels.get(COMPOSE_WORKING_DIR_LABEL).cloned())
+                }),
+                "not part of a compose project".to_string(),
+            ),
+            "compose_file" => {
+                let files = match (project, container) {
+                    (Some(p), _) => p.config_files.clone(),
+                    (None, Some(c)) => c
+                        .labels
+                        .get(COMPOSE_CONFIG_FILES_LABEL)
+                        .map(|label| split_config_files(label))
+                        .unwrap_or_default(),
+                    (None, None) => Vec::new(),
+                };
+                if files.is_empty() {
+                    Err("no compose file recorded".to_string())
+                } else {
+                    // Several files are passed as `-f a -f b`
+                    Ok(files
+                        .iter()
+                        .map(|file| shell_quote(file))
+                        .collect::<Vec<_>>()
+                        .join(" -f "))
                 }
-                ContextMenuMatcher::ComposeProject => {
-                    if project.is_some() {
-                        return true;
+            }
+            // The project options for `docker compose`, the way the compose
+            // view runs its own commands
+            "compose_args" => match (project, container) {
+                (Some(p), _) => Ok(p.compose_args()),
+                (None, Some(c)) => match &c.compose_project {
+                    Some(name) => {
+                        let label =
+                            |key: &str| c.labels.get(key).map(String::as_str).unwrap_or_default();
+                        Ok(compose_args(
+                            name,
+                            label(COMPOSE_WORKING_DIR_LABEL),
+                            &split_config_files(label(COMPOSE_CONFIG_FILES_LABEL)),
+                        ))
                     }
+                    None => Err("not part of a compose project".to_string()),
+                },
+                (None, None) => Err("not part of a compose project".to_string()),
+            },
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Compiled matcher patterns and `enabled_when` conditions, kept across
+/// menu openings. Matchers read the container data from the last refresh,
+/// so opening the menu runs no docker commands for them. Patterns that do
+/// not compile are cached as None and never match.
+#[derive(Clone, Debug, Default)]
+struct MatcherCache {
+    regexes: HashMap<String, Option<Regex>>,
+    conditions: HashMap<String, Option<SpecExpr>>,
+}
+
+/// What the menu was opened on: a container, a compose project, or a
+/// service of a project.
+#[derive(Clone, Copy)]
+struct Target<'a> {
+    container: Option<&'a Container>,
+    project: Option<&'a ComposeProject>,
+    service: Option<&'a ComposeService>,
+}
+
+impl MatcherCache {
+    /// An action is offered if any of its matchers matches (or it has
+    /// none) and its `enabled_when` condition holds for the container.
+    /// Without a container the condition is skipped for actions whose
+    /// matchers picked out the project, and fails otherwise.
+    fn matches_action(&mut self, action: &ContextMenuAction, target: Target) -> bool {
+        let matched = action.matchers.is_empty()
+            || action
+                .matchers
+                .iter()
+                .any(|matcher| self.matches(matcher, target));
+        if !matched {
+            return false;
+        }
+        match (&action.enabled_when, target.container) {
+            (None, _) => true,
+            (Some(condition), Some(c)) => self.condition_holds(condition, c),
+            (Some(_), None) => target.project.is_some() && !action.matchers.is_empty(),
+        }
+    }
+
+    fn matches(&mut self, matcher: &ContextMenuMatcher, target: Target) -> bool {
+        let Target {
+            container, project, ..
+        } = target;
+        match matcher {
+            ContextMenuMatcher::ComposeProject => project.is_some() && target.service.is_none(),
+            ContextMenuMatcher::ComposeService => target.service.is_some(),
+            ContextMenuMatcher::All { matchers } => {
+                matchers.iter().all(|matcher| self.matches(matcher, target))
+            }
+            ContextMenuMatcher::Any { matchers } => {
+                matchers.iter().any(|matcher| self.matches(matcher, target))
+            }
+            ContextMenuMatcher::Not { matcher } => !self.matches(matcher, target),
+            ContextMenuMatcher::ProjectPattern { pattern } => {
+                let name = match project {
+                    Some(p) => Some(p.project_name.as_str()),
+                    None => container.and_then(|c| c.compose_project.as_deref()),
+                };
+                name.is_some_and(|name| self.regex_matches(pattern, name))
+            }
+            ContextMenuMatcher::ProjectDirPattern { pattern } => {
+                let dir = match project {
+                    Some(p) => Some(p.working_dir.as_str()),
+                    None => container
+                        .and_then(|c| c.labels.get(COMPOSE_WORKING_DIR_LABEL))
+                        .map(String::as_str),
+                };
+                dir.is_some_and(|dir| self.regex_matches(pattern, dir))
+            }
+            _ => container.is_some_and(|c| self.matches_container(matcher, c)),
+        }
+    }
+
+    fn matches_container(&mut self, matcher: &ContextMenuMatcher, c: &Container) -> bool {
+        match matcher {
+            ContextMenuMatcher::NamePattern { pattern } => self.regex_matches(pattern, &c.name),
+            ContextMenuMatcher::ImagePattern { pattern } => self.regex_matches(pattern, &c.image),
+            ContextMenuMatcher::LabelPattern { label, value } => {
+                match (c.labels.get(label), value) {
+                    (Some(actual), Some(expected)) => actual == expected,
+                    (Some(actual), None) => !actual.is_empty(),
+                    (None, _) => false,
                 }
             }
+            ContextMenuMatcher::Status { status } => Term::Status(status.to_string()).matches(c),
+            ContextMenuMatcher::Health { health } => container_health(&c.status) == health,
+            ContextMenuMatcher::Port { port } => container_ports(&c.ports).contains(port),
+            ContextMenuMatcher::NetworkPattern { pattern } => c
+                .networks
+                .iter()
+                .any(|network| self.regex_matches(pattern, network)),
+            _ => false,
         }
+    }
 
-        false
+    fn regex_matches(&mut self, pattern: &str, text: &str) -> bool {
+        self.regexes
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok())
+            .as_ref()
+            .is_some_and(|re| re.is_match(text))
     }
 
-    fn check_enabled_condition(&self, condition: &str, container: &Container) -> bool {
-        match condition {
-            "running" => container.status.to_lowercase().contains("up"),
-            "stopped" => !container.status.to_lowercase().contains("up"),
-            _ => true,
-        }
+    fn condition_holds(&mut self, condition: &str, container: &Container) -> bool {
+        self.conditions
+            .entry(condition.to_string())
+            .or_insert_with(|| parse_condition(condition).ok())
+            .as_ref()
+            .is_some_and(|expr| expr.matches(container))
     }
+}
 
-    fn check_container_label(
-        &self,
-        container_name: &str,
-        label: &str,
-        expected_value: Option<&str>,
-    ) -> bool {
-        let output = Command::new("docker")
-            .args([
-                "inspect",
-                "--format",
-                &format!("{{{{index .Config.Labels \"{}\"}}}}", label),
-                container_name,
-            ])
-            .output();
+/// Parses an `enabled_when` condition: a docker state such as "running",
+/// "stopped" or "paused", a health state such as "unhealthy", or a
+/// container spec like `status:exited or health:unhealthy`.
+pub fn parse_condition(condition: &str) -> Result<SpecExpr, String> {
+    let word = condition.trim();
+    if STATUS_VALUES.contains(&word) {
+        return Ok(SpecExpr::Term(Term::Status(word.to_string())));
+    }
+    if HEALTH_VALUES.contains(&word) {
+        return Ok(SpecExpr::Term(Term::Health(word.to_string())));
+    }
+    parse_spec(condition).map_err(|e| e.to_string())
+}
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if let Some(expected) = expected_value {
-                    return value == expected;
+/// Lists context menu actions whose matchers or conditions can never work:
+/// patterns that are not valid regexes, unknown states and conditions that
+/// do not parse.
+pub fn context_menu_problems(config: &Config) -> Vec<String> {
+    let mut problems = Vec::new();
+    for action in &config.context_menu.actions {
+        let mut report = |problem: String| {
+            problems.push(format!("context_menu: '{}': {}", action.label, problem))
+        };
+        if let Some(condition) = &action.enabled_when {
+            if let Err(e) = parse_condition(condition) {
+                report(format!("enabled_when '{}': {}", condition, e));
+            }
+        }
+        for (index, prompt) in action.prompts.iter().enumerate() {
+            if prompt.name.is_empty() || prompt.name.contains([':', '{', '}']) {
+                report(format!(
+                    "prompt name '{}' cannot be a placeholder",
+                    prompt.name
+                ));
+            } else if PLACEHOLDERS.contains(&prompt.name.as_str()) {
+                report(format!(
+                    "prompt '{}' hides the {{{}}} placeholder",
+                    prompt.name, prompt.name
+                ));
+            } else if action.prompts[..index]
+                .iter()
+                .any(|p| p.name == prompt.name)
+            {
+                report(format!("prompt '{}' is declared twice", prompt.name));
+            }
+        }
+        let mut pending: Vec<&ContextMenuMatcher> = action.matchers.iter().collect();
+        while let Some(matcher) = pending.pop() {
+            match matcher {
+                ContextMenuMatcher::NamePattern { pattern }
+                | ContextMenuMatcher::ImagePattern { pattern }
+                | ContextMenuMatcher::NetworkPattern { pattern }
+                | ContextMenuMatcher::ProjectPattern { pattern }
+                | ContextMenuMatcher::ProjectDirPattern { pattern } => {
+                    if let Err(e) = Regex::new(pattern) {
+                        report(format!("invalid pattern '{}': {}", pattern, e));
+                    }
+                }
+                ContextMenuMatcher::Status { status }
+                    if !STATUS_VALUES.contains(&status.as_str()) =>
+                {
+                    report(format!(
+                        "unknown status '{}' (expected one of {})",
+                        status,
+                        STATUS_VALUES.join(", ")
+                    ));
                 }
-                return !value.is_empty();
+                ContextMenuMatcher::Health { health }
+                    if !HEALTH_VALUES.contains(&health.as_str()) =>
+                {
+                    report(format!(
+                        "unknown health '{}' (expected one of {})",
+                        health,
+                        HEALTH_VALUES.join(", ")
+                    ));
+                }
+                ContextMenuMatcher::All { matchers } | ContextMenuMatcher::Any { matchers } => {
+                    pending.extend(matchers)
+                }
+                ContextMenuMatcher::Not { matcher } => pending.push(matcher),
+                _ => {}
             }
         }
-        false
     }
+    problems
+}
 
-    pub fn execute_selected_action(&self) -> Option<String> {
-        if let Some(action) = self.get_selected_action() {
-            let mut command = action.command.clone();
+/// Placeholders filled in from the target, besides `{label:KEY}`,
+/// `{env:KEY}` and `{port:N}`.
+const PLACEHOLDERS: &[&str] = &[
+    "name",
+    "image",
+    "ip",
+    "id",
+    "short_id",
+    "url",
+    "service",
+    "project",
+    "dir",
+    "compose_file",
+    "compose_args",
+];
+
+/// The address of a container's web interface: its first published port on
+/// localhost, else its IP address.
+fn container_url(container: &Container) -> Option<String> {
+    let mappings = port_mappings(&container.ports);
+    if let Some(host_port) = mappings.iter().find_map(|(host, _)| *host) {
+        return Some(format!("http://localhost:{}", host_port));
+    }
+    let ip = container
+        .ip_address
+        .split([',', ' '])
+        .find(|ip| !ip.is_empty())?;
+    Some(match mappings.first() {
+        Some((_, port)) if *port != 80 => format!("http://{}:{}", ip, port),
+        _ => format!("http://{}", ip),
+    })
+}
 
-            // Replace placeholders with actual values
-            if let Some(ref container) = self.target_container {
-                command = command.replace("{name}", &container.name);
-                command = command.replace("{image}", &container.image);
-                command = command.replace("{ip}", &container.ip_address);
-            }
+/// Reads a container's environment with `docker inspect`.
+fn container_env(container_name: &str) -> Result<HashMap<String, String>, String> {
+    let output = Command::new("docker")
+        .args([
+            "inspect",
+            "--format",
+            "{{json .Config.Env}}",
+            container_name,
+        ])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let vars: Vec<String> = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    Ok(vars
+        .iter()
+        .filter_map(|var| var.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
 
-            if let Some(ref project) = self.target_project {
-                command = command.replace("{project}", &project.project_name);
-                command = command.replace("{dir}", &project.working_dir);
-                // For compose projects, try to find compose file
-                let compose_file = format!("{}/docker-compose.yml", project.working_dir);
-                command = command.replace("{compose_file}", &compose_file);
-                // For service name, use first container name
-                if let Some(first_container) = project.containers.first() {
-                    command = command.replace("{service}", first_container);
+/// Replaces each `{name}` in `template` with `lookup(name)`, in one pass so
+/// substituted values are never expanded again. Braces that do not name a
+/// placeholder, such as an awk program's, are left as they are; the first
+/// placeholder without a value fails the expansion.
+pub fn expand_placeholders(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<Result<String, String>>,
+) -> Result<String, String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(value) => expanded.push_str(&value?),
+                    None => {
+                        expanded.push('{');
+                        expanded.push_str(name);
+                        expanded.push('}');
+                    }
                 }
+                rest = &after[end + 1..];
+            }
+            None => {
+                expanded.push_str(&rest[start..]);
+                rest = "";
             }
-
-            return Some(command);
         }
-        None
     }
+    expanded.push_str(rest);
+    Ok(expanded)
 }
 
 pub fn render_context_menu<B: Backend>(
@@ -333,10 +808,16 @@ pub fn render_context_menu<B: Backend>(
                 Style::default()
             };
 
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::styled(if is_selected { "▶ " } else { "  " }, style),
                 Span::styled(&action.label, style),
-            ]);
+            ];
+            if let Some(Err(reason)) = context_menu.commands.get(index) {
+                let dim = style.fg(config.get_color("text_dim"));
+                spans[1] = Span::styled(&action.label, dim);
+                spans.push(Span::styled(format!("  ({})", reason), dim));
+            }
+            let line = Line::from(spans);
 
             ListItem::new(line).style(style)
         })
@@ -344,18 +825,10 @@ pub fn render_context_menu<B: Backend>(
 
     all_items.extend(action_items);
 
-    let title = if context_menu.target_project.is_some() {
-        "Context Menu - Compose Project"
-    } else if context_menu.target_container.is_some() {
-        "Context Menu - Container"
-    } else {
-        "Context Menu"
-    };
-
     let list = List::new(all_items).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(title)
+            .title(context_menu.title())
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(config.get_color("border_light")))
             .style(Style::default().bg(config.get_color("background_dark")))
@@ -363,6 +836,64 @@ pub fn render_context_menu<B: Backend>(
     );
 
     f.render_widget(list, area);
+
+    if let Some(form) = &context_menu.form {
+        render_prompt_form(f, form, config);
+    }
+}
+
+fn render_prompt_form(f: &mut Frame, form: &PromptForm, config: &Config) {
+    let area = centered_rect(50, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let name_width = form
+        .fields
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<Line> = form
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, (name, value))| {
+            let focused = index == form.focused;
+            let value_style = if focused {
+                Style::default()
+                    .bg(config.get_color("selected_bg"))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>width$}: ", name, width = name_width),
+                    Style::default().fg(config.get_color("text_dim")),
+                ),
+                Span::styled(value.clone(), value_style),
+                Span::styled(
+                    if focused { "_" } else { "" },
+                    Style::default().fg(config.get_color("filter_cursor")),
+                ),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Tab/Shift+Tab: field | Enter: run | Esc: back",
+        Style::default().fg(config.get_color("hotkey_gray")),
+    )));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(form.title.as_str())
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.get_color("border_light")))
+            .style(Style::default().bg(config.get_color("background_dark")))
+            .padding(Padding::uniform(1)),
+    );
+    f.render_widget(paragraph, area);
 }
 
 fn format_started_time(started_at: &str) -> String {
@@ -404,4 +935,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
+#[cfg(test)]
+mod tests;
+
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
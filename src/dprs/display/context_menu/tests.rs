@@ -0,0 +1,414 @@
+// The tests module for the context menu checks matchers and their
+// combinators, `enabled_when` conditions, placeholder expansion, that
+// substituted values are shell-quoted, and that actions whose placeholders
+// have no value are disabled with a reason, and the form for prompted
+// values.
+
+use super::*;
+use crate::shared::config::CONFIG_VERSION;
+
+/// Parses a config written for this version, so migrations add nothing.
+fn parse_config(content: &str) -> Config {
+    Config::parse(&format!("version = {}\n{}", CONFIG_VERSION, content)).unwrap()
+}
+
+fn menu_for(container: Container, command: &str) -> ContextMenuState {
+    let mut config = Config::default();
+    config.context_menu.actions = vec![ContextMenuAction {
+        label: "Test".to_string(),
+        command: command.to_string(),
+        matchers: vec![],
+        enabled_when: None,
+        run: Default::default(),
+        prompts: vec![],
+        confirm: false,
+    }];
+    let mut menu = ContextMenuState::new();
+    menu.activate(Some(container), None, &config);
+    menu
+}
+
+fn compose_container() -> Container {
+    let labels = [
+        ("com.docker.compose.service", "db"),
+        ("com.docker.compose.project", "shop"),
+        ("com.docker.compose.project.working_dir", "/srv/shop"),
+        (
+            "com.docker.compose.project.config_files",
+            "/srv/shop/compose.yml,/srv/shop/compose.override.yml",
+        ),
+        ("role", "primary db"),
+    ];
+    Container {
+        name: "shop-db-1".to_string(),
+        container_id: "0123456789abcdef0123".to_string(),
+        ports: "0.0.0.0:15432->5432/tcp, 6379/tcp".to_string(),
+        ip_address: "172.18.0.3".to_string(),
+        compose_project: Some("shop".to_string()),
+        labels: labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_expand_placeholders() {
+    let lookup = |name: &str| match name {
+        "a" => Some(Ok("{b}".to_string())),
+        "b" => Some(Ok("B".to_string())),
+        "bad" => Some(Err("no value".to_string())),
+        _ => None,
+    };
+    assert_eq!(
+        expand_placeholders("x {a} {b} {c} {", lookup),
+        Ok("x {b} B {c} {".to_string())
+    );
+    assert_eq!(
+        expand_placeholders("awk '{print $1}' {bad}", lookup),
+        Err("no value".to_string())
+    );
+}
+
+#[test]
+fn test_expanded_command_quotes_values() {
+    let container = Container {
+        name: "web; touch /tmp/pwned".to_string(),
+        image: "nginx:latest".to_string(),
+        ip_address: "172.17.0.2".to_string(),
+        ..Default::default()
+    };
+    let menu = menu_for(container, "docker logs {name} # {image} {ip} {other}");
+    assert_eq!(
+        menu.expanded_command(),
+        Some(Ok(
+            "docker logs 'web; touch /tmp/pwned' # nginx:latest 172.17.0.2 {other}".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_compose_placeholders() {
+    let menu = menu_for(
+        compose_container(),
+        "docker compose -f {compose_file} up -d {service} {short_id} {label:role} {port:5432} {url}",
+    );
+    assert_eq!(
+        menu.expanded_command(),
+        Some(Ok(
+            "docker compose -f /srv/shop/compose.yml -f /srv/shop/compose.override.yml \
+             up -d db 0123456789ab 'primary db' 15432 http://localhost:15432"
+                .to_string()
+        ))
+    );
+
+    let menu = menu_for(compose_container(), "docker compose {compose_args} ps");
+    assert_eq!(
+        menu.expanded_command(),
+        Some(Ok(
+            "docker compose -p shop -f /srv/shop/compose.yml -f /srv/shop/compose.override.yml ps"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_missing_values_disable_the_action() {
+    let expect = |command: &str, reason: &str| {
+        let menu = menu_for(compose_container(), command);
+        assert_eq!(menu.expanded_command(), Some(Err(reason.to_string())));
+    };
+    expect("psql -p {port:6379}", "port 6379 is not published");
+    expect("echo {label:missing}", "no label missing");
+    expect("echo {port:web}", "{port:web} is not a port");
+
+    let menu = menu_for(Container::default(), "docker compose restart {service}");
+    assert_eq!(
+        menu.expanded_command(),
+        Some(Err("not a compose service".to_string()))
+    );
+}
+
+fn offered(actions_toml: &str, container: Option<Container>, project: bool) -> Vec<String> {
+    let config = parse_config(actions_toml);
+    let project = project.then(|| ComposeProject {
+        project_name: "shop".to_string(),
+        working_dir: "/srv/shop".to_string(),
+        config_files: Vec::new(),
+        containers: Vec::new(),
+        services: Vec::new(),
+    });
+    let mut menu = ContextMenuState::new();
+    menu.activate(container, project, &config);
+    menu.actions.iter().map(|a| a.label.clone()).collect()
+}
+
+const MATCHER_ACTIONS: &str = r#"
+[[context_menu.actions]]
+label = "psql"
+command = "psql"
+matchers = [
+  { type = "all", matchers = [
+    { type = "label", label = "com.docker.compose.service", value = "db" },
+    { type = "port", port = 5432 },
+    { type = "not", matcher = { type = "health", health = "unhealthy" } },
+  ] },
+]
+
+[[context_menu.actions]]
+label = "backend"
+command = "true"
+matchers = [
+  { type = "network", pattern = "^back" },
+  { type = "image", pattern = "redis" },
+]
+
+[[context_menu.actions]]
+label = "revive"
+command = "true"
+enabled_when = "status:exited or health:unhealthy"
+
+[[context_menu.actions]]
+label = "project"
+command = "true"
+enabled_when = "running"
+matchers = [{ type = "compose_project" }]
+"#;
+
+#[test]
+fn test_matchers_and_conditions() {
+    let mut db = compose_container();
+    db.status = "Up 5 minutes (healthy)".to_string();
+    db.networks = vec!["backend".to_string()];
+    assert_eq!(
+        offered(MATCHER_ACTIONS, Some(db.clone()), false),
+        vec!["psql", "backend"]
+    );
+
+    db.status = "Up 5 minutes (unhealthy)".to_string();
+    db.networks.clear();
+    assert_eq!(
+        offered(MATCHER_ACTIONS, Some(db.clone()), false),
+        vec!["revive"]
+    );
+
+    db.status = "Exited (1) 2 minutes ago".to_string();
+    db.image = "redis:7".to_string();
+    assert_eq!(
+        offered(MATCHER_ACTIONS, Some(db), false),
+        vec!["psql", "backend", "revive"]
+    );
+
+    assert_eq!(offered(MATCHER_ACTIONS, None, true), vec!["project"]);
+}
+
+#[test]
+fn test_parse_condition() {
+    let mut container = compose_container();
+    container.status = "Up 1 hour (Paused)".to_string();
+    assert!(parse_condition("paused").unwrap().matches(&container));
+    assert!(parse_condition("stopped").unwrap().matches(&container));
+    assert!(!parse_condition("running").unwrap().matches(&container));
+    assert!(parse_condition("label:role and port:5432")
+        .unwrap()
+        .matches(&container));
+    assert!(parse_condition("status:").is_err());
+}
+
+#[test]
+fn test_context_menu_problems() {
+    let config = parse_config(
+        r#"
+        [[context_menu.actions]]
+        label = "bad"
+        command = "true"
+        enabled_when = "(running"
+        matchers = [
+          { type = "not", matcher = { type = "name", pattern = "[" } },
+          { type = "status", status = "sleeping" },
+        ]
+        "#,
+    );
+    let problems = context_menu_problems(&config);
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+    assert!(problems[0].starts_with("context_menu: 'bad': enabled_when '(running'"));
+    assert!(problems
+        .iter()
+        .any(|p| p.contains("unknown status 'sleeping'")));
+    assert!(problems.iter().any(|p| p.contains("invalid pattern '['")));
+    assert!(context_menu_problems(&Config::default()).is_empty());
+}
+
+#[test]
+fn test_prompt_form() {
+    let config = parse_config(
+        r#"
+        [[context_menu.actions]]
+        label = "Logs since"
+        command = "docker logs --since {minutes}m {name} | grep {pattern}"
+        prompts = [{ name = "minutes", default = "10" }, { name = "pattern" }]
+        "#,
+    );
+    let mut menu = ContextMenuState::new();
+    menu.activate(Some(compose_container()), None, &config);
+    assert_eq!(
+        menu.expanded_command(),
+        Some(Ok("docker logs --since 10m shop-db-1 | grep ''".to_string()))
+    );
+
+    assert!(menu.open_form());
+    let form = menu.form.as_mut().unwrap();
+    form.pop_char();
+    form.pop_char();
+    form.push_char('5');
+    form.next_field();
+    for c in "a b".chars() {
+        form.push_char(c);
+    }
+    assert_eq!(
+        menu.submit_form(),
+        Some(Ok(
+            "docker logs --since 5m shop-db-1 | grep 'a b'".to_string()
+        ))
+    );
+    assert!(menu.form.is_none());
+}
+
+#[test]
+fn test_prompt_problems() {
+    let config = parse_config(
+        r#"
+        [[context_menu.actions]]
+        label = "p"
+        command = "true"
+        prompts = [{ name = "name" }, { name = "tag" }, { name = "tag" }, { name = "env:X" }]
+        "#,
+    );
+    assert_eq!(
+        context_menu_problems(&config),
+        vec![
+            "context_menu: 'p': prompt 'name' hides the {name} placeholder",
+            "context_menu: 'p': prompt 'tag' is declared twice",
+            "context_menu: 'p': prompt name 'env:X' cannot be a placeholder",
+        ]
+    );
+}
+
+#[test]
+fn test_project_actions() {
+    let config = parse_config(
+        r#"
+        [[context_menu.actions]]
+        label = "Migrate"
+        command = "true"
+        matchers = [{ type = "project", pattern = "^shop$" }]
+
+        [[context_menu.actions]]
+        label = "Deploy"
+        command = "true"
+        matchers = [{ type = "project_dir", pattern = "^/srv/" }]
+        "#,
+    );
+    let project = |name: &str, dir: &str| ComposeProject {
+        project_name: name.to_string(),
+        working_dir: dir.to_string(),
+        config_files: vec![format!("{}/compose.yml", dir)],
+        containers: Vec::new(),
+        services: Vec::new(),
+    };
+    let labels = |menu: &ContextMenuState| -> Vec<String> {
+        menu.actions.iter().map(|a| a.label.clone()).collect()
+    };
+
+    let mut menu = ContextMenuState::new();
+    menu.activate(None, Some(project("shop", "/srv/shop")), &config);
+    assert_eq!(labels(&menu), vec!["Migrate", "Deploy"]);
+    menu.activate(None, Some(project("blog", "/home/me/blog")), &config);
+    assert!(labels(&menu).is_empty());
+    // A container of the project matches too
+    menu.activate(Some(compose_container()), None, &config);
+    assert_eq!(labels(&menu), vec!["Migrate", "Deploy"]);
+
+    // The default project actions run docker compose for the project
+    let mut menu = ContextMenuState::new();
+    menu.activate(None, Some(project("shop", "/srv/shop")), &Config::default());
+    let up = menu.actions.iter().position(|a| a.label == "Up").unwrap();
+    assert_eq!(
+        menu.commands[up],
+        Ok("docker compose -p shop -f /srv/shop/compose.yml up -d".to_string())
+    );
+    // Without recorded compose files compose looks in the project directory
+    let mut no_files = project("blog", "/home/me/blog");
+    no_files.config_files.clear();
+    menu.activate(None, Some(no_files), &Config::default());
+    let up = menu.actions.iter().position(|a| a.label == "Up").unwrap();
+    assert_eq!(
+        menu.commands[up],
+        Ok("docker compose -p blog --project-directory /home/me/blog up -d".to_string())
+    );
+    let editor = menu
+        .actions
+        .iter()
+        .position(|a| a.label == "Open in Editor")
+        .unwrap();
+    assert_eq!(
+        menu.commands[editor],
+        Ok("cd /home/me/blog && ${EDITOR:-vi} .".to_string())
+    );
+}
+
+#[test]
+fn test_service_actions() {
+    let project = ComposeProject {
+        project_name: "shop".to_string(),
+        working_dir: "/srv/shop".to_string(),
+        config_files: vec!["/srv/shop/compose.yml".to_string()],
+        containers: vec!["shop-web-1".to_string()],
+        services: Vec::new(),
+    };
+    let service = ComposeService {
+        name: "web".to_string(),
+        containers: vec!["shop-web-1".to_string()],
+        running: 1,
+    };
+    let config = Config::default();
+    let mut menu = ContextMenuState::new();
+    menu.activate_service(project.clone(), service, &config);
+    assert_eq!(menu.title(), "Context Menu - Compose Service");
+    let labels: Vec<&str> = menu.actions.iter().map(|a| a.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "Up Service",
+            "Restart Service",
+            "Recreate Service",
+            "Build & Up Service",
+            "Scale Service",
+            "Service Logs",
+        ]
+    );
+    assert_eq!(
+        menu.commands[1],
+        Ok("docker compose -p shop -f /srv/shop/compose.yml restart web".to_string())
+    );
+
+    menu.selected_index = 4;
+    assert!(menu.open_form());
+    let form = menu.form.as_mut().unwrap();
+    form.pop_char();
+    form.push_char('3');
+    assert_eq!(
+        menu.submit_form(),
+        Some(Ok(
+            "docker compose -p shop -f /srv/shop/compose.yml up -d --scale web=3 web".to_string()
+        ))
+    );
+
+    // Project rows do not offer them
+    menu.activate(None, Some(project), &config);
+    assert_eq!(menu.title(), "Context Menu - Compose Project");
+    assert!(!menu.actions.iter().any(|a| a.label == "Restart Service"));
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -23,12 +23,13 @@ fn get_key_for_action(config: &Config, action: &str, mode: &str) -> Option<Strin
         _ => return None,
     };
 
-    for (key, bound_action) in bindings {
-        if bound_action == action {
-            return Some(key.clone());
-        }
-    }
-    None
+    // With several keys bound to the action, show the shortest
+    bindings
+        .iter()
+        .filter(|(_, bound_action)| *bound_action == action)
+        .map(|(key, _)| key)
+        .min_by_key(|key| (key.len(), key.as_str()))
+        .cloned()
 }
 
 pub fn render_hotkey_bar<B: Backend>(f: &mut Frame, area: Rect, config: &Config) {
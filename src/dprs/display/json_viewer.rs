@@ -0,0 +1,728 @@
+// The json_viewer module implements a full-screen, collapsible tree view for
+// JSON documents such as the output of `docker inspect`. The document is
+// flattened into a list of nodes in document order; collapsing a node hides
+// its descendants. The viewer supports incremental search over keys and
+// values, jumping to a path like `.NetworkSettings.Networks`, and yanking the
+// value under the cursor.
+
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::shared::config::Config;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum NodeKind {
+    Object(usize),
+    Array(usize),
+    Scalar(String),
+}
+
+#[derive(Clone, Debug)]
+struct JsonNode {
+    path: Vec<PathSegment>,
+    parent: Option<usize>,
+    kind: NodeKind,
+    // Index one past the last descendant of this node.
+    subtree_end: usize,
+}
+
+impl JsonNode {
+    fn depth(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+
+    fn is_container(&self) -> bool {
+        !matches!(self.kind, NodeKind::Scalar(_))
+    }
+
+    fn label(&self) -> String {
+        match self.path.last() {
+            Some(PathSegment::Key(key)) => key.clone(),
+            Some(PathSegment::Index(index)) => format!("[{}]", index),
+            None => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViewerInput {
+    Search(String),
+    JumpToPath(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct JsonViewerState {
+    pub active: bool,
+    pub title: String,
+    pub list_state: ListState,
+    pub input: Option<ViewerInput>,
+    pub message: Option<String>,
+    pub page_height: usize,
+    root: Value,
+    nodes: Vec<JsonNode>,
+    collapsed: HashSet<usize>,
+    visible: Vec<usize>,
+    search_query: String,
+    matches: Vec<usize>,
+    current_match: Option<usize>,
+}
+
+impl Default for JsonViewerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonViewerState {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            title: String::new(),
+            list_state: ListState::default(),
+            input: None,
+            message: None,
+            page_height: 10,
+            root: Value::Null,
+            nodes: Vec::new(),
+            collapsed: HashSet::new(),
+            visible: Vec::new(),
+            search_query: String::new(),
+            matches: Vec::new(),
+            current_match: None,
+        }
+    }
+
+    pub fn open(&mut self, title: String, root: Value) {
+        let mut nodes = Vec::new();
+        flatten(&root, &mut Vec::new(), None, &mut nodes);
+
+        // Start fully collapsed so large documents open as a readable
+        // overview of their top-level keys.
+        let collapsed = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.is_container())
+            .map(|(index, _)| index)
+            .collect();
+
+        *self = Self {
+            active: true,
+            title,
+            root,
+            nodes,
+            collapsed,
+            ..Self::new()
+        };
+        self.rebuild_visible();
+        self.select_visible(0);
+    }
+
+    pub fn close(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible.len()
+    }
+
+    fn selected_node(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .and_then(|row| self.visible.get(row).copied())
+    }
+
+    fn select_visible(&mut self, row: usize) {
+        if self.visible.is_empty() {
+            self.list_state.select(None);
+        } else {
+            self.list_state
+                .select(Some(row.min(self.visible.len() - 1)));
+        }
+    }
+
+    fn select_node(&mut self, node: usize) {
+        self.reveal(node);
+        if let Some(row) = self.visible.iter().position(|&n| n == node) {
+            self.list_state.select(Some(row));
+        }
+    }
+
+    fn rebuild_visible(&mut self) {
+        self.visible.clear();
+        let mut index = 0;
+        while index < self.nodes.len() {
+            self.visible.push(index);
+            if self.collapsed.contains(&index) {
+                index = self.nodes[index].subtree_end;
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Expands every ancestor of `node` so that it becomes visible.
+    fn reveal(&mut self, node: usize) {
+        let mut parent = self.nodes.get(node).and_then(|n| n.parent);
+        while let Some(p) = parent {
+            self.collapsed.remove(&p);
+            parent = self.nodes[p].parent;
+        }
+        self.rebuild_visible();
+    }
+
+    pub fn next(&mut self) {
+        let row = self.list_state.selected().map_or(0, |row| row + 1);
+        self.select_visible(row);
+    }
+
+    pub fn previous(&mut self) {
+        let row = self.list_state.selected().unwrap_or(0).saturating_sub(1);
+        self.select_visible(row);
+    }
+
+    pub fn go_to_first(&mut self) {
+        self.select_visible(0);
+    }
+
+    pub fn go_to_last(&mut self) {
+        self.select_visible(self.visible.len().saturating_sub(1));
+    }
+
+    pub fn page_down(&mut self) {
+        let row = self.list_state.selected().unwrap_or(0) + self.page_height / 2;
+        self.select_visible(row);
+    }
+
+    pub fn page_up(&mut self) {
+        let row = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_sub(self.page_height / 2);
+        self.select_visible(row);
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(node) = self.selected_node() {
+            if self.collapsed.contains(&node) {
+                self.expand();
+            } else {
+                self.collapse();
+            }
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(node) = self.selected_node() {
+            if self.collapsed.remove(&node) {
+                self.rebuild_visible();
+            }
+        }
+    }
+
+    /// Collapses the node under the cursor. If it is a scalar or already
+    /// collapsed, moves the cursor to its parent instead.
+    pub fn collapse(&mut self) {
+        let Some(node) = self.selected_node() else {
+            return;
+        };
+
+        if self.nodes[node].is_container() && !self.collapsed.contains(&node) {
+            self.collapsed.insert(node);
+            self.rebuild_visible();
+            self.select_node(node);
+        } else if let Some(parent) = self.nodes[node].parent {
+            self.select_node(parent);
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        let selected = self.selected_node();
+        self.collapsed.clear();
+        self.rebuild_visible();
+        if let Some(node) = selected {
+            self.select_node(node);
+        }
+    }
+
+    pub fn collapse_all(&mut self) {
+        let selected = self.selected_node();
+        self.collapsed = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.is_container())
+            .map(|(index, _)| index)
+            .collect();
+        self.rebuild_visible();
+
+        // Keep the cursor on the top-level ancestor of the previous selection.
+        let mut top = selected;
+        while let Some(parent) = top.and_then(|n| self.nodes[n].parent) {
+            top = Some(parent);
+        }
+        match top {
+            Some(node) => self.select_node(node),
+            None => self.select_visible(0),
+        }
+    }
+
+    pub fn begin_search(&mut self) {
+        self.input = Some(ViewerInput::Search(String::new()));
+        self.message = None;
+    }
+
+    pub fn begin_jump(&mut self) {
+        self.input = Some(ViewerInput::JumpToPath(String::new()));
+        self.message = None;
+    }
+
+    pub fn push_input(&mut self, c: char) {
+        match self.input {
+            Some(ViewerInput::Search(ref mut query)) => {
+                query.push(c);
+                let query = query.clone();
+                self.search(&query);
+            }
+            Some(ViewerInput::JumpToPath(ref mut path)) => path.push(c),
+            None => {}
+        }
+    }
+
+    pub fn pop_input(&mut self) {
+        match self.input {
+            Some(ViewerInput::Search(ref mut query)) => {
+                query.pop();
+                let query = query.clone();
+                self.search(&query);
+            }
+            Some(ViewerInput::JumpToPath(ref mut path)) => {
+                path.pop();
+            }
+            None => {}
+        }
+    }
+
+    pub fn cancel_input(&mut self) {
+        self.input = None;
+    }
+
+    pub fn submit_input(&mut self) {
+        match self.input.take() {
+            Some(ViewerInput::Search(query)) => {
+                self.message = if query.is_empty() {
+                    None
+                } else if self.matches.is_empty() {
+                    Some(format!("Pattern not found: {}", query))
+                } else {
+                    Some(format!("{} matches for {}", self.matches.len(), query))
+                };
+            }
+            Some(ViewerInput::JumpToPath(path)) => {
+                self.message = self.jump_to_path(&path).err();
+            }
+            None => {}
+        }
+    }
+
+    /// Updates the search matches for `query` and moves the cursor to the
+    /// first match. Keys and scalar values are matched case-insensitively.
+    pub fn search(&mut self, query: &str) {
+        self.search_query = query.to_string();
+        self.current_match = None;
+        self.matches.clear();
+
+        if query.is_empty() {
+            return;
+        }
+
+        let query_lower = query.to_lowercase();
+        self.matches = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| {
+                node.label().to_lowercase().contains(&query_lower)
+                    || matches!(node.kind, NodeKind::Scalar(ref text) if text.to_lowercase().contains(&query_lower))
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        if let Some(&first) = self.matches.first() {
+            self.current_match = Some(0);
+            self.select_node(first);
+        }
+    }
+
+    pub fn next_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let next = self
+            .current_match
+            .map_or(0, |index| (index + 1) % self.matches.len());
+        self.current_match = Some(next);
+        self.select_node(self.matches[next]);
+    }
+
+    pub fn previous_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let previous = match self.current_match {
+            Some(0) | None => self.matches.len() - 1,
+            Some(index) => index - 1,
+        };
+        self.current_match = Some(previous);
+        self.select_node(self.matches[previous]);
+    }
+
+    fn is_match(&self, node: usize) -> bool {
+        self.matches.binary_search(&node).is_ok()
+    }
+
+    /// Moves the cursor to the node at `path`, expanding its ancestors.
+    /// Keys are matched exactly first, then case-insensitively.
+    pub fn jump_to_path(&mut self, path: &str) -> Result<(), String> {
+        let segments = parse_path(path)?;
+        if segments.is_empty() {
+            self.go_to_first();
+            return Ok(());
+        }
+
+        let found = self
+            .nodes
+            .iter()
+            .position(|node| node.path == segments)
+            .or_else(|| {
+                self.nodes
+                    .iter()
+                    .position(|node| paths_match_ignore_case(&node.path, &segments))
+            });
+
+        match found {
+            Some(node) => {
+                self.select_node(node);
+                Ok(())
+            }
+            None => Err(format!("No such path: {}", path)),
+        }
+    }
+
+    /// Returns the path of the node under the cursor in jump-to-path syntax.
+    pub fn selected_path(&self) -> Option<String> {
+        self.selected_node()
+            .map(|node| format_path(&self.nodes[node].path))
+    }
+
+    /// Returns the value under the cursor as text suitable for the clipboard:
+    /// strings without quotes, other scalars verbatim, and objects or arrays
+    /// as pretty-printed JSON.
+    pub fn selected_value(&self) -> Option<String> {
+        let node = self.selected_node()?;
+        let value = lookup(&self.root, &self.nodes[node].path)?;
+        Some(match value {
+            Value::String(s) => s.clone(),
+            Value::Object(_) | Value::Array(_) => {
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            }
+            other => other.to_string(),
+        })
+    }
+}
+
+fn flatten(
+    value: &Value,
+    path: &mut Vec<PathSegment>,
+    parent: Option<usize>,
+    nodes: &mut Vec<JsonNode>,
+) {
+    let children: Vec<(PathSegment, &Value)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (PathSegment::Key(key.clone()), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (PathSegment::Index(index), child))
+            .collect(),
+        _ => return,
+    };
+
+    for (segment, child) in children {
+        path.push(segment);
+        let index = nodes.len();
+        let kind = match child {
+            Value::Object(map) => NodeKind::Object(map.len()),
+            Value::Array(items) => NodeKind::Array(items.len()),
+            scalar => NodeKind::Scalar(scalar.to_string()),
+        };
+        nodes.push(JsonNode {
+            path: path.clone(),
+            parent,
+            kind,
+            subtree_end: index + 1,
+        });
+        flatten(child, path, Some(index), nodes);
+        nodes[index].subtree_end = nodes.len();
+        path.pop();
+    }
+}
+
+fn lookup<'a>(root: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(index) => value.get(index),
+    })
+}
+
+fn paths_match_ignore_case(a: &[PathSegment], b: &[PathSegment]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (PathSegment::Key(x), PathSegment::Key(y)) => x.eq_ignore_ascii_case(y),
+            (x, y) => x == y,
+        })
+}
+
+/// Parses a jq-style path such as `.NetworkSettings.Networks`,
+/// `.Mounts[0].Source` or `.Config.Labels["com.docker.compose.project"]`.
+/// The leading dot is optional.
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut segments = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        match chars[pos] {
+            '.' => {
+                pos += 1;
+                let start = pos;
+                while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                    pos += 1;
+                }
+                if pos > start {
+                    segments.push(PathSegment::Key(chars[start..pos].iter().collect()));
+                } else if pos < chars.len() && chars[pos] == '.' {
+                    return Err(format!("Empty key at position {}", start));
+                }
+            }
+            '[' => {
+                pos += 1;
+                if pos < chars.len() && chars[pos] == '"' {
+                    pos += 1;
+                    let start = pos;
+                    while pos < chars.len() && chars[pos] != '"' {
+                        pos += 1;
+                    }
+                    if pos + 1 >= chars.len() || chars[pos + 1] != ']' {
+                        return Err(format!("Unterminated key at position {}", start - 2));
+                    }
+                    segments.push(PathSegment::Key(chars[start..pos].iter().collect()));
+                    pos += 2;
+                } else {
+                    let start = pos;
+                    while pos < chars.len() && chars[pos] != ']' {
+                        pos += 1;
+                    }
+                    if pos >= chars.len() {
+                        return Err(format!("Unterminated index at position {}", start - 1));
+                    }
+                    let text: String = chars[start..pos].iter().collect();
+                    let index = text
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid index '{}' at position {}", text, start))?;
+                    segments.push(PathSegment::Index(index));
+                    pos += 1;
+                }
+            }
+            _ if pos == 0 => {
+                // Allow the leading dot to be omitted.
+                let start = pos;
+                while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                    pos += 1;
+                }
+                segments.push(PathSegment::Key(chars[start..pos].iter().collect()));
+            }
+            c => return Err(format!("Unexpected '{}' at position {}", c, pos)),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Formats a path so that `parse_path` reads it back, quoting keys that
+/// contain dots or brackets (common in label names).
+pub fn format_path(path: &[PathSegment]) -> String {
+    if path.is_empty() {
+        return ".".to_string();
+    }
+
+    path.iter()
+        .map(|segment| match segment {
+            PathSegment::Key(key) if key.contains(['.', '[', ']', '"']) || key.is_empty() => {
+                format!("[\"{}\"]", key)
+            }
+            PathSegment::Key(key) => format!(".{}", key),
+            PathSegment::Index(index) => format!("[{}]", index),
+        })
+        .collect()
+}
+
+pub fn render_json_viewer<B: Backend>(
+    f: &mut Frame,
+    viewer: &mut JsonViewerState,
+    config: &Config,
+) {
+    if !viewer.active {
+        return;
+    }
+
+    let area = f.area();
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .split(area);
+
+    // Borders take two rows of the list area.
+    viewer.page_height = chunks[0].height.saturating_sub(2) as usize;
+
+    let items: Vec<ListItem> = viewer
+        .visible
+        .iter()
+        .map(|&index| {
+            let node = &viewer.nodes[index];
+            let mut base_style = Style::default().bg(config.get_color("background_main"));
+            if viewer.is_match(index) {
+                base_style = base_style.add_modifier(Modifier::UNDERLINED);
+            }
+
+            let marker = if !node.is_container() {
+                "  "
+            } else if viewer.collapsed.contains(&index) {
+                "▸ "
+            } else {
+                "▾ "
+            };
+
+            let mut spans = vec![
+                Span::styled("  ".repeat(node.depth()), base_style),
+                Span::styled(marker, base_style.fg(config.get_color("text_selection"))),
+                Span::styled(
+                    node.label(),
+                    base_style
+                        .fg(config.get_color("container_name"))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(": ", base_style),
+            ];
+
+            let value = match &node.kind {
+                NodeKind::Object(len) => Span::styled(
+                    format!("{{{}}}", plural(*len, "key")),
+                    base_style.fg(config.get_color("text_dim")),
+                ),
+                NodeKind::Array(len) => Span::styled(
+                    format!("[{}]", plural(*len, "item")),
+                    base_style.fg(config.get_color("text_dim")),
+                ),
+                NodeKind::Scalar(text) => {
+                    let color = if text.starts_with('"') {
+                        "container_image"
+                    } else if text == "null" || text == "true" || text == "false" {
+                        "container_status"
+                    } else {
+                        "container_ports"
+                    };
+                    Span::styled(text.clone(), base_style.fg(config.get_color(color)))
+                }
+            };
+            spans.push(value);
+
+            ListItem::new(Line::from(spans)).style(base_style)
+        })
+        .collect();
+
+    let position = match viewer.list_state.selected() {
+        Some(row) => format!("{}/{}", row + 1, viewer.visible.len()),
+        None => "empty".to_string(),
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} ({})", viewer.title, position))
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(config.get_color("border_light")))
+                .style(Style::default().bg(config.get_color("background_main"))),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(config.get_color("selected_bg"))
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_stateful_widget(list, chunks[0], &mut viewer.list_state);
+
+    let footer = match &viewer.input {
+        Some(ViewerInput::Search(query)) => Line::from(format!("/{}", query)),
+        Some(ViewerInput::JumpToPath(path)) => Line::from(format!("path: {}", path)),
+        None => match &viewer.message {
+            Some(message) => Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(config.get_color("message_warning")),
+            )),
+            None => Line::from(vec![
+                Span::styled(
+                    viewer.selected_path().unwrap_or_default(),
+                    Style::default().fg(config.get_color("container_status")),
+                ),
+                Span::styled(
+                    "   Enter: toggle | h/l: collapse/expand | E/C: all | /: search | :: path | y/Y: yank value/path | q: close",
+                    Style::default().fg(config.get_color("hotkey_gray")),
+                ),
+            ]),
+        },
+    };
+
+    let footer_widget = Paragraph::new(footer)
+        .style(Style::default().bg(config.get_color("background_dark")))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().bg(config.get_color("background_dark"))),
+        );
+
+    f.render_widget(footer_widget, chunks[1]);
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,182 @@
+// The tests module for the json_viewer component validates the tree model
+// behind the inspect viewer: path parsing and formatting, collapsing and
+// expanding nodes, incremental search revealing collapsed matches, jumping
+// to a path, and the text produced when yanking a value.
+
+use super::*;
+use serde_json::json;
+
+fn sample() -> Value {
+    json!({
+        "Id": "abc123",
+        "State": { "Status": "running", "Running": true },
+        "Config": {
+            "Labels": { "com.docker.compose.project": "shop" }
+        },
+        "Mounts": [
+            { "Source": "/data", "Destination": "/var/lib/postgresql" }
+        ],
+        "NetworkSettings": {
+            "Networks": { "backend": { "IPAddress": "172.18.0.2" } }
+        }
+    })
+}
+
+fn open_sample() -> JsonViewerState {
+    let mut viewer = JsonViewerState::new();
+    viewer.open("Inspect: db".to_string(), sample());
+    viewer
+}
+
+#[test]
+fn test_parse_path() {
+    assert_eq!(
+        parse_path(".NetworkSettings.Networks").unwrap(),
+        vec![
+            PathSegment::Key("NetworkSettings".to_string()),
+            PathSegment::Key("Networks".to_string()),
+        ]
+    );
+    assert_eq!(
+        parse_path("Mounts[0].Source").unwrap(),
+        vec![
+            PathSegment::Key("Mounts".to_string()),
+            PathSegment::Index(0),
+            PathSegment::Key("Source".to_string()),
+        ]
+    );
+    assert_eq!(
+        parse_path(".Config.Labels[\"com.docker.compose.project\"]").unwrap(),
+        vec![
+            PathSegment::Key("Config".to_string()),
+            PathSegment::Key("Labels".to_string()),
+            PathSegment::Key("com.docker.compose.project".to_string()),
+        ]
+    );
+    assert!(parse_path(".").unwrap().is_empty());
+    assert!(parse_path(".Mounts[x]").is_err());
+    assert!(parse_path(".Mounts[0").is_err());
+    assert!(parse_path(".a..b").is_err());
+}
+
+#[test]
+fn test_format_path_round_trips() {
+    let path = vec![
+        PathSegment::Key("Config".to_string()),
+        PathSegment::Key("Labels".to_string()),
+        PathSegment::Key("com.docker.compose.project".to_string()),
+    ];
+    let formatted = format_path(&path);
+    assert_eq!(formatted, ".Config.Labels[\"com.docker.compose.project\"]");
+    assert_eq!(parse_path(&formatted).unwrap(), path);
+}
+
+#[test]
+fn test_open_shows_top_level_only() {
+    let viewer = open_sample();
+    assert!(viewer.active);
+    // Id, State, Config, Mounts, NetworkSettings
+    assert_eq!(viewer.visible_count(), 5);
+    assert_eq!(viewer.list_state.selected(), Some(0));
+}
+
+#[test]
+fn test_expand_and_collapse() {
+    let mut viewer = open_sample();
+    viewer.jump_to_path(".State").unwrap();
+    viewer.expand();
+    assert_eq!(viewer.visible_count(), 7);
+
+    // Collapsing a scalar moves to its parent.
+    viewer.next();
+    assert!(viewer.selected_path().unwrap().starts_with(".State."));
+    viewer.collapse();
+    assert_eq!(viewer.selected_path().unwrap(), ".State");
+
+    viewer.collapse();
+    assert_eq!(viewer.visible_count(), 5);
+}
+
+#[test]
+fn test_expand_all_and_collapse_all() {
+    let mut viewer = open_sample();
+    viewer.expand_all();
+    assert_eq!(viewer.visible_count(), 15);
+
+    viewer
+        .jump_to_path(".NetworkSettings.Networks.backend")
+        .unwrap();
+    viewer.collapse_all();
+    assert_eq!(viewer.visible_count(), 5);
+    assert_eq!(viewer.selected_path().unwrap(), ".NetworkSettings");
+}
+
+#[test]
+fn test_jump_to_path_reveals_node() {
+    let mut viewer = open_sample();
+    viewer.jump_to_path(".NetworkSettings.Networks").unwrap();
+    assert_eq!(viewer.selected_path().unwrap(), ".NetworkSettings.Networks");
+
+    // Case-insensitive fallback
+    viewer.jump_to_path(".mounts[0].source").unwrap();
+    assert_eq!(viewer.selected_path().unwrap(), ".Mounts[0].Source");
+
+    assert!(viewer.jump_to_path(".Nope").is_err());
+}
+
+#[test]
+fn test_search_reveals_matches() {
+    let mut viewer = open_sample();
+    viewer.search("172.18");
+    assert_eq!(
+        viewer.selected_path().unwrap(),
+        ".NetworkSettings.Networks.backend.IPAddress"
+    );
+
+    viewer.search("status");
+    assert_eq!(viewer.selected_path().unwrap(), ".State.Status");
+
+    viewer.search("o");
+    let first = viewer.selected_path().unwrap();
+    viewer.next_match();
+    assert_ne!(viewer.selected_path().unwrap(), first);
+    viewer.previous_match();
+    assert_eq!(viewer.selected_path().unwrap(), first);
+}
+
+#[test]
+fn test_incremental_search_input() {
+    let mut viewer = open_sample();
+    viewer.begin_search();
+    for c in "runn".chars() {
+        viewer.push_input(c);
+    }
+    assert!(viewer.selected_path().unwrap().starts_with(".State."));
+    viewer.submit_input();
+    assert!(viewer.input.is_none());
+    assert_eq!(viewer.message.as_deref(), Some("2 matches for runn"));
+}
+
+#[test]
+fn test_selected_value() {
+    let mut viewer = open_sample();
+    viewer.jump_to_path(".Id").unwrap();
+    assert_eq!(viewer.selected_value().unwrap(), "abc123");
+
+    viewer.jump_to_path(".State.Running").unwrap();
+    assert_eq!(viewer.selected_value().unwrap(), "true");
+
+    viewer.jump_to_path(".State").unwrap();
+    let yanked: Value = serde_json::from_str(&viewer.selected_value().unwrap()).unwrap();
+    assert_eq!(yanked, json!({ "Status": "running", "Running": true }));
+}
+
+#[test]
+fn test_close_resets_state() {
+    let mut viewer = open_sample();
+    viewer.close();
+    assert!(!viewer.active);
+    assert_eq!(viewer.visible_count(), 0);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,261 @@
+// The log_pane module embeds a follow-mode log view in the main dprs screen.
+// It tails the selected container through a DockerLogWatcher and hands the
+// collected lines to the shared LogView widget used by dplw. When the cursor
+// moves to another container and stays there for a moment, the old watcher is
+// stopped and a new one is started, so the pane shows the container under the
+// cursor without starting a watcher for every container the cursor passes.
+// Errors from the watcher are shown as lines of the pane. The pane
+// can be placed below or beside the container list and maximized to take
+// over the whole main area.
+
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::shared::config::Config;
+use crate::shared::display::log_view::{render_titled_log_view, LogLevel, LogView};
+use crate::shared::docker::docker_log_watcher::DockerLogWatcher;
+use std::time::{Duration, Instant};
+
+const MAX_PANE_LOGS: usize = 1000;
+
+/// How long the cursor must rest on a container before the pane follows it.
+pub const FOLLOW_DELAY: Duration = Duration::from_millis(250);
+
+pub struct LogPaneState {
+    pub visible: bool,
+    pub maximized: bool,
+    pub page_height: usize,
+    container_name: Option<String>,
+    watcher: Option<DockerLogWatcher>,
+    log_view: LogView,
+    synced: usize,
+    /// The container to follow next and when the cursor moved to it.
+    pending: Option<(String, Instant)>,
+    error_shown: bool,
+}
+
+impl Default for LogPaneState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogPaneState {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            maximized: false,
+            page_height: 10,
+            container_name: None,
+            watcher: None,
+            log_view: LogView::new(MAX_PANE_LOGS),
+            synced: 0,
+            pending: None,
+            error_shown: false,
+        }
+    }
+
+    /// Shows or hides the pane. Hiding it stops tailing the container.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if !self.visible {
+            self.maximized = false;
+            self.follow(None);
+        }
+    }
+
+    /// Maximizes the pane, showing it first if it is hidden.
+    pub fn toggle_maximized(&mut self) {
+        if !self.visible {
+            self.visible = true;
+            self.maximized = true;
+        } else {
+            self.maximized = !self.maximized;
+        }
+    }
+
+    pub fn container_name(&self) -> Option<&str> {
+        self.container_name.as_deref()
+    }
+
+    /// Asks the pane to follow the given container. The switch happens in
+    /// `sync` once the container has stayed selected for `FOLLOW_DELAY`.
+    /// `None` stops tailing at once.
+    pub fn follow(&mut self, container_name: Option<&str>) {
+        let Some(name) = container_name else {
+            self.pending = None;
+            self.switch_to(None);
+            return;
+        };
+        if self.container_name.as_deref() == Some(name) {
+            self.pending = None;
+        } else if self.pending.as_ref().map(|(pending, _)| pending.as_str()) != Some(name) {
+            self.pending = Some((name.to_string(), Instant::now()));
+        }
+    }
+
+    /// Switches to the pending container if it was selected at least
+    /// `FOLLOW_DELAY` before `now`.
+    pub fn settle(&mut self, now: Instant) {
+        let Some((_, since)) = &self.pending else {
+            return;
+        };
+        if now.duration_since(*since) < FOLLOW_DELAY {
+            return;
+        }
+        if let Some((name, _)) = self.pending.take() {
+            self.switch_to(Some(&name));
+        }
+    }
+
+    /// Restarts the watcher on the given container.
+    fn switch_to(&mut self, container_name: Option<&str>) {
+        if self.container_name.as_deref() == container_name {
+            return;
+        }
+
+        if let Some(mut watcher) = self.watcher.take() {
+            watcher.stop();
+        }
+        self.container_name = container_name.map(|name| name.to_string());
+        self.log_view = LogView::new(MAX_PANE_LOGS);
+        self.synced = 0;
+        self.error_shown = false;
+
+        if let Some(name) = container_name {
+            let mut watcher = DockerLogWatcher::new(name.to_string(), MAX_PANE_LOGS);
+            match watcher.start() {
+                Ok(_) => self.watcher = Some(watcher),
+                Err(e) => self.log_view.add_log(
+                    format!("Failed to follow logs for {}: {}", name, e),
+                    LogLevel::Error,
+                ),
+            }
+        }
+    }
+
+    /// Follows a container the cursor settled on and pulls lines the
+    /// watcher received since the last call into the view, followed by the
+    /// watcher's error if it failed.
+    pub fn sync(&mut self) {
+        self.settle(Instant::now());
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+
+        let received = watcher.received_count();
+        let error = watcher.error().filter(|_| !self.error_shown);
+        if received != self.synced {
+            let logs = watcher.get_logs();
+            let new_count = (received - self.synced).min(logs.len());
+            self.synced = received;
+            self.append_lines(logs[logs.len() - new_count..].to_vec());
+        }
+        if let Some(error) = error {
+            self.error_shown = true;
+            self.log_view.add_log(error, LogLevel::Error);
+        }
+    }
+
+    pub fn append_lines(&mut self, lines: Vec<String>) {
+        for line in lines {
+            self.log_view.add_log(line, LogLevel::Info);
+        }
+    }
+
+    pub fn log_count(&self) -> usize {
+        self.log_view.get_log_count()
+    }
+
+    pub fn scroll_position(&mut self) -> usize {
+        self.log_view.get_scroll_position()
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.log_view.scroll_down();
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.log_view.scroll_up();
+    }
+
+    pub fn page_down(&mut self) {
+        self.log_view.page_down(self.page_height.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.log_view.page_up(self.page_height.max(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        // Paging rather than LogView::scroll_to_top so follow mode is left
+        self.log_view.page_up(self.log_view.get_log_count());
+    }
+
+    /// Jumps to the newest line and keeps following new output.
+    pub fn enable_follow(&mut self) {
+        self.log_view.enable_follow();
+    }
+}
+
+impl Drop for LogPaneState {
+    fn drop(&mut self) {
+        if let Some(watcher) = &mut self.watcher {
+            watcher.stop();
+        }
+    }
+}
+
+/// Splits the main area between the container list and the log pane.
+/// Returns the list area and, if the pane is visible, the pane area. A
+/// maximized pane gets the whole area and the list is not drawn.
+pub fn split_main_area(
+    log_pane: &LogPaneState,
+    area: Rect,
+    config: &Config,
+) -> (Option<Rect>, Option<Rect>) {
+    if !log_pane.visible {
+        return (Some(area), None);
+    }
+    if log_pane.maximized {
+        return (None, Some(area));
+    }
+
+    let pane_size = config.layout.log_pane_size.clamp(10, 90);
+    let direction = if config.layout.log_pane_position == "right" {
+        Direction::Horizontal
+    } else {
+        Direction::Vertical
+    };
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints([
+            Constraint::Percentage(100 - pane_size),
+            Constraint::Percentage(pane_size),
+        ])
+        .split(area);
+
+    (Some(chunks[0]), Some(chunks[1]))
+}
+
+pub fn render_log_pane<B: Backend>(
+    f: &mut Frame,
+    log_pane: &mut LogPaneState,
+    area: Rect,
+    config: &Config,
+) {
+    log_pane.page_height = area.height.saturating_sub(2) as usize;
+    let title = match &log_pane.container_name {
+        Some(name) => format!("Logs: {}", name),
+        None => "Logs: no container selected".to_string(),
+    };
+    render_titled_log_view::<B>(f, &mut log_pane.log_view, area, config, &title);
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,101 @@
+// The tests module for the log_pane component validates showing, hiding and
+// maximizing the pane, that it follows a container only once the cursor
+// settles on it, splitting the main area for each placement, and scrolling
+// through lines handed to the pane.
+
+use super::*;
+
+#[test]
+fn test_toggle_and_maximize() {
+    let mut pane = LogPaneState::new();
+    assert!(!pane.visible);
+
+    pane.toggle_maximized();
+    assert!(pane.visible);
+    assert!(pane.maximized);
+
+    pane.toggle_maximized();
+    assert!(pane.visible);
+    assert!(!pane.maximized);
+
+    pane.toggle_maximized();
+    pane.toggle();
+    assert!(!pane.visible);
+    assert!(!pane.maximized);
+    assert_eq!(pane.container_name(), None);
+}
+
+#[test]
+fn test_follow_waits_for_the_cursor_to_settle() {
+    let mut pane = LogPaneState::new();
+    let start = Instant::now();
+
+    // Passing over containers starts no watcher
+    pane.follow(Some("web-1"));
+    pane.follow(Some("web-2"));
+    pane.settle(start);
+    assert_eq!(pane.container_name(), None);
+    assert!(pane.watcher.is_none());
+
+    pane.follow(Some("web-3"));
+    pane.settle(Instant::now() + FOLLOW_DELAY);
+    assert_eq!(pane.container_name(), Some("web-3"));
+
+    // Moving back to the followed container cancels the pending switch
+    pane.follow(Some("web-4"));
+    pane.follow(Some("web-3"));
+    pane.settle(Instant::now() + FOLLOW_DELAY);
+    assert_eq!(pane.container_name(), Some("web-3"));
+
+    pane.follow(None);
+    assert_eq!(pane.container_name(), None);
+    assert!(pane.watcher.is_none());
+}
+
+#[test]
+fn test_split_main_area() {
+    let mut config = Config::default();
+    let mut pane = LogPaneState::new();
+    let area = Rect::new(0, 0, 100, 50);
+
+    assert_eq!(split_main_area(&pane, area, &config), (Some(area), None));
+
+    pane.visible = true;
+    let (list, logs) = split_main_area(&pane, area, &config);
+    assert_eq!(list, Some(Rect::new(0, 0, 100, 30)));
+    assert_eq!(logs, Some(Rect::new(0, 30, 100, 20)));
+
+    config.layout.log_pane_position = "right".to_string();
+    config.layout.log_pane_size = 50;
+    let (list, logs) = split_main_area(&pane, area, &config);
+    assert_eq!(list, Some(Rect::new(0, 0, 50, 50)));
+    assert_eq!(logs, Some(Rect::new(50, 0, 50, 50)));
+
+    pane.maximized = true;
+    assert_eq!(split_main_area(&pane, area, &config), (None, Some(area)));
+}
+
+#[test]
+fn test_scrolling_leaves_and_resumes_follow() {
+    let mut pane = LogPaneState::new();
+    pane.page_height = 5;
+    pane.append_lines((1..=20).map(|i| format!("line {}", i)).collect());
+    assert_eq!(pane.log_count(), 20);
+    assert_eq!(pane.scroll_position(), 19);
+
+    pane.page_up();
+    assert_eq!(pane.scroll_position(), 14);
+
+    // New lines do not move the view while scrolled back
+    pane.append_lines(vec!["line 21".to_string()]);
+    assert_eq!(pane.scroll_position(), 14);
+
+    pane.scroll_to_top();
+    assert_eq!(pane.scroll_position(), 0);
+
+    pane.enable_follow();
+    pane.append_lines(vec!["line 22".to_string()]);
+    assert_eq!(pane.scroll_position(), 21);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -1,9 +1,13 @@
 // DPRS display modules
 
 pub mod compose_view;
+pub mod confirm_dialog;
 pub mod context_menu;
 pub mod filter_input;
 pub mod hotkey_bar;
+pub mod json_viewer;
+pub mod log_pane;
+pub mod output_pager;
 pub mod process_list;
 pub mod process_list_tabular;
 pub mod toast;
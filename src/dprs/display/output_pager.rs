@@ -0,0 +1,512 @@
+// The output_pager module implements a full-screen pager for command output
+// that is too large for a toast, such as `:logs`. Any command can return
+// `CommandResult::Output` to open it. The pager supports vertical and
+// horizontal scrolling, incremental search, saving the output to a file and
+// copying it to the clipboard. Every opened output is kept in a bounded
+// history so earlier results can be reopened with `:output N`. Output of a
+// command that is still running is appended as it arrives.
+
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::path::PathBuf;
+
+use crate::shared::config::Config;
+
+const MAX_OUTPUT_HISTORY: usize = 50;
+
+#[derive(Clone, Debug)]
+pub struct OutputEntry {
+    pub id: usize,
+    pub title: String,
+    pub content: String,
+    pub created_at: chrono::DateTime<chrono::Local>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PagerInput {
+    Search(String),
+    SaveAs(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct OutputPagerState {
+    pub active: bool,
+    pub input: Option<PagerInput>,
+    pub message: Option<String>,
+    pub page_height: usize,
+    history: Vec<OutputEntry>,
+    next_id: usize,
+    current: Option<usize>,
+    lines: Vec<String>,
+    scroll: usize,
+    hscroll: usize,
+    search_query: String,
+    matches: Vec<usize>,
+    current_match: Option<usize>,
+}
+
+impl Default for OutputPagerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputPagerState {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            input: None,
+            message: None,
+            page_height: 10,
+            history: Vec::new(),
+            next_id: 0,
+            current: None,
+            lines: Vec::new(),
+            scroll: 0,
+            hscroll: 0,
+            search_query: String::new(),
+            matches: Vec::new(),
+            current_match: None,
+        }
+    }
+
+    /// Opens the pager on new output and records it in the history.
+    /// Returns the entry's id, for appending to it later.
+    pub fn open(&mut self, title: String, content: String) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.history.push(OutputEntry {
+            id,
+            title,
+            content,
+            created_at: chrono::Local::now(),
+        });
+        if self.history.len() > MAX_OUTPUT_HISTORY {
+            self.history.remove(0);
+        }
+        self.show(self.history.len() - 1);
+        id
+    }
+
+    /// Adds lines to an entry in the history. If it is on screen and the
+    /// view is at the bottom, the view follows the new lines. Returns false
+    /// once the entry has left the history.
+    pub fn append(&mut self, id: usize, lines: &[String]) -> bool {
+        let Some(index) = self.history.iter().position(|entry| entry.id == id) else {
+            return false;
+        };
+        let entry = &mut self.history[index];
+        if !entry.content.is_empty() && !entry.content.ends_with('\n') {
+            entry.content.push('\n');
+        }
+        for line in lines {
+            entry.content.push_str(line);
+            entry.content.push('\n');
+        }
+
+        if self.current == Some(index) {
+            let following = self.scroll >= self.max_scroll();
+            self.lines
+                .extend(lines.iter().map(|line| line.replace('\t', "    ")));
+            if following {
+                self.scroll_to_bottom();
+            }
+        }
+        true
+    }
+
+    /// Reopens a previous output. `n` counts back from the most recent
+    /// output, starting at 1.
+    pub fn reopen(&mut self, n: usize) -> Result<(), String> {
+        if self.history.is_empty() {
+            return Err("No previous output".to_string());
+        }
+        if n == 0 || n > self.history.len() {
+            return Err(format!(
+                "Output {} not found ({} available)",
+                n,
+                self.history.len()
+            ));
+        }
+        self.show(self.history.len() - n);
+        Ok(())
+    }
+
+    fn show(&mut self, index: usize) {
+        self.active = true;
+        self.current = Some(index);
+        self.lines = self.history[index]
+            .content
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect();
+        self.scroll = 0;
+        self.hscroll = 0;
+        self.input = None;
+        self.message = None;
+        self.search_query.clear();
+        self.matches.clear();
+        self.current_match = None;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+        self.input = None;
+        self.message = None;
+    }
+
+    pub fn history(&self) -> &[OutputEntry] {
+        &self.history
+    }
+
+    pub fn current_entry(&self) -> Option<&OutputEntry> {
+        self.current.and_then(|index| self.history.get(index))
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn scroll_position(&self) -> usize {
+        self.scroll
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.page_height.max(1))
+    }
+
+    fn scroll_to(&mut self, line: usize) {
+        self.scroll = line.min(self.max_scroll());
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_to(self.scroll + 1);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_to(self.scroll.saturating_sub(1));
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_to(self.scroll + self.page_height.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_to(self.scroll.saturating_sub(self.page_height.max(1)));
+    }
+
+    pub fn half_page_down(&mut self) {
+        self.scroll_to(self.scroll + (self.page_height / 2).max(1));
+    }
+
+    pub fn half_page_up(&mut self) {
+        self.scroll_to(self.scroll.saturating_sub((self.page_height / 2).max(1)));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.hscroll += 8;
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.hscroll = self.hscroll.saturating_sub(8);
+    }
+
+    pub fn begin_search(&mut self) {
+        self.input = Some(PagerInput::Search(String::new()));
+        self.message = None;
+    }
+
+    /// Prompts for a file name, suggesting one derived from the title.
+    pub fn begin_save(&mut self) {
+        let suggestion = self
+            .current_entry()
+            .map(|entry| default_file_name(&entry.title, &entry.created_at))
+            .unwrap_or_else(|| "dprs-output.txt".to_string());
+        self.input = Some(PagerInput::SaveAs(suggestion));
+        self.message = None;
+    }
+
+    pub fn push_input(&mut self, c: char) {
+        match self.input {
+            Some(PagerInput::Search(ref mut query)) => {
+                query.push(c);
+                let query = query.clone();
+                self.search(&query);
+            }
+            Some(PagerInput::SaveAs(ref mut path)) => path.push(c),
+            None => {}
+        }
+    }
+
+    pub fn pop_input(&mut self) {
+        match self.input {
+            Some(PagerInput::Search(ref mut query)) => {
+                query.pop();
+                let query = query.clone();
+                self.search(&query);
+            }
+            Some(PagerInput::SaveAs(ref mut path)) => {
+                path.pop();
+            }
+            None => {}
+        }
+    }
+
+    pub fn cancel_input(&mut self) {
+        self.input = None;
+    }
+
+    pub fn submit_input(&mut self) {
+        match self.input.take() {
+            Some(PagerInput::Search(query)) => {
+                self.message = if query.is_empty() {
+                    None
+                } else if self.matches.is_empty() {
+                    Some(format!("Pattern not found: {}", query))
+                } else {
+                    Some(format!(
+                        "{} matching lines for {}",
+                        self.matches.len(),
+                        query
+                    ))
+                };
+            }
+            Some(PagerInput::SaveAs(path)) => {
+                self.message = Some(match self.save_to_file(&path) {
+                    Ok(saved) => format!("Saved {} lines to {}", self.lines.len(), saved.display()),
+                    Err(e) => e,
+                });
+            }
+            None => {}
+        }
+    }
+
+    /// Finds lines containing `query` (case-insensitive) and scrolls to the
+    /// first match at or below the current position.
+    pub fn search(&mut self, query: &str) {
+        self.search_query = query.to_string();
+        self.matches.clear();
+        self.current_match = None;
+
+        if query.is_empty() {
+            return;
+        }
+
+        let query_lower = query.to_lowercase();
+        self.matches = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(&query_lower))
+            .map(|(index, _)| index)
+            .collect();
+
+        if !self.matches.is_empty() {
+            let next = self
+                .matches
+                .iter()
+                .position(|&line| line >= self.scroll)
+                .unwrap_or(0);
+            self.current_match = Some(next);
+            self.scroll_to(self.matches[next]);
+        }
+    }
+
+    pub fn next_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let next = self
+            .current_match
+            .map_or(0, |index| (index + 1) % self.matches.len());
+        self.current_match = Some(next);
+        self.scroll_to(self.matches[next]);
+    }
+
+    pub fn previous_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let previous = match self.current_match {
+            Some(0) | None => self.matches.len() - 1,
+            Some(index) => index - 1,
+        };
+        self.current_match = Some(previous);
+        self.scroll_to(self.matches[previous]);
+    }
+
+    /// Returns the full text of the output being shown.
+    pub fn content(&self) -> Option<&str> {
+        self.current_entry().map(|entry| entry.content.as_str())
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<PathBuf, String> {
+        let content = self.content().ok_or("No output to save")?;
+        let path = path.trim();
+        if path.is_empty() {
+            return Err("No file name given".to_string());
+        }
+
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .ok_or("Could not determine home directory")?
+                .join(rest),
+            None => PathBuf::from(path),
+        };
+
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+fn default_file_name(title: &str, created_at: &chrono::DateTime<chrono::Local>) -> String {
+    let slug: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!(
+        "dprs-{}-{}.txt",
+        if slug.is_empty() { "output" } else { &slug },
+        created_at.format("%Y%m%d-%H%M%S")
+    )
+}
+
+pub fn render_output_pager<B: Backend>(
+    f: &mut Frame,
+    pager: &mut OutputPagerState,
+    config: &Config,
+) {
+    if !pager.active {
+        return;
+    }
+
+    let area = f.area();
+    f.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .split(area);
+
+    // Borders take two rows of the text area.
+    pager.page_height = chunks[0].height.saturating_sub(2) as usize;
+    pager.scroll_to(pager.scroll);
+
+    let base_style = Style::default()
+        .bg(config.get_color("background_main"))
+        .fg(config.get_color("text_main"));
+    let current_match_line = pager
+        .current_match
+        .and_then(|index| pager.matches.get(index))
+        .copied();
+
+    let visible_lines: Vec<Line> = pager
+        .lines
+        .iter()
+        .enumerate()
+        .skip(pager.scroll)
+        .take(pager.page_height)
+        .map(|(index, line)| {
+            let text: String = line.chars().skip(pager.hscroll).collect();
+            let style = if Some(index) == current_match_line {
+                base_style
+                    .bg(config.get_color("search_highlight"))
+                    .add_modifier(Modifier::BOLD)
+            } else if pager.matches.binary_search(&index).is_ok() {
+                base_style.bg(config.get_color("search_highlight"))
+            } else {
+                base_style
+            };
+            Line::from(Span::styled(text, style))
+        })
+        .collect();
+
+    let (title, history_position) = match pager.current {
+        Some(index) => (
+            pager.history[index].title.clone(),
+            pager.history.len() - index,
+        ),
+        None => (String::new(), 0),
+    };
+    let position = if pager.lines.is_empty() {
+        "empty".to_string()
+    } else {
+        format!(
+            "{}-{}/{}",
+            pager.scroll + 1,
+            (pager.scroll + pager.page_height).min(pager.lines.len()),
+            pager.lines.len()
+        )
+    };
+
+    let text_widget = Paragraph::new(visible_lines).style(base_style).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "{} ({}) [output {}/{}]",
+                title,
+                position,
+                history_position,
+                pager.history.len()
+            ))
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.get_color("border_light")))
+            .style(Style::default().bg(config.get_color("background_main"))),
+    );
+    f.render_widget(text_widget, chunks[0]);
+
+    let footer = match &pager.input {
+        Some(PagerInput::Search(query)) => Line::from(format!("/{}", query)),
+        Some(PagerInput::SaveAs(path)) => Line::from(format!("Save to: {}", path)),
+        None => match &pager.message {
+            Some(message) => Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(config.get_color("message_warning")),
+            )),
+            None => Line::from(Span::styled(
+                "j/k: scroll | Ctrl+d/u: half page | g/G: top/bottom | h/l: left/right | /: search | n/N: next/prev | w: save | y: copy | q: close",
+                Style::default().fg(config.get_color("hotkey_gray")),
+            )),
+        },
+    };
+
+    let footer_widget = Paragraph::new(footer)
+        .style(Style::default().bg(config.get_color("background_dark")))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().bg(config.get_color("background_dark"))),
+        );
+    f.render_widget(footer_widget, chunks[1]);
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,186 @@
+// The tests module for the output_pager component validates scrolling
+// bounds, incremental search, the bounded output history reachable through
+// `reopen`, appending to running output, and saving the current output to
+// a file.
+
+use super::*;
+
+fn numbered_lines(count: usize) -> String {
+    (1..=count)
+        .map(|i| format!("line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_open_and_close() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs for web".to_string(), numbered_lines(3));
+    assert!(pager.active);
+    assert_eq!(pager.line_count(), 3);
+    assert_eq!(pager.history().len(), 1);
+
+    pager.close();
+    assert!(!pager.active);
+    assert_eq!(pager.history().len(), 1);
+}
+
+#[test]
+fn test_scrolling_is_bounded() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs".to_string(), numbered_lines(100));
+    pager.page_height = 10;
+
+    pager.scroll_up();
+    assert_eq!(pager.scroll_position(), 0);
+
+    pager.page_down();
+    assert_eq!(pager.scroll_position(), 10);
+
+    pager.half_page_down();
+    assert_eq!(pager.scroll_position(), 15);
+
+    pager.scroll_to_bottom();
+    assert_eq!(pager.scroll_position(), 90);
+
+    pager.scroll_down();
+    assert_eq!(pager.scroll_position(), 90);
+
+    pager.scroll_to_top();
+    assert_eq!(pager.scroll_position(), 0);
+}
+
+#[test]
+fn test_search_scrolls_to_matches() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs".to_string(), numbered_lines(100));
+    pager.page_height = 10;
+
+    pager.search("LINE 5");
+    // line 5 and lines 50-59
+    assert_eq!(pager.matches.len(), 11);
+    assert_eq!(pager.scroll_position(), 4);
+
+    pager.next_match();
+    assert_eq!(pager.scroll_position(), 49);
+
+    pager.previous_match();
+    assert_eq!(pager.scroll_position(), 4);
+
+    pager.previous_match();
+    assert_eq!(pager.scroll_position(), 58);
+}
+
+#[test]
+fn test_incremental_search_input() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs".to_string(), numbered_lines(20));
+    pager.page_height = 5;
+
+    pager.begin_search();
+    for c in "line 1".chars() {
+        pager.push_input(c);
+    }
+    pager.pop_input();
+    pager.push_input('2');
+    pager.submit_input();
+
+    // "line 2" and "line 20"
+    assert_eq!(
+        pager.message.as_deref(),
+        Some("2 matching lines for line 2")
+    );
+    assert_eq!(pager.scroll_position(), 1);
+}
+
+#[test]
+fn test_reopen_history() {
+    let mut pager = OutputPagerState::new();
+    assert!(pager.reopen(1).is_err());
+
+    pager.open("first".to_string(), "a".to_string());
+    pager.open("second".to_string(), "b\nc".to_string());
+    pager.close();
+
+    pager.reopen(2).unwrap();
+    assert!(pager.active);
+    assert_eq!(pager.current_entry().unwrap().title, "first");
+    assert_eq!(pager.content(), Some("a"));
+
+    pager.reopen(1).unwrap();
+    assert_eq!(pager.current_entry().unwrap().title, "second");
+    assert_eq!(pager.line_count(), 2);
+
+    assert!(pager.reopen(0).is_err());
+    assert!(pager.reopen(3).is_err());
+    // Reopening does not add to the history.
+    assert_eq!(pager.history().len(), 2);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut pager = OutputPagerState::new();
+    for i in 0..(MAX_OUTPUT_HISTORY + 5) {
+        pager.open(format!("output {}", i), String::new());
+    }
+    assert_eq!(pager.history().len(), MAX_OUTPUT_HISTORY);
+    assert_eq!(pager.history()[0].title, "output 5");
+}
+
+#[test]
+fn test_save_to_file() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs for web".to_string(), numbered_lines(3));
+
+    let path = std::env::temp_dir().join(format!("dprs-pager-test-{}.txt", std::process::id()));
+    let saved = pager.save_to_file(path.to_str().unwrap()).unwrap();
+    assert_eq!(std::fs::read_to_string(&saved).unwrap(), numbered_lines(3));
+    std::fs::remove_file(saved).unwrap();
+
+    assert!(pager.save_to_file("  ").is_err());
+}
+
+#[test]
+fn test_default_file_name() {
+    let mut pager = OutputPagerState::new();
+    pager.open("Logs for web-1:".to_string(), String::new());
+    pager.begin_save();
+    match &pager.input {
+        Some(PagerInput::SaveAs(name)) => {
+            assert!(name.starts_with("dprs-logs-for-web-1-"));
+            assert!(name.ends_with(".txt"));
+        }
+        other => panic!("unexpected input state: {:?}", other),
+    }
+}
+
+#[test]
+fn test_append_follows_the_bottom() {
+    let mut pager = OutputPagerState::new();
+    let id = pager.open("sh: make".to_string(), numbered_lines(20));
+    pager.page_height = 10;
+    pager.scroll_to_bottom();
+
+    let lines: Vec<String> = vec!["more".to_string(), "done".to_string()];
+    assert!(pager.append(id, &lines));
+    assert_eq!(pager.line_count(), 22);
+    assert_eq!(pager.scroll_position(), 12);
+
+    // Scrolled up, the view stays put
+    pager.scroll_to_top();
+    assert!(pager.append(id, &lines));
+    assert_eq!(pager.scroll_position(), 0);
+    assert!(pager
+        .current_entry()
+        .unwrap()
+        .content
+        .ends_with("more\ndone\n"));
+
+    // Output that is not on screen is still recorded
+    pager.open("other".to_string(), String::new());
+    assert!(pager.append(id, &lines));
+    assert_eq!(pager.line_count(), 0);
+    assert!(!pager.append(id + 5, &lines));
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -2,7 +2,8 @@
 // in a styled list format. It renders each container with details including name, image, status,
 // IP address, and port mappings. The module formats this information with appropriate colors and
 // styling to enhance readability, while also handling the selection state to highlight the currently
-// selected container. This component forms the main interactive area of the Docker Process Manager.
+// selected container and showing marked containers in a gutter. This component forms the main
+// interactive area of the Docker Process Manager.
 
 use ratatui::{
     backend::Backend,
@@ -23,6 +24,8 @@ pub fn render_container_list<B: Backend>(
     config: &Config,
 ) {
     let displayed_containers = app_state.get_displayed_containers();
+    // The mark gutter only takes space while something is marked
+    let has_marks = !app_state.marked.is_empty();
     let items: Vec<ListItem> = displayed_containers
         .iter()
         .enumerate()
@@ -45,7 +48,18 @@ pub fn render_container_list<B: Backend>(
             if is_search_match {
                 base_style = base_style.add_modifier(Modifier::UNDERLINED);
             }
+            let gutter = match (has_marks, app_state.is_marked(c)) {
+                (false, _) => "",
+                (true, false) => "  ",
+                (true, true) => "● ",
+            };
             let header = Line::from(vec![
+                Span::styled(
+                    gutter,
+                    base_style
+                        .fg(config.get_color("mark"))
+                        .add_modifier(Modifier::BOLD),
+                ),
                 Span::styled(
                     &c.name,
                     base_style
@@ -87,7 +101,11 @@ pub fn render_container_list<B: Backend>(
     let list = List::new(items)
         .block(
             Block::default()
-                .borders(Borders::ALL)
+                .borders(if config.layout.show_borders {
+                    Borders::ALL
+                } else {
+                    Borders::NONE
+                })
                 .title("Docker Containers")
                 .border_type(BorderType::Rounded)
                 .border_style(
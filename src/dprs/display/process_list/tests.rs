@@ -51,6 +51,7 @@ fn test_container_list_render_with_containers() {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             compose_project: None,
             labels: std::collections::HashMap::new(),
+            networks: Vec::new(),
         },
         Container {
             name: "database".to_string(),
@@ -65,6 +66,7 @@ fn test_container_list_render_with_containers() {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             compose_project: None,
             labels: std::collections::HashMap::new(),
+            networks: Vec::new(),
         },
     ];
     let config = Config::default();
@@ -99,6 +101,7 @@ fn test_container_list_with_selection() {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             compose_project: None,
             labels: std::collections::HashMap::new(),
+            networks: Vec::new(),
         },
         Container {
             name: "database".to_string(),
@@ -113,6 +116,7 @@ fn test_container_list_with_selection() {
             started_at: "2025-01-01T00:00:00Z".to_string(),
             compose_project: None,
             labels: std::collections::HashMap::new(),
+            networks: Vec::new(),
         },
     ];
 
@@ -149,6 +153,7 @@ fn test_container_list_styles() {
         started_at: "2025-01-01T00:00:00Z".to_string(),
         compose_project: None,
         labels: std::collections::HashMap::new(),
+        networks: Vec::new(),
     }];
     let config = Config::default();
 
@@ -4,7 +4,8 @@
 // tabular view offers a more compact and scannable layout compared to the
 // list-based display, making it easier to compare container information at a
 // glance. The module uses ratatui's Table widget with proper column widths,
-// headers, and row highlighting for the selected container.
+// headers, and row highlighting for the selected container, plus a gutter
+// column for marked containers.
 
 use ratatui::{
     backend::Backend,
@@ -23,9 +24,14 @@ pub fn render_container_table<B: Backend>(
     area: Rect,
     config: &Config,
 ) {
+    // A narrow gutter column shows marks while anything is marked
+    let has_marks = !app_state.marked.is_empty();
+    let gutter: &[&str] = if has_marks { &[""] } else { &[] };
+
     // Define table headers
-    let header_cells = ["Name", "Image", "Status", "IP Address", "Ports"]
+    let header_cells = gutter
         .iter()
+        .chain(["Name", "Image", "Status", "IP Address", "Ports"].iter())
         .map(|h| {
             Cell::from(*h).style(
                 Style::default()
@@ -42,7 +48,22 @@ pub fn render_container_table<B: Backend>(
     // Create rows from containers
     let displayed_containers = app_state.get_displayed_containers();
     let rows = displayed_containers.iter().map(|container| {
-        let cells = vec![
+        let mut cells = Vec::new();
+        if has_marks {
+            let marker = if app_state.is_marked(container) {
+                "●"
+            } else {
+                ""
+            };
+            cells.push(
+                Cell::from(marker).style(
+                    Style::default()
+                        .bg(config.get_color("background_very_dark"))
+                        .fg(config.get_color("mark")),
+                ),
+            );
+        }
+        cells.extend([
             Cell::from(container.name.clone()).style(
                 Style::default()
                     .bg(config.get_color("background_very_dark"))
@@ -68,25 +89,36 @@ pub fn render_container_table<B: Backend>(
                     .bg(config.get_color("background_very_dark"))
                     .fg(config.get_color("container_ports_tabular")),
             ),
-        ];
+        ]);
         Row::new(cells).height(1).bottom_margin(0)
     });
 
     // Define column widths
-    let widths = [
+    let mut widths = Vec::new();
+    if has_marks {
+        widths.push(Constraint::Length(1));
+    }
+    widths.extend([
         Constraint::Percentage(20),
         Constraint::Percentage(25),
         Constraint::Percentage(20),
         Constraint::Percentage(15),
         Constraint::Percentage(20),
-    ];
+    ]);
 
     // Create the table
-    let table = Table::new(rows, widths)
-        .header(header)
+    let mut table = Table::new(rows, widths);
+    if config.layout.show_headers {
+        table = table.header(header);
+    }
+    let table = table
         .block(
             Block::default()
-                .borders(Borders::ALL)
+                .borders(if config.layout.show_borders {
+                    Borders::ALL
+                } else {
+                    Borders::NONE
+                })
                 .title("Docker Containers")
                 .border_type(BorderType::Rounded)
                 .border_style(
@@ -3,7 +3,11 @@
 //
 // and contains submodules for specific interface components:
 //
+// - confirm_dialog: confirmation popup for destructive batch operations
 // - hotkey_bar: displays available keyboard shortcuts at the top of the screen
+// - json_viewer: full-screen collapsible tree for `:inspect` output
+// - log_pane: follow-mode logs for the selected container
+// - output_pager: full-screen pager for long command output
 // - process_list: renders the container list with details like name, status, and IP
 // - toast: implements a notification system for user feedback
 // - log_tabs: provides container selection tabs for the log viewer
@@ -21,9 +25,13 @@ use tachyonfx::{CellFilter, Duration, EffectManager, Interpolation};
 
 use crate::dprs::app::state_machine::AppState;
 use crate::dprs::display::compose_view::render_compose_view;
+use crate::dprs::display::confirm_dialog::render_confirm_dialog;
 use crate::dprs::display::context_menu::render_context_menu;
 use crate::dprs::display::filter_input::render_filter_input;
 use crate::dprs::display::hotkey_bar::render_hotkey_bar;
+use crate::dprs::display::json_viewer::render_json_viewer;
+use crate::dprs::display::log_pane::{render_log_pane, split_main_area};
+use crate::dprs::display::output_pager::render_output_pager;
 use crate::dprs::display::process_list::render_container_list;
 use crate::dprs::display::process_list_tabular::render_container_table;
 use crate::dprs::display::toast::ToastManager;
@@ -65,21 +73,27 @@ pub fn draw<B: Backend>(
     // Render the hotkey bar
     render_hotkey_bar::<B>(f, chunks[0], &*config);
 
+    // Share the main area with the log pane if it is open
+    let (list_area, log_area) = split_main_area(&app_state.log_pane, chunks[1], &*config);
+
     // Render container list (compose view, tabular, or normal based on mode)
-    let container_area = if app_state.compose_view_mode {
-        render_compose_view::<B>(f, app_state, chunks[1], &*config);
-        chunks[1]
-    } else if app_state.tabular_mode {
-        render_container_table::<B>(f, app_state, chunks[1], &*config);
-        chunks[1]
-    } else {
-        render_container_list::<B>(f, app_state, chunks[1], &*config);
-        chunks[1]
-    };
+    if let Some(container_area) = list_area {
+        if app_state.compose_view_mode {
+            render_compose_view::<B>(f, app_state, container_area, &*config);
+        } else if app_state.tabular_mode {
+            render_container_table::<B>(f, app_state, container_area, &*config);
+        } else {
+            render_container_list::<B>(f, app_state, container_area, &*config);
+        }
+
+        // Add swipe-in effects for new containers (skip if progress modal is active)
+        if !app_state.is_progress_active() {
+            add_container_effects(f, app_state, effects, container_area, elapsed, config);
+        }
+    }
 
-    // Add swipe-in effects for new containers (skip if progress modal is active)
-    if !app_state.is_progress_active() {
-        add_container_effects(f, app_state, effects, container_area, elapsed, config);
+    if let Some(log_area) = log_area {
+        render_log_pane::<B>(f, &mut app_state.log_pane, log_area, &*config);
     }
 
     // Render status line
@@ -132,6 +146,13 @@ pub fn draw<B: Backend>(
 
     // Render context menu if active
     render_context_menu::<B>(f, &app_state.context_menu, &*config);
+
+    // Render the full-screen viewers over everything else if open
+    render_output_pager::<B>(f, &mut app_state.output_pager, &*config);
+    render_json_viewer::<B>(f, &mut app_state.json_viewer, &*config);
+
+    // A pending confirmation is drawn above everything
+    render_confirm_dialog::<B>(f, &app_state.confirm_dialog, &*config);
 }
 
 fn render_status_line(
@@ -187,6 +208,20 @@ fn render_status_line(
         ));
     }
 
+    // Count and keys typed so far of an unfinished command
+    let pending_keys = app_state.key_sequence.pending();
+    if app_state.pending_count.is_some() || !pending_keys.is_empty() {
+        let count = app_state
+            .pending_count
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+        status_parts.push(format!("{}{}", count, pending_keys));
+    }
+
+    if !app_state.marked.is_empty() {
+        status_parts.push(format!("Marked: {}", app_state.marked.len()));
+    }
+
     // Visual selection info
     if let Some(selection) = &app_state.visual_selection {
         status_parts.push(format!(
@@ -240,8 +275,22 @@ fn render_command_line(
                     .fg(config.get_color("text_main"))
                     .bg(config.get_color("mode_command")),
             );
+            let command_state = &app_state.command_state;
+            let input = match &command_state.reverse_search {
+                Some(search) => format!(
+                    "({}reverse-i-search)'{}': {}",
+                    if command_state.reverse_search_failed() {
+                        "failing "
+                    } else {
+                        ""
+                    },
+                    search.query,
+                    command_state.input
+                ),
+                None => format!(":{}", command_state.input),
+            };
             let input_text = Span::styled(
-                format!(":{}", app_state.command_state.input),
+                input,
                 Style::default()
                     .fg(config.get_color("text_main"))
                     .bg(config.get_color("mode_command")),
@@ -256,6 +305,10 @@ fn render_command_line(
                 );
 
             f.render_widget(command_widget, popup_area);
+
+            if app_state.completion.is_active() {
+                render_completion_popup(f, app_state, popup_area, config);
+            }
         }
         Mode::Search => {
             let popup_area = ratatui::layout::Rect {
@@ -307,6 +360,60 @@ fn render_command_line(
     }
 }
 
+fn render_completion_popup(
+    f: &mut Frame,
+    app_state: &AppState,
+    command_area: Rect,
+    config: &Config,
+) {
+    use ratatui::widgets::{Clear, List, ListItem, ListState};
+
+    const MAX_VISIBLE: usize = 10;
+    let completion = &app_state.completion;
+
+    let longest = completion
+        .candidates
+        .iter()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = ((longest + 4) as u16).max(20).min(command_area.width);
+    let height = (completion.candidates.len().min(MAX_VISIBLE) + 2) as u16;
+
+    // Line the popup up with the word being completed: the border, the
+    // "-- COMMAND -- " indicator and the ':' prompt come first.
+    let word_x = command_area.x + 1 + 14 + 1 + completion.word_start() as u16;
+    let x = word_x.min(command_area.width.saturating_sub(width));
+    let y = command_area.y.saturating_sub(height);
+    let area = Rect {
+        x,
+        y,
+        width,
+        height: height.min(command_area.y),
+    };
+
+    let items: Vec<ListItem> = completion
+        .candidates
+        .iter()
+        .map(|c| ListItem::new(c.as_str()))
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(config.get_color("border_main")))
+                .style(Style::default().bg(config.get_color("background_dark"))),
+        )
+        .style(Style::default().fg(config.get_color("text_main")))
+        .highlight_style(Style::default().bg(config.get_color("selected_bg")));
+
+    let mut list_state = ListState::default();
+    list_state.select(completion.selected);
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
 fn render_progress_modal(
     f: &mut Frame,
     app_state: &AppState,
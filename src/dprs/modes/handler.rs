@@ -6,6 +6,10 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::collections::HashSet;
 
+use super::history::push_history;
+
+const DEFAULT_MAX_HISTORY: usize = 100;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Mode {
     Normal,
@@ -59,6 +63,14 @@ impl VisualSelection {
     pub fn is_selected(&self, index: usize) -> bool {
         self.selected_indices.contains(&index)
     }
+
+    /// First and last index of the selection.
+    pub fn range(&self) -> (usize, usize) {
+        (
+            self.start_index.min(self.current_index),
+            self.start_index.max(self.current_index),
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +79,16 @@ pub struct CommandState {
     pub cursor_pos: usize,
     pub history: Vec<String>,
     pub history_index: Option<usize>,
+    pub max_history: usize,
+    pub reverse_search: Option<ReverseSearch>,
+}
+
+/// State of a Ctrl+R reverse incremental search through the history.
+#[derive(Debug, Clone)]
+pub struct ReverseSearch {
+    pub query: String,
+    pub match_index: Option<usize>,
+    original_input: String,
 }
 
 impl Default for CommandState {
@@ -82,6 +104,8 @@ impl CommandState {
             cursor_pos: 0,
             history: Vec::new(),
             history_index: None,
+            max_history: DEFAULT_MAX_HISTORY,
+            reverse_search: None,
         }
     }
 
@@ -89,17 +113,91 @@ impl CommandState {
         self.input.clear();
         self.cursor_pos = 0;
         self.history_index = None;
+        self.reverse_search = None;
     }
 
     pub fn add_to_history(&mut self, command: String) {
-        if !command.is_empty() && !self.history.contains(&command) {
-            self.history.push(command);
-            if self.history.len() > 100 {
-                self.history.remove(0);
-            }
+        push_history(&mut self.history, command, self.max_history);
+    }
+
+    pub fn start_reverse_search(&mut self) {
+        self.reverse_search = Some(ReverseSearch {
+            query: String::new(),
+            match_index: None,
+            original_input: self.input.clone(),
+        });
+    }
+
+    /// Extends the query, keeping the current match if it still matches.
+    pub fn reverse_search_push(&mut self, c: char) {
+        if let Some(search) = &mut self.reverse_search {
+            search.query.push(c);
+            let before = search.match_index.map_or(self.history.len(), |i| i + 1);
+            self.find_reverse_match(before);
+        }
+    }
+
+    /// Shortens the query and searches again from the newest entry.
+    pub fn reverse_search_pop(&mut self) {
+        if let Some(search) = &mut self.reverse_search {
+            search.query.pop();
+            self.find_reverse_match(self.history.len());
+        }
+    }
+
+    /// Moves on to the next older entry matching the query.
+    pub fn reverse_search_next(&mut self) {
+        if let Some(search) = &self.reverse_search {
+            let before = search.match_index.unwrap_or(self.history.len());
+            self.find_reverse_match(before);
+        }
+    }
+
+    /// Ends the search, keeping the matched entry in the input.
+    pub fn accept_reverse_search(&mut self) {
+        self.reverse_search = None;
+    }
+
+    /// Ends the search and restores the input from before it started.
+    pub fn cancel_reverse_search(&mut self) {
+        if let Some(search) = self.reverse_search.take() {
+            self.input = search.original_input;
+            self.cursor_pos = self.input.len();
         }
     }
 
+    fn find_reverse_match(&mut self, before: usize) {
+        let Some(search) = &mut self.reverse_search else {
+            return;
+        };
+
+        if search.query.is_empty() {
+            search.match_index = None;
+            return;
+        }
+
+        let found = self.history[..before.min(self.history.len())]
+            .iter()
+            .rposition(|entry| entry.contains(&search.query));
+        // A failed search keeps the previous match on the command line
+        if let Some(index) = found {
+            search.match_index = Some(index);
+            self.input = self.history[index].clone();
+            self.cursor_pos = self.input.len();
+            self.history_index = None;
+        }
+    }
+
+    /// True if the last reverse search keystroke found nothing.
+    pub fn reverse_search_failed(&self) -> bool {
+        self.reverse_search.as_ref().is_some_and(|search| {
+            !search.query.is_empty()
+                && search
+                    .match_index
+                    .is_none_or(|i| !self.history[i].contains(&search.query))
+        })
+    }
+
     pub fn navigate_history(&mut self, up: bool) {
         if self.history.is_empty() {
             return;
@@ -201,6 +299,9 @@ pub struct SearchState {
     pub current_match: Option<usize>,
     pub matches: Vec<usize>,
     pub last_query: String,
+    pub history: Vec<String>,
+    pub history_index: Option<usize>,
+    pub max_history: usize,
 }
 
 impl Default for SearchState {
@@ -217,6 +318,9 @@ impl SearchState {
             current_match: None,
             matches: Vec::new(),
             last_query: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            max_history: DEFAULT_MAX_HISTORY,
         }
     }
 
@@ -224,6 +328,29 @@ impl SearchState {
         self.query.clear();
         self.current_match = None;
         self.matches.clear();
+        self.history_index = None;
+    }
+
+    pub fn add_to_history(&mut self, query: String) {
+        push_history(&mut self.history, query, self.max_history);
+    }
+
+    /// Replaces the query with an older (`up`) or newer history entry.
+    pub fn navigate_history(&mut self, up: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+
+        self.history_index = match (self.history_index, up) {
+            (None, true) => Some(self.history.len() - 1),
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < self.history.len() => Some(index + 1),
+            (Some(_), false) | (None, false) => None,
+        };
+        self.query = match self.history_index {
+            Some(index) => self.history[index].clone(),
+            None => String::new(),
+        };
     }
 
     pub fn set_query(&mut self, query: String, is_forward: bool) {
@@ -0,0 +1,94 @@
+// The history module persists command and search history across sessions.
+// Both histories share one plain text file next to the config file, one entry
+// per line: command entries start with ':' and search entries with '/'.
+// Entries are deduplicated, keeping the most recent occurrence, and capped at
+// `max_history_items` from the general config.
+
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Adds an entry to a history list, moving it to the end if it is already
+/// present and dropping the oldest entries beyond `max_items`.
+pub fn push_history(history: &mut Vec<String>, entry: String, max_items: usize) {
+    if entry.is_empty() {
+        return;
+    }
+    history.retain(|existing| existing != &entry);
+    history.push(entry);
+    if history.len() > max_items {
+        let excess = history.len() - max_items;
+        history.drain(..excess);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    pub commands: Vec<String>,
+    pub searches: Vec<String>,
+}
+
+impl History {
+    pub fn parse(content: &str, max_items: usize) -> Self {
+        let mut history = Self::default();
+        for line in content.lines() {
+            if let Some(command) = line.strip_prefix(':') {
+                push_history(&mut history.commands, command.to_string(), max_items);
+            } else if let Some(query) = line.strip_prefix('/') {
+                push_history(&mut history.searches, query.to_string(), max_items);
+            }
+        }
+        history
+    }
+
+    pub fn to_file_content(&self) -> String {
+        let mut content = String::new();
+        for command in &self.commands {
+            content.push(':');
+            content.push_str(command);
+            content.push('\n');
+        }
+        for query in &self.searches {
+            content.push('/');
+            content.push_str(query);
+            content.push('\n');
+        }
+        content
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryFile {
+    path: PathBuf,
+}
+
+impl HistoryFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the history file. A missing file is an empty history.
+    pub fn load(&self, max_items: usize) -> Result<History, Error> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(History::parse(&content, max_items)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(History::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, history: &History) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, history.to_file_content())
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
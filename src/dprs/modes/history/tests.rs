@@ -0,0 +1,89 @@
+// The tests module for history persistence validates deduplication and
+// capping, the on-disk format, and Ctrl+R reverse search over the command
+// history.
+
+use super::*;
+use crate::dprs::modes::CommandState;
+
+#[test]
+fn test_push_history_dedupes_and_caps() {
+    let mut history = Vec::new();
+    push_history(&mut history, "stop web".to_string(), 3);
+    push_history(&mut history, "logs db".to_string(), 3);
+    push_history(&mut history, "stop web".to_string(), 3);
+    assert_eq!(history, vec!["logs db", "stop web"]);
+
+    push_history(&mut history, "ps".to_string(), 3);
+    push_history(&mut history, "inspect api".to_string(), 3);
+    push_history(&mut history, String::new(), 3);
+    assert_eq!(history, vec!["stop web", "ps", "inspect api"]);
+}
+
+#[test]
+fn test_parse_and_serialize() {
+    let content = ":stop web\n/api\n:logs db\n:stop web\n\nnot an entry\n/:colon\n";
+    let history = History::parse(content, 10);
+    assert_eq!(history.commands, vec!["logs db", "stop web"]);
+    assert_eq!(history.searches, vec!["api", ":colon"]);
+
+    let reparsed = History::parse(&history.to_file_content(), 10);
+    assert_eq!(reparsed, history);
+
+    let capped = History::parse(content, 1);
+    assert_eq!(capped.commands, vec!["stop web"]);
+}
+
+#[test]
+fn test_history_file_round_trip() {
+    let dir = std::env::temp_dir().join(format!("dprs-history-test-{}", std::process::id()));
+    let file = HistoryFile::new(dir.join("history"));
+    assert_eq!(file.load(10).unwrap(), History::default());
+
+    let history = History {
+        commands: vec!["ps".to_string()],
+        searches: vec!["web".to_string()],
+    };
+    file.save(&history).unwrap();
+    assert_eq!(file.load(10).unwrap(), history);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_reverse_search() {
+    let mut state = CommandState::new();
+    for command in ["stop web-1", "logs db", "stop web-2", "ps"] {
+        state.add_to_history(command.to_string());
+    }
+    state.input = "draft".to_string();
+
+    state.start_reverse_search();
+    state.reverse_search_push('s');
+    state.reverse_search_push('t');
+    assert_eq!(state.input, "stop web-2");
+
+    state.reverse_search_next();
+    assert_eq!(state.input, "stop web-1");
+
+    // No older match: the last match stays and the search reports failure
+    state.reverse_search_next();
+    assert_eq!(state.input, "stop web-1");
+    state.reverse_search_push('x');
+    assert!(state.reverse_search_failed());
+
+    state.reverse_search_pop();
+    assert!(!state.reverse_search_failed());
+    assert_eq!(state.input, "stop web-2");
+
+    state.cancel_reverse_search();
+    assert_eq!(state.input, "draft");
+    assert!(state.reverse_search.is_none());
+
+    state.start_reverse_search();
+    state.reverse_search_push('d');
+    state.accept_reverse_search();
+    assert_eq!(state.input, "logs db");
+    assert_eq!(state.cursor_pos, 7);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,248 @@
+// The keymap module turns key presses into AppEvents through the keybinding
+// tables of the config. Keys are named by `key_event_to_string`, and a
+// sequence such as `gg` is the names of its keys joined together, as
+// `split_key_sequence` reads them back. While the keys typed so far could
+// still grow into a bound sequence they are held as pending, and dropped if
+// the next key does not arrive within SEQUENCE_TIMEOUT. Visual mode looks
+// keys up in its own table first, where an empty action unmaps a key, and
+// falls back to the normal mode table for motions only, so keys such as `q`
+// do not act from visual mode unless it binds them itself.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::Mode;
+use crate::dprs::app::state_machine::AppEvent;
+use crate::shared::config::{split_key_sequence, Config};
+
+/// How long a partial sequence waits for its next key.
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyDispatch {
+    Event(AppEvent),
+    /// The keys so far are the start of a longer sequence.
+    Pending,
+    /// The key is bound to an action name that does not exist.
+    UnknownAction(String),
+    Unbound,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeySequence {
+    pending: Vec<String>,
+    last_key: Option<Instant>,
+}
+
+impl KeySequence {
+    /// The keys typed so far of an unfinished sequence.
+    pub fn pending(&self) -> String {
+        self.pending.concat()
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.last_key = None;
+    }
+
+    /// Drops a partial sequence that has waited too long. Returns true if
+    /// one was dropped.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        match self.last_key {
+            Some(last_key) if now.duration_since(last_key) >= SEQUENCE_TIMEOUT => {
+                self.clear();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds a key to the sequence and reports what it resolves to. A key
+    /// that cannot continue the pending sequence starts a new one, as in vim.
+    pub fn feed(&mut self, key: &str, mode: &Mode, config: &Config, now: Instant) -> KeyDispatch {
+        self.expire(now);
+
+        let mut sequence = self.pending.clone();
+        sequence.push(key.to_string());
+        if let Some(dispatch) = self.resolve(sequence, mode, config, now) {
+            return dispatch;
+        }
+        if !self.pending.is_empty() {
+            self.clear();
+            if let Some(dispatch) = self.resolve(vec![key.to_string()], mode, config, now) {
+                return dispatch;
+            }
+        }
+        self.clear();
+        KeyDispatch::Unbound
+    }
+
+    fn resolve(
+        &mut self,
+        sequence: Vec<String>,
+        mode: &Mode,
+        config: &Config,
+        now: Instant,
+    ) -> Option<KeyDispatch> {
+        // A bound sequence fires at once, even if a longer one starts with it
+        if let Some(action) = lookup(config, mode, &sequence) {
+            self.clear();
+            return Some(match AppEvent::from_action(action) {
+                Some(event) => KeyDispatch::Event(event),
+                None => KeyDispatch::UnknownAction(action.to_string()),
+            });
+        }
+        if has_longer_binding(config, mode, &sequence) {
+            self.pending = sequence;
+            self.last_key = Some(now);
+            return Some(KeyDispatch::Pending);
+        }
+        None
+    }
+}
+
+/// The action bound to a key sequence in the given mode. Sequences are
+/// compared key by key, so `ctrl+u` in the config matches `Ctrl+u`.
+pub fn lookup<'a>(config: &'a Config, mode: &Mode, sequence: &[String]) -> Option<&'a str> {
+    let keybindings = &config.keybindings;
+    let action = match mode {
+        Mode::Visual => find_binding(&keybindings.visual_mode, sequence).or_else(|| {
+            find_binding(&keybindings.normal_mode, sequence)
+                .filter(|action| AppEvent::from_action(action).is_some_and(|e| e.is_motion()))
+        }),
+        _ => tables(config, mode)
+            .into_iter()
+            .find_map(|table| find_binding(table, sequence)),
+    };
+    action.filter(|a| !a.is_empty())
+}
+
+/// The tables a mode looks keys up in, in order. An entry in an earlier
+/// table hides one for the same keys in a later table; visual mode only
+/// takes motions from the normal mode table.
+pub fn tables<'a>(config: &'a Config, mode: &Mode) -> Vec<&'a HashMap<String, String>> {
+    let keybindings = &config.keybindings;
+    match mode {
+        Mode::Visual => vec![&keybindings.visual_mode, &keybindings.normal_mode],
+        Mode::Command => vec![&keybindings.command_mode],
+        _ => vec![&keybindings.normal_mode],
+    }
+}
+
+fn find_binding<'a>(table: &'a HashMap<String, String>, sequence: &[String]) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(keys, _)| split_key_sequence(keys).is_ok_and(|keys| keys == sequence))
+        .map(|(_, action)| action.as_str())
+}
+
+fn has_longer_binding(config: &Config, mode: &Mode, sequence: &[String]) -> bool {
+    tables(config, mode)
+        .into_iter()
+        .flat_map(|table| table.keys())
+        .filter_map(|keys| split_key_sequence(keys).ok())
+        .any(|keys| {
+            keys.len() > sequence.len()
+                && keys.starts_with(sequence)
+                && lookup(config, mode, &keys).is_some()
+        })
+}
+
+/// Checks the keybinding tables for actions that do not exist, keys bound
+/// twice under different spellings, sequences that can never be typed
+/// because a shorter prefix is bound, and visual mode unmaps of keys that
+/// normal mode does not bind.
+pub fn validate_keybindings(config: &Config) -> Vec<String> {
+    let keybindings = &config.keybindings;
+    let mut problems = Vec::new();
+
+    for (table_name, table, mode) in [
+        ("normal_mode", &keybindings.normal_mode, Mode::Normal),
+        ("visual_mode", &keybindings.visual_mode, Mode::Visual),
+        ("command_mode", &keybindings.command_mode, Mode::Command),
+    ] {
+        let mut entries: Vec<_> = table.iter().collect();
+        entries.sort();
+        let mut seen: HashMap<Vec<String>, &String> = HashMap::new();
+
+        for (keys, action) in entries {
+            let sequence = match split_key_sequence(keys) {
+                Ok(sequence) => sequence,
+                Err(e) => {
+                    problems.push(format!("{}: '{}': {}", table_name, keys, e));
+                    continue;
+                }
+            };
+            if let Some(other) = seen.insert(sequence.clone(), keys) {
+                problems.push(format!(
+                    "{}: '{}' is bound twice, also as '{}'",
+                    table_name, keys, other
+                ));
+            }
+
+            if action.is_empty() {
+                let falls_back = find_binding(&keybindings.normal_mode, &sequence)
+                    .and_then(AppEvent::from_action)
+                    .is_some_and(|event| event.is_motion());
+                if mode == Mode::Visual && !falls_back {
+                    problems.push(format!(
+                        "{}: '{}' is unmapped but normal_mode does not bind it to a motion",
+                        table_name, keys
+                    ));
+                }
+                continue;
+            }
+            if AppEvent::from_action(action).is_none() {
+                problems.push(format!(
+                    "{}: '{}' is bound to unknown action '{}'",
+                    table_name, keys, action
+                ));
+            }
+            if let Some(problem) = shadowing_prefix(config, &mode, &sequence) {
+                problems.push(format!("{}: '{}' {}", table_name, keys, problem));
+            }
+        }
+    }
+
+    // Visual mode can also hide normal mode sequences behind its own keys
+    let mut normal_keys: Vec<_> = keybindings.normal_mode.keys().collect();
+    normal_keys.sort();
+    for keys in normal_keys {
+        let Ok(sequence) = split_key_sequence(keys) else {
+            continue;
+        };
+        if find_binding(&keybindings.visual_mode, &sequence).is_some()
+            || lookup(config, &Mode::Visual, &sequence).is_none()
+        {
+            continue;
+        }
+        let shadowed_in_visual = (1..sequence.len()).any(|i| {
+            find_binding(&keybindings.visual_mode, &sequence[..i]).is_some_and(|a| !a.is_empty())
+        });
+        if shadowed_in_visual {
+            if let Some(problem) = shadowing_prefix(config, &Mode::Visual, &sequence) {
+                problems.push(format!("visual_mode: '{}' {}", keys, problem));
+            }
+        }
+    }
+
+    problems
+}
+
+fn shadowing_prefix(config: &Config, mode: &Mode, sequence: &[String]) -> Option<String> {
+    (1..sequence.len()).find_map(|i| {
+        let prefix = &sequence[..i];
+        lookup(config, mode, prefix).map(|action| {
+            format!(
+                "can never be typed, since '{}' is bound to {}",
+                prefix.concat(),
+                action
+            )
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
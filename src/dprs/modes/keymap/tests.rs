@@ -0,0 +1,183 @@
+// The tests module for the keymap covers single keys and sequences, the
+// timeout on partial sequences, remapped keys, and visual mode's motion
+// fallback and unmaps.
+
+use super::*;
+
+fn feed(sequence: &mut KeySequence, keys: &[&str], mode: &Mode, config: &Config) -> KeyDispatch {
+    let now = Instant::now();
+    let mut dispatch = KeyDispatch::Unbound;
+    for key in keys {
+        dispatch = sequence.feed(key, mode, config, now);
+    }
+    dispatch
+}
+
+#[test]
+fn test_single_keys_and_sequences() {
+    let config = Config::default();
+    let mut sequence = KeySequence::default();
+
+    assert_eq!(
+        feed(&mut sequence, &["j"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::SelectNext)
+    );
+    assert_eq!(
+        feed(&mut sequence, &["g"], &Mode::Normal, &config),
+        KeyDispatch::Pending
+    );
+    assert_eq!(sequence.pending(), "g");
+    assert_eq!(
+        feed(&mut sequence, &["g"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::GoToFirst)
+    );
+    assert_eq!(sequence.pending(), "");
+
+    // A key that cannot continue the sequence starts over
+    assert_eq!(
+        feed(&mut sequence, &["g", "k"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::SelectPrevious)
+    );
+    assert_eq!(
+        feed(&mut sequence, &["g", "x"], &Mode::Normal, &config),
+        KeyDispatch::Unbound
+    );
+    assert_eq!(sequence.pending(), "");
+}
+
+#[test]
+fn test_sequence_timeout() {
+    let config = Config::default();
+    let mut sequence = KeySequence::default();
+    let start = Instant::now();
+
+    assert_eq!(
+        sequence.feed("g", &Mode::Normal, &config, start),
+        KeyDispatch::Pending
+    );
+    assert!(!sequence.expire(start + SEQUENCE_TIMEOUT / 2));
+    assert!(sequence.expire(start + SEQUENCE_TIMEOUT));
+    assert_eq!(sequence.pending(), "");
+
+    // After the timeout the second `g` starts a new sequence
+    sequence.feed("g", &Mode::Normal, &config, start);
+    assert_eq!(
+        sequence.feed("g", &Mode::Normal, &config, start + SEQUENCE_TIMEOUT),
+        KeyDispatch::Pending
+    );
+}
+
+#[test]
+fn test_remapped_keys() {
+    let mut config = Config::default();
+    let normal = &mut config.keybindings.normal_mode;
+    normal.remove("s");
+    normal.insert("x".to_string(), "StopContainer".to_string());
+    normal.insert("dd".to_string(), "StopContainer".to_string());
+    normal.insert("z".to_string(), "NoSuchAction".to_string());
+    let mut sequence = KeySequence::default();
+
+    assert_eq!(
+        feed(&mut sequence, &["s"], &Mode::Normal, &config),
+        KeyDispatch::Unbound
+    );
+    assert_eq!(
+        feed(&mut sequence, &["x"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::StopContainer)
+    );
+    assert_eq!(
+        feed(&mut sequence, &["d", "d"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::StopContainer)
+    );
+    assert_eq!(
+        feed(&mut sequence, &["z"], &Mode::Normal, &config),
+        KeyDispatch::UnknownAction("NoSuchAction".to_string())
+    );
+}
+
+#[test]
+fn test_visual_mode_fallback_and_unmap() {
+    let mut config = Config::default();
+    let mut sequence = KeySequence::default();
+
+    assert_eq!(
+        feed(&mut sequence, &["s"], &Mode::Visual, &config),
+        KeyDispatch::Event(AppEvent::StopSelectedContainers)
+    );
+    // Motions come from normal mode, other normal mode keys do not
+    assert_eq!(
+        feed(&mut sequence, &["g", "g"], &Mode::Visual, &config),
+        KeyDispatch::Event(AppEvent::GoToFirst)
+    );
+    assert_eq!(
+        feed(&mut sequence, &["q"], &Mode::Visual, &config),
+        KeyDispatch::Unbound
+    );
+    assert_eq!(
+        feed(&mut sequence, &["p"], &Mode::Visual, &config),
+        KeyDispatch::Unbound
+    );
+    assert_eq!(
+        feed(&mut sequence, &[":"], &Mode::Visual, &config),
+        KeyDispatch::Event(AppEvent::EnterCommandMode)
+    );
+
+    config
+        .keybindings
+        .visual_mode
+        .insert("G".to_string(), String::new());
+    assert_eq!(
+        feed(&mut sequence, &["G"], &Mode::Visual, &config),
+        KeyDispatch::Unbound
+    );
+    assert_eq!(
+        feed(&mut sequence, &["q"], &Mode::Normal, &config),
+        KeyDispatch::Event(AppEvent::Quit)
+    );
+}
+
+#[test]
+fn test_validate_keybindings() {
+    assert_eq!(
+        validate_keybindings(&Config::default()),
+        Vec::<String>::new()
+    );
+
+    let mut config = Config::default();
+    let keybindings = &mut config.keybindings;
+    keybindings
+        .normal_mode
+        .insert("x".to_string(), "StopContainr".to_string());
+    keybindings
+        .normal_mode
+        .insert("ctrl+u".to_string(), "HalfPageUp".to_string());
+    keybindings
+        .normal_mode
+        .insert("g".to_string(), "GoToLast".to_string());
+    keybindings
+        .visual_mode
+        .insert("X".to_string(), String::new());
+    keybindings
+        .visual_mode
+        .insert("q".to_string(), String::new());
+    keybindings
+        .visual_mode
+        .insert("d".to_string(), "StopSelectedContainers".to_string());
+    keybindings
+        .normal_mode
+        .insert("dd".to_string(), "HalfPageDown".to_string());
+
+    assert_eq!(
+        validate_keybindings(&config),
+        vec![
+            "normal_mode: 'ctrl+u' is bound twice, also as 'Ctrl+u'",
+            "normal_mode: 'gg' can never be typed, since 'g' is bound to GoToLast",
+            "normal_mode: 'x' is bound to unknown action 'StopContainr'",
+            "visual_mode: 'X' is unmapped but normal_mode does not bind it to a motion",
+            "visual_mode: 'q' is unmapped but normal_mode does not bind it to a motion",
+            "visual_mode: 'dd' can never be typed, since 'd' is bound to StopSelectedContainers",
+        ]
+    );
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -3,5 +3,7 @@
 pub use handler::*;
 
 mod handler;
+pub mod history;
+pub mod keymap;
 
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,156 @@
+// The files module finds the config files and combines them. The user
+// config lives at `$XDG_CONFIG_HOME/dprs/config.toml` (`~/.config` when the
+// variable is unset), or at the original `~/.dprs/config` if only that one
+// exists, unless `--config` names another file. A `.dprs.toml` found in the
+// current directory or one of its parents is merged over the user config, so
+// a repository can ship context menu actions, aliases and filters for its
+// compose stack. Tables merge key by key and the project's context menu
+// actions are added after the user's.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use toml::Value;
+
+pub const PROJECT_CONFIG_FILE: &str = ".dprs.toml";
+
+/// Arrays that a project config extends instead of replacing.
+const APPENDED_ARRAYS: &[&str] = &["context_menu.actions"];
+
+static CONFIG_FILE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Uses `path` as the user config file, for `--config`. Only the first call
+/// has an effect.
+pub fn set_config_file_override(path: PathBuf) {
+    let _ = CONFIG_FILE_OVERRIDE.set(path);
+}
+
+pub fn user_config_path() -> PathBuf {
+    if let Some(path) = CONFIG_FILE_OVERRIDE.get() {
+        return path.clone();
+    }
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let xdg_config_home = std::env::var("XDG_CONFIG_HOME").ok();
+    choose_user_config(
+        &user_config_candidates(&home, xdg_config_home.as_deref()),
+        |path| path.exists(),
+    )
+}
+
+/// The XDG location first, then the original `~/.dprs/config`. A relative
+/// XDG_CONFIG_HOME is ignored, as the spec asks.
+pub fn user_config_candidates(home: &Path, xdg_config_home: Option<&str>) -> Vec<PathBuf> {
+    let config_home = match xdg_config_home {
+        Some(dir) if Path::new(dir).is_absolute() => PathBuf::from(dir),
+        _ => home.join(".config"),
+    };
+    vec![
+        config_home.join("dprs").join("config.toml"),
+        home.join(".dprs").join("config"),
+    ]
+}
+
+/// The first candidate that exists, or the first one if none does so a
+/// new config is created there.
+pub fn choose_user_config(candidates: &[PathBuf], exists: impl Fn(&Path) -> bool) -> PathBuf {
+    candidates
+        .iter()
+        .find(|path| exists(path))
+        .unwrap_or(&candidates[0])
+        .clone()
+}
+
+/// The nearest `.dprs.toml` in `start` or one of its parents.
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+pub fn project_config_path() -> Option<PathBuf> {
+    find_project_config(&std::env::current_dir().ok()?)
+}
+
+pub fn read_value(path: &Path) -> Result<Value, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+}
+
+/// Merges a project config over the user config.
+pub fn merge_project_values(base: &mut Value, overlay: Value) {
+    merge_values(base, overlay, "", APPENDED_ARRAYS);
+}
+
+/// Merges `overlay` into `base`: tables key by key, arrays named in
+/// `appended` by adding the overlay's items, and anything else by
+/// replacing it.
+pub fn merge_values(base: &mut Value, overlay: Value, path: &str, appended: &[&str]) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                let key_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value, &key_path, appended),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overlay)) if appended.contains(&path) => {
+            base.extend(overlay);
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Removes from `value` every setting that `overlay` provides, so settings
+/// that came from a project config are not saved into the user config.
+/// Returns the dotted names of the single values removed that differ from
+/// the overlay's, whose change would otherwise be lost without a word.
+pub fn strip_values(value: &mut Value, overlay: &Value) -> Vec<String> {
+    let mut overridden = Vec::new();
+    strip_values_at(value, overlay, "", &mut overridden);
+    overridden
+}
+
+fn strip_values_at(value: &mut Value, overlay: &Value, path: &str, overridden: &mut Vec<String>) {
+    let (Value::Table(table), Value::Table(overlay)) = (value, overlay) else {
+        return;
+    };
+    for (key, overlay_value) in overlay {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        let strip_whole = match table.get_mut(key) {
+            Some(existing @ Value::Table(_)) if overlay_value.is_table() => {
+                strip_values_at(existing, overlay_value, &key_path, overridden);
+                false
+            }
+            // Arrays such as the context menu actions hold the project's
+            // items appended to the user's, so they always differ
+            Some(existing) => {
+                if !existing.is_table() && !existing.is_array() && existing != overlay_value {
+                    overridden.push(key_path);
+                }
+                true
+            }
+            None => false,
+        };
+        if strip_whole {
+            table.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,131 @@
+// The tests module for config files covers choosing the user config,
+// finding a project config in a parent directory, and merging and stripping
+// project settings.
+
+use super::*;
+
+#[test]
+fn test_user_config_candidates() {
+    let home = Path::new("/home/user");
+    assert_eq!(
+        user_config_candidates(home, None),
+        vec![
+            PathBuf::from("/home/user/.config/dprs/config.toml"),
+            PathBuf::from("/home/user/.dprs/config"),
+        ]
+    );
+    assert_eq!(
+        user_config_candidates(home, Some("/xdg"))[0],
+        PathBuf::from("/xdg/dprs/config.toml")
+    );
+    // A relative XDG_CONFIG_HOME is ignored
+    assert_eq!(
+        user_config_candidates(home, Some("relative"))[0],
+        PathBuf::from("/home/user/.config/dprs/config.toml")
+    );
+}
+
+#[test]
+fn test_choose_user_config() {
+    let candidates = user_config_candidates(Path::new("/home/user"), None);
+    assert_eq!(choose_user_config(&candidates, |_| false), candidates[0]);
+    assert_eq!(
+        choose_user_config(&candidates, |path| path.ends_with(".dprs/config")),
+        candidates[1]
+    );
+    assert_eq!(choose_user_config(&candidates, |_| true), candidates[0]);
+}
+
+#[test]
+fn test_find_project_config() {
+    let dir = std::env::temp_dir().join(format!("dprs-files-{}", std::process::id()));
+    let nested = dir.join("a").join("b");
+    std::fs::create_dir_all(&nested).unwrap();
+    assert_eq!(find_project_config(&nested), None);
+
+    std::fs::write(dir.join(PROJECT_CONFIG_FILE), "").unwrap();
+    assert_eq!(
+        find_project_config(&nested),
+        Some(dir.join(PROJECT_CONFIG_FILE))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_merge_project_values() {
+    let mut base: Value = toml::from_str(
+        r#"
+        [general]
+        refresh_interval = 1000
+        max_history = 50
+
+        [[context_menu.actions]]
+        name = "Shell"
+        "#,
+    )
+    .unwrap();
+    let overlay: Value = toml::from_str(
+        r#"
+        [general]
+        refresh_interval = 250
+
+        [aliases]
+        r = "restart"
+
+        [[context_menu.actions]]
+        name = "Migrate"
+        "#,
+    )
+    .unwrap();
+    merge_project_values(&mut base, overlay);
+
+    assert_eq!(base["general"]["refresh_interval"].as_integer(), Some(250));
+    assert_eq!(base["general"]["max_history"].as_integer(), Some(50));
+    assert_eq!(base["aliases"]["r"].as_str(), Some("restart"));
+    let names: Vec<&str> = base["context_menu"]["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|action| action["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Shell", "Migrate"]);
+}
+
+#[test]
+fn test_strip_values() {
+    let mut value: Value = toml::from_str(
+        r#"
+        [general]
+        refresh_interval = 250
+        max_history = 50
+        tabular_mode = false
+
+        [aliases]
+        r = "restart"
+        "#,
+    )
+    .unwrap();
+    let overlay: Value = toml::from_str(
+        r#"
+        aliases = { r = "restart" }
+
+        [general]
+        refresh_interval = 250
+        tabular_mode = true
+        "#,
+    )
+    .unwrap();
+    // Only a value changed from the overlay's is reported
+    assert_eq!(
+        strip_values(&mut value, &overlay),
+        vec!["general.tabular_mode"]
+    );
+
+    assert!(value["general"].get("refresh_interval").is_none());
+    assert!(value["general"].get("tabular_mode").is_none());
+    assert_eq!(value["general"]["max_history"].as_integer(), Some(50));
+    assert!(value["aliases"].get("r").is_none());
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
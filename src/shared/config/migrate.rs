@@ -0,0 +1,291 @@
+// The migrate module upgrades config files written by older versions of
+// dprs. Every file records the schema `version` it was written for; a file
+// without one predates versioning and counts as version 1. Each step takes
+// the file one version forward, working on the parsed TOML so settings the
+// current `Config` no longer knows are not lost. At startup an outdated user
+// config is upgraded in place after a copy of it is kept next to it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+use super::theme::theme_color;
+
+/// The schema version this build writes.
+pub const CONFIG_VERSION: u32 = 5;
+
+/// Bindings added to the defaults in version 2, as (mode table, key,
+/// action). They are added to an older file when it binds neither the key
+/// nor the action, so earlier customisations are kept.
+const V2_BINDINGS: &[(&str, &str, &str)] = &[
+    ("normal_mode", "Down", "SelectNext"),
+    ("normal_mode", "Up", "SelectPrevious"),
+    ("normal_mode", "p", "ToggleComposeView"),
+    ("normal_mode", ".", "OpenContextMenu"),
+    ("normal_mode", "L", "ToggleLogPane"),
+    ("normal_mode", "Z", "MaximizeLogPane"),
+    ("normal_mode", "Ctrl+e", "ScrollLogPaneDown"),
+    ("normal_mode", "Ctrl+y", "ScrollLogPaneUp"),
+    ("normal_mode", "F", "FollowLogPane"),
+    ("normal_mode", "+", "ToggleRecent"),
+    ("normal_mode", "!", "ToggleAll"),
+    ("normal_mode", "Space", "ToggleMark"),
+    ("normal_mode", "*", "MarkSearchMatches"),
+    ("normal_mode", "~", "InvertMarks"),
+    ("visual_mode", "Down", "ExtendSelectionNext"),
+    ("visual_mode", "Up", "ExtendSelectionPrevious"),
+    ("visual_mode", "Space", "MarkSelection"),
+];
+
+/// Bindings added to the defaults in version 5, when visual mode stopped
+/// taking anything but motions from the normal mode table.
+const V5_BINDINGS: &[(&str, &str, &str)] = &[
+    ("visual_mode", "v", "EnterVisualMode"),
+    ("visual_mode", ":", "EnterCommandMode"),
+];
+
+/// The version a parsed config file was written for.
+pub fn config_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_integer)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(1)
+}
+
+/// Brings a parsed config file up to `CONFIG_VERSION`. Returns false if it
+/// was already there, or was written by a newer dprs and is left alone.
+pub fn migrate_value(value: &mut Value) -> bool {
+    let mut version = config_version(value);
+    if version >= CONFIG_VERSION {
+        return false;
+    }
+    while version < CONFIG_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(value),
+            2 => migrate_v2_to_v3(value),
+            3 => migrate_v3_to_v4(value),
+            4 => add_bindings(value, V5_BINDINGS),
+            _ => {}
+        }
+        version += 1;
+    }
+    if let Value::Table(table) = value {
+        table.insert("version".to_string(), Value::Integer(version.into()));
+    }
+    true
+}
+
+/// Version 1 files carried the whole default palette in `custom_colors`,
+/// which now hides the chosen theme, and lack the bindings added since.
+fn migrate_v1_to_v2(value: &mut Value) {
+    if let Some(Value::Table(custom)) = value
+        .get_mut("colors")
+        .and_then(|colors| colors.get_mut("custom_colors"))
+    {
+        custom.retain(|key, color| {
+            let default = theme_color("default", key);
+            !matches!((default, color.as_str()), (Some(d), Some(c)) if d.eq_ignore_ascii_case(c))
+        });
+    }
+
+    add_bindings(value, V2_BINDINGS);
+}
+
+/// Adds default bindings, as (mode table, key, action), to the tables a
+/// file has, unless it binds the key or the action already.
+fn add_bindings(value: &mut Value, added: &[(&str, &str, &str)]) {
+    let Some(Value::Table(keybindings)) = value.get_mut("keybindings") else {
+        return;
+    };
+    for (mode, key, action) in added {
+        let Some(Value::Table(bindings)) = keybindings.get_mut(*mode) else {
+            continue;
+        };
+        let action_bound = bindings
+            .values()
+            .any(|bound| bound.as_str() == Some(action));
+        if !bindings.contains_key(*key) && !action_bound {
+            bindings.insert(key.to_string(), Value::String(action.to_string()));
+        }
+    }
+}
+
+/// The default context menu actions used to name dprs commands, which
+/// never worked once the menu ran commands in a shell, and the project
+/// action built `{service}`, which a project has none of.
+fn migrate_v2_to_v3(value: &mut Value) {
+    let Some(Value::Array(actions)) = value
+        .get_mut("context_menu")
+        .and_then(|menu| menu.get_mut("actions"))
+    else {
+        return;
+    };
+    for action in actions {
+        let Some(command) = action.get_mut("command") else {
+            continue;
+        };
+        if let Some(old @ ("stop {name}" | "start {name}" | "restart {name}")) = command.as_str() {
+            *command = Value::String(format!("docker {}", old));
+        }
+        if command.as_str() == Some(OLD_BUILD_COMMAND) {
+            *command = Value::String(V3_BUILD_COMMAND.to_string());
+        }
+    }
+}
+
+/// Version 4 replaces the default "Build & Restart" action, which ran the
+/// `docker-compose` v1 binary, with the project and service actions. A
+/// "Build & Restart" the user changed is kept.
+fn migrate_v3_to_v4(value: &mut Value) {
+    if let Some(Value::Array(actions)) = value
+        .get_mut("context_menu")
+        .and_then(|menu| menu.get_mut("actions"))
+    {
+        actions.retain(|action| {
+            action.get("label").and_then(Value::as_str) != Some("Build & Restart")
+                || action.get("command").and_then(Value::as_str) != Some(V3_BUILD_COMMAND)
+        });
+    }
+    append_actions(value, V4_ACTIONS);
+}
+
+/// Project and service actions added to the default context menu in
+/// version 4, as they were then. Later changes to the defaults do not
+/// alter what a version 3 file migrates to.
+const V4_ACTIONS: &str = r#"
+[[actions]]
+label = "Up"
+command = "docker compose {compose_args} up -d"
+matchers = [{ type = "compose_project" }]
+
+[[actions]]
+label = "Down"
+command = "docker compose {compose_args} down"
+matchers = [{ type = "compose_project" }]
+
+[[actions]]
+label = "Down & Remove Volumes"
+command = "docker compose {compose_args} down -v"
+matchers = [{ type = "compose_project" }]
+confirm = true
+
+[[actions]]
+label = "Pull"
+command = "docker compose {compose_args} pull"
+matchers = [{ type = "compose_project" }]
+
+[[actions]]
+label = "Build"
+command = "docker compose {compose_args} build"
+matchers = [{ type = "compose_project" }]
+
+[[actions]]
+label = "Validate Config"
+command = "docker compose {compose_args} config --quiet && echo 'Configuration is valid'"
+matchers = [{ type = "compose_project" }]
+
+[[actions]]
+label = "Open in Editor"
+command = "cd {dir} && ${EDITOR:-vi} ."
+matchers = [{ type = "compose_project" }]
+run = "foreground"
+
+[[actions]]
+label = "Up Service"
+command = "docker compose {compose_args} up -d {service}"
+matchers = [{ type = "compose_service" }]
+
+[[actions]]
+label = "Restart Service"
+command = "docker compose {compose_args} restart {service}"
+matchers = [{ type = "compose_service" }]
+
+[[actions]]
+label = "Recreate Service"
+command = "docker compose {compose_args} up -d --force-recreate {service}"
+matchers = [{ type = "compose_service" }]
+
+[[actions]]
+label = "Build & Up Service"
+command = "docker compose {compose_args} up -d --build {service}"
+matchers = [{ type = "compose_service" }]
+
+[[actions]]
+label = "Scale Service"
+command = "docker compose {compose_args} up -d --scale {service}={replicas} {service}"
+matchers = [{ type = "compose_service" }]
+prompts = [{ name = "replicas", default = "1" }]
+
+[[actions]]
+label = "Service Logs"
+command = "docker compose {compose_args} logs --tail 500 {service}"
+matchers = [{ type = "compose_service" }]
+"#;
+
+/// Files list every context menu action, so the `[[actions]]` of `added`
+/// are appended unless an action already has their label.
+fn append_actions(value: &mut Value, added: &str) {
+    let Some(Value::Array(actions)) = value
+        .get_mut("context_menu")
+        .and_then(|menu| menu.get_mut("actions"))
+    else {
+        return;
+    };
+    let Ok(Value::Table(mut added)) = toml::from_str::<Value>(added) else {
+        return;
+    };
+    let Some(Value::Array(added)) = added.remove("actions") else {
+        return;
+    };
+    for action in added {
+        let label = action.get("label").and_then(Value::as_str);
+        let exists = actions
+            .iter()
+            .any(|existing| existing.get("label").and_then(Value::as_str) == label);
+        if !exists {
+            actions.push(action);
+        }
+    }
+}
+
+const V3_BUILD_COMMAND: &str =
+    "docker-compose -f {compose_file} build && docker-compose -f {compose_file} up -d";
+
+const OLD_BUILD_COMMAND: &str = "docker-compose -f {compose_file} build {service} && docker-compose -f {compose_file} up -d {service}";
+
+/// Where the copy of a file is kept before it is upgraded from `version`.
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    path.with_file_name(name)
+}
+
+/// Upgrades the config file at `path` in place, keeping a copy first.
+/// Returns the copy's path, or None if nothing needed upgrading. A file that
+/// cannot be read or parsed is left for the loader to report.
+pub fn upgrade_config_file(path: &Path) -> Result<Option<PathBuf>, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let Ok(mut value) = toml::from_str::<Value>(&content) else {
+        return Ok(None);
+    };
+    let version = config_version(&value);
+    if !migrate_value(&mut value) {
+        return Ok(None);
+    }
+
+    let backup = backup_path(path, version);
+    fs::write(&backup, &content)
+        .map_err(|e| format!("Could not back up {}: {}", path.display(), e))?;
+    let upgraded = toml::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    fs::write(path, upgraded)
+        .map_err(|e| format!("Could not upgrade {}: {}", path.display(), e))?;
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
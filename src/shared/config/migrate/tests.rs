@@ -0,0 +1,170 @@
+// The tests module for config migration walks files through each version.
+// A version 1 file loses the stale default colours and gains the new
+// bindings, keeping its own settings. Old context menu commands are fixed,
+// the docker-compose v1 build action is retired and the project and service
+// actions are added. Visual mode gains its mode switches. Upgrading a file
+// keeps a backup of it.
+
+use super::*;
+use crate::shared::config::ContextMenuConfig;
+
+const V1_CONFIG: &str = r##"
+[general]
+tabular_mode = true
+auto_refresh_interval = 0
+max_history_items = 100
+
+[keybindings.normal_mode]
+j = "SelectNext"
+x = "ToggleMark"
+Down = "GoToLast"
+
+[keybindings.visual_mode]
+j = "ExtendSelectionNext"
+
+[keybindings.command_mode]
+Enter = "ExecuteCommand"
+
+[colors]
+theme = "light"
+
+[colors.custom_colors]
+background_main = "#000000"
+text_main = "#ffffff"
+mode_normal = "#FF0000"
+
+[layout]
+show_headers = true
+show_borders = true
+
+[layout.column_widths]
+name = 30
+"##;
+
+#[test]
+fn test_migrate_v1() {
+    let mut value: Value = toml::from_str(V1_CONFIG).unwrap();
+    assert_eq!(config_version(&value), 1);
+    assert!(migrate_value(&mut value));
+    assert_eq!(config_version(&value), CONFIG_VERSION);
+
+    let custom = value["colors"]["custom_colors"].as_table().unwrap();
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom["mode_normal"].as_str(), Some("#FF0000"));
+
+    let normal = value["keybindings"]["normal_mode"].as_table().unwrap();
+    // Keys and actions the user already bound are left alone
+    assert_eq!(normal["Down"].as_str(), Some("GoToLast"));
+    assert_eq!(normal["x"].as_str(), Some("ToggleMark"));
+    assert!(normal.get("Space").is_none());
+    assert_eq!(normal["p"].as_str(), Some("ToggleComposeView"));
+    let visual = value["keybindings"]["visual_mode"].as_table().unwrap();
+    assert_eq!(visual["Space"].as_str(), Some("MarkSelection"));
+    assert_eq!(visual[":"].as_str(), Some("EnterCommandMode"));
+    assert_eq!(value["general"]["tabular_mode"].as_bool(), Some(true));
+
+    assert!(!migrate_value(&mut value));
+}
+
+#[test]
+fn test_migrate_context_menu_actions() {
+    let mut value: Value = toml::from_str(
+        r#"
+        version = 2
+
+        [[context_menu.actions]]
+        label = "Stop"
+        command = "stop {name}"
+
+        [[context_menu.actions]]
+        label = "Tail"
+        command = "tail -f /tmp/{name}.log"
+
+        [[context_menu.actions]]
+        label = "Build & Restart"
+        command = "docker-compose -f {compose_file} build {service} && docker-compose -f {compose_file} up -d {service}"
+        "#,
+    )
+    .unwrap();
+    assert!(migrate_value(&mut value));
+    let actions = value["context_menu"]["actions"].as_array().unwrap();
+    assert_eq!(actions[0]["command"].as_str(), Some("docker stop {name}"));
+    assert_eq!(
+        actions[1]["command"].as_str(),
+        Some("tail -f /tmp/{name}.log")
+    );
+
+    // Version 4 adds the project and service actions after the user's own
+    let labels: Vec<&str> = actions
+        .iter()
+        .map(|action| action["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(labels[..3], ["Stop", "Tail", "Up"]);
+    assert!(!labels.contains(&"Build & Restart"));
+    assert!(labels.contains(&"Open in Editor"));
+    assert_eq!(labels.last(), Some(&"Service Logs"));
+    let scale = actions
+        .iter()
+        .find(|action| action["label"].as_str() == Some("Scale Service"))
+        .unwrap();
+    assert_eq!(scale["prompts"][0]["name"].as_str(), Some("replicas"));
+    let down_v = actions
+        .iter()
+        .find(|action| action["label"].as_str() == Some("Down & Remove Volumes"))
+        .unwrap();
+    assert_eq!(down_v["confirm"].as_bool(), Some(true));
+
+    // The added actions are ones the current config reads
+    let menu: ContextMenuConfig = value["context_menu"].clone().try_into().unwrap();
+    assert_eq!(menu.actions.len(), labels.len());
+}
+
+#[test]
+fn test_migrate_v4_visual_bindings() {
+    let mut value: Value = toml::from_str(
+        r#"
+        version = 4
+
+        [keybindings.visual_mode]
+        j = "ExtendSelectionNext"
+        v = "EnterNormalMode"
+        "#,
+    )
+    .unwrap();
+    assert!(migrate_value(&mut value));
+    let visual = value["keybindings"]["visual_mode"].as_table().unwrap();
+    assert_eq!(visual[":"].as_str(), Some("EnterCommandMode"));
+    // A key the user already bound is left alone
+    assert_eq!(visual["v"].as_str(), Some("EnterNormalMode"));
+    assert!(value.get("context_menu").is_none());
+}
+
+#[test]
+fn test_newer_version_left_alone() {
+    let mut value: Value = toml::from_str("version = 99\n[colors]\ntheme = \"x\"").unwrap();
+    let before = value.clone();
+    assert!(!migrate_value(&mut value));
+    assert_eq!(value, before);
+}
+
+#[test]
+fn test_upgrade_config_file() {
+    let dir = std::env::temp_dir().join(format!("dprs-migrate-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    fs::write(&path, V1_CONFIG).unwrap();
+
+    let backup = upgrade_config_file(&path).unwrap().unwrap();
+    assert_eq!(backup, dir.join("config.toml.v1.bak"));
+    assert_eq!(fs::read_to_string(&backup).unwrap(), V1_CONFIG);
+    let upgraded: Value = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(config_version(&upgraded), CONFIG_VERSION);
+
+    // Nothing more to do the second time
+    assert_eq!(upgrade_config_file(&path).unwrap(), None);
+    assert_eq!(upgrade_config_file(&dir.join("missing")).unwrap(), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
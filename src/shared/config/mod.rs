@@ -1,7 +1,15 @@
 // Configuration management shared by both applications
 
+pub use files::*;
+pub use migrate::*;
 pub use settings::*;
+pub use theme::*;
+pub use watcher::*;
 
+mod files;
+mod migrate;
 mod settings;
+mod theme;
+mod watcher;
 
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -1,6 +1,7 @@
 // The config module implements persistent configuration management for DPRS,
 // supporting user-customizable settings including key bindings, color schemes,
-// and layout options. Configuration is stored in ~/.dprs/config and loaded
+// and layout options. Configuration is stored in the user config file, with
+// a project's `.dprs.toml` merged over it (see the files module), and loaded
 // at startup with fallback to sensible defaults.
 
 use crossterm::event::{KeyCode, KeyModifiers};
@@ -10,23 +11,60 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use super::files::{
+    merge_project_values, merge_values, project_config_path, read_value, strip_values,
+    user_config_path,
+};
+use super::migrate::{migrate_value, upgrade_config_file, CONFIG_VERSION};
+use super::theme::{parse_color, theme_color, ColorDepth, COLOR_DEPTHS, THEMES};
+
+/// Every section and setting has a default, so a file only needs the
+/// settings it changes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
+    /// The schema version the file was written for, see the migrate module.
+    #[serde(default = "unversioned")]
+    pub version: u32,
     pub general: GeneralConfig,
     pub keybindings: KeyBindings,
     pub colors: ColorConfig,
     pub layout: LayoutConfig,
-    #[serde(default)]
     pub context_menu: ContextMenuConfig,
+    /// User-defined command aliases, e.g. `reset-db = "stop label:role=$1; rm label:role=$1"`.
+    pub commands: HashMap<String, String>,
+    pub confirm: ConfirmConfig,
+    /// The `.dprs.toml` merged over the user config, if one was found.
+    #[serde(skip)]
+    pub project_file: Option<PathBuf>,
+}
+
+/// Files written before the version field existed.
+fn unversioned() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeneralConfig {
     pub tabular_mode: bool,
     pub auto_refresh_interval: u64, // seconds, 0 = disabled
     pub max_history_items: usize,
-    #[serde(default = "default_experimental_fx")]
     pub experimental_fx: bool,
+    /// Containers listed at startup: "running", "recent" or "all".
+    pub container_filter: String,
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            tabular_mode: false,
+            auto_refresh_interval: 0,
+            max_history_items: 100,
+            experimental_fx: default_experimental_fx(),
+            container_filter: "running".to_string(),
+        }
+    }
 }
 
 fn default_experimental_fx() -> bool {
@@ -34,31 +72,127 @@ fn default_experimental_fx() -> bool {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct KeyBindings {
     /// Key bindings for normal mode. These are the default key mappings.
     pub normal_mode: HashMap<String, String>,
-    /// Key bindings for visual mode. Keys can be:
+    /// Key bindings for visual mode. Motions not bound here come from
+    /// normal_mode; other normal mode keys do nothing in visual mode. Keys can be:
     /// - Mapped to different actions than normal mode
-    /// - Set to empty string ("") to unmap/disable them in visual mode
+    /// - Set to empty string ("") to unmap a normal mode motion in visual mode
     /// - Added as visual-mode-only bindings (not present in normal_mode)
     pub visual_mode: HashMap<String, String>,
     pub command_mode: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ColorConfig {
+    /// One of THEMES. Colours not set in `custom_colors` come from it.
     pub theme: String,
     pub custom_colors: HashMap<String, String>,
+    /// One of COLOR_DEPTHS: "auto" detects what the terminal supports.
+    pub color_depth: String,
+    /// The depth colours are downgraded to, resolved from `color_depth`.
+    #[serde(skip)]
+    pub depth: ColorDepth,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self {
+            theme: "default".to_string(),
+            custom_colors: HashMap::new(),
+            color_depth: "auto".to_string(),
+            depth: ColorDepth::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LayoutConfig {
     pub show_headers: bool,
     pub column_widths: HashMap<String, u16>,
     pub show_borders: bool,
+    /// Where the log pane is placed: "bottom" or "right".
+    pub log_pane_position: String,
+    /// Percentage of the main area given to the log pane.
+    pub log_pane_size: u16,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        let mut column_widths = HashMap::new();
+        column_widths.insert("name".to_string(), 25);
+        column_widths.insert("image".to_string(), 20);
+        column_widths.insert("status".to_string(), 15);
+        column_widths.insert("ip".to_string(), 15);
+        column_widths.insert("ports".to_string(), 20);
+
+        Self {
+            show_headers: true,
+            column_widths,
+            show_borders: true,
+            log_pane_position: "bottom".to_string(),
+            log_pane_size: 40,
+        }
+    }
 }
 
+/// When `:stop`, `:kill`, `:rm` and visual-mode batches ask before acting.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    /// "always", "never", "count" (more than `min_containers` targets) or
+    /// "label" (any target carries `label`).
+    pub policy: String,
+    pub min_containers: usize,
+    /// `key` or `key=value`, e.g. "env=shared".
+    pub label: String,
+}
+
+pub const CONFIRM_POLICIES: &[&str] = &["always", "never", "count", "label"];
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            policy: "always".to_string(),
+            min_containers: 1,
+            label: String::new(),
+        }
+    }
+}
+
+impl ConfirmConfig {
+    /// Decides whether acting on targets with these labels needs
+    /// confirmation. An unrecognised policy asks, to be on the safe side.
+    pub fn requires_confirmation(&self, targets: &[&HashMap<String, String>]) -> bool {
+        if targets.is_empty() {
+            return false;
+        }
+        match self.policy.as_str() {
+            "never" => false,
+            "count" => targets.len() > self.min_containers,
+            "label" => {
+                let (key, value) = match self.label.split_once('=') {
+                    Some((key, value)) => (key, Some(value)),
+                    None => (self.label.as_str(), None),
+                };
+                !key.is_empty()
+                    && targets.iter().any(|labels| match (labels.get(key), value) {
+                        (Some(actual), Some(expected)) => actual == expected,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    })
+            }
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ContextMenuConfig {
     pub actions: Vec<ContextMenuAction>,
 }
@@ -69,8 +203,38 @@ pub struct ContextMenuAction {
     pub command: String,
     #[serde(default)]
     pub matchers: Vec<ContextMenuMatcher>,
+    /// A state such as "running", "stopped" or "unhealthy", or a container
+    /// spec such as `status:exited or health:unhealthy`.
     #[serde(default)]
     pub enabled_when: Option<String>,
+    /// How the command is run: "background" or "foreground".
+    #[serde(default)]
+    pub run: ActionRunMode,
+    /// Values asked for in a form before the command runs, each available
+    /// as a `{name}` placeholder.
+    #[serde(default)]
+    pub prompts: Vec<ActionPrompt>,
+    /// Ask before running, as for commands that destroy data.
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub default: String,
+}
+
+/// Background commands stream their output into the output pager;
+/// foreground commands take over the terminal until they exit, for
+/// interactive programs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionRunMode {
+    #[default]
+    Background,
+    Foreground,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,8 +249,36 @@ pub enum ContextMenuMatcher {
         label: String,
         value: Option<String>,
     },
+    /// A project row of the compose view.
     #[serde(rename = "compose_project")]
     ComposeProject,
+    /// A service row of the compose view.
+    #[serde(rename = "compose_service")]
+    ComposeService,
+    /// A docker state such as "running", "exited" or "paused"; "stopped"
+    /// is anything but running.
+    #[serde(rename = "status")]
+    Status { status: String },
+    /// "healthy", "unhealthy", "starting" or "none".
+    #[serde(rename = "health")]
+    Health { health: String },
+    /// The container publishes or exposes this port.
+    #[serde(rename = "port")]
+    Port { port: u16 },
+    #[serde(rename = "network")]
+    NetworkPattern { pattern: String },
+    /// The compose project's name, for a project or one of its containers.
+    #[serde(rename = "project")]
+    ProjectPattern { pattern: String },
+    /// The compose project's working directory.
+    #[serde(rename = "project_dir")]
+    ProjectDirPattern { pattern: String },
+    #[serde(rename = "all")]
+    All { matchers: Vec<ContextMenuMatcher> },
+    #[serde(rename = "any")]
+    Any { matchers: Vec<ContextMenuMatcher> },
+    #[serde(rename = "not")]
+    Not { matcher: Box<ContextMenuMatcher> },
 }
 
 impl Default for ContextMenuConfig {
@@ -95,41 +287,119 @@ impl Default for ContextMenuConfig {
             // Default actions available for all containers
             ContextMenuAction {
                 label: "Stop".to_string(),
-                command: "stop {name}".to_string(),
+                command: "docker stop {name}".to_string(),
                 matchers: vec![],
                 enabled_when: Some("running".to_string()),
+                run: ActionRunMode::Background,
+                prompts: vec![],
+                confirm: false,
             },
             ContextMenuAction {
                 label: "Start".to_string(),
-                command: "start {name}".to_string(),
+                command: "docker start {name}".to_string(),
                 matchers: vec![],
                 enabled_when: Some("stopped".to_string()),
+                run: ActionRunMode::Background,
+                prompts: vec![],
+                confirm: false,
             },
             ContextMenuAction {
                 label: "Restart".to_string(),
-                command: "restart {name}".to_string(),
+                command: "docker restart {name}".to_string(),
+                matchers: vec![],
+                enabled_when: Some("running".to_string()),
+                run: ActionRunMode::Background,
+                prompts: vec![],
+                confirm: false,
+            },
+            ContextMenuAction {
+                label: "Shell".to_string(),
+                command: "docker exec -it {name} sh".to_string(),
                 matchers: vec![],
                 enabled_when: Some("running".to_string()),
+                run: ActionRunMode::Foreground,
+                prompts: vec![],
+                confirm: false,
+            },
+            // Project actions, offered on compose projects
+            project_action("Up", "up -d", ActionRunMode::Background),
+            project_action("Down", "down", ActionRunMode::Background),
+            ContextMenuAction {
+                confirm: true,
+                ..project_action(
+                    "Down & Remove Volumes",
+                    "down -v",
+                    ActionRunMode::Background,
+                )
             },
+            project_action("Pull", "pull", ActionRunMode::Background),
+            project_action("Build", "build", ActionRunMode::Background),
+            project_action(
+                "Validate Config",
+                "config --quiet && echo 'Configuration is valid'",
+                ActionRunMode::Background,
+            ),
             ContextMenuAction {
-                label: "Build & Restart".to_string(),
-                command: "docker-compose -f {compose_file} build {service} && docker-compose -f {compose_file} up -d {service}".to_string(),
+                label: "Open in Editor".to_string(),
+                command: "cd {dir} && ${EDITOR:-vi} .".to_string(),
                 matchers: vec![ContextMenuMatcher::ComposeProject],
                 enabled_when: None,
+                run: ActionRunMode::Foreground,
+                prompts: vec![],
+                confirm: false,
             },
+            // Service actions, offered on the service rows of a project
+            service_action("Up Service", "up -d {service}"),
+            service_action("Restart Service", "restart {service}"),
+            service_action("Recreate Service", "up -d --force-recreate {service}"),
+            service_action("Build & Up Service", "up -d --build {service}"),
+            ContextMenuAction {
+                prompts: vec![ActionPrompt {
+                    name: "replicas".to_string(),
+                    default: "1".to_string(),
+                }],
+                ..service_action(
+                    "Scale Service",
+                    "up -d --scale {service}={replicas} {service}",
+                )
+            },
+            service_action("Service Logs", "logs --tail 500 {service}"),
         ];
 
         Self { actions }
     }
 }
 
-impl Default for Config {
+/// An action running `docker compose <subcommand>` for a project.
+fn project_action(label: &str, subcommand: &str, run: ActionRunMode) -> ContextMenuAction {
+    ContextMenuAction {
+        label: label.to_string(),
+        command: format!("docker compose {{compose_args}} {}", subcommand),
+        matchers: vec![ContextMenuMatcher::ComposeProject],
+        enabled_when: None,
+        run,
+        prompts: vec![],
+        confirm: false,
+    }
+}
+
+/// An action running `docker compose <subcommand>` for a service row.
+fn service_action(label: &str, subcommand: &str) -> ContextMenuAction {
+    ContextMenuAction {
+        matchers: vec![ContextMenuMatcher::ComposeService],
+        ..project_action(label, subcommand, ActionRunMode::Background)
+    }
+}
+
+impl Default for KeyBindings {
     fn default() -> Self {
         let mut normal_mode = HashMap::new();
 
         // Navigation
         normal_mode.insert("j".to_string(), "SelectNext".to_string());
         normal_mode.insert("k".to_string(), "SelectPrevious".to_string());
+        normal_mode.insert("Down".to_string(), "SelectNext".to_string());
+        normal_mode.insert("Up".to_string(), "SelectPrevious".to_string());
         normal_mode.insert("gg".to_string(), "GoToFirst".to_string());
         normal_mode.insert("G".to_string(), "GoToLast".to_string());
         normal_mode.insert("w".to_string(), "WordNext".to_string());
@@ -153,10 +423,26 @@ impl Default for Config {
         normal_mode.insert("c".to_string(), "CopyIp".to_string());
         normal_mode.insert("o".to_string(), "OpenBrowser".to_string());
         normal_mode.insert("t".to_string(), "ToggleTabular".to_string());
+        normal_mode.insert("p".to_string(), "ToggleComposeView".to_string());
+        normal_mode.insert(".".to_string(), "OpenContextMenu".to_string());
+
+        // Log pane
+        normal_mode.insert("L".to_string(), "ToggleLogPane".to_string());
+        normal_mode.insert("Z".to_string(), "MaximizeLogPane".to_string());
+        normal_mode.insert("Ctrl+e".to_string(), "ScrollLogPaneDown".to_string());
+        normal_mode.insert("Ctrl+y".to_string(), "ScrollLogPaneUp".to_string());
+        normal_mode.insert("F".to_string(), "FollowLogPane".to_string());
 
         // Filter
         normal_mode.insert("f".to_string(), "EnterFilterMode".to_string());
         normal_mode.insert("Escape".to_string(), "ClearFilter".to_string());
+        normal_mode.insert("+".to_string(), "ToggleRecent".to_string());
+        normal_mode.insert("!".to_string(), "ToggleAll".to_string());
+
+        // Marks
+        normal_mode.insert("Space".to_string(), "ToggleMark".to_string());
+        normal_mode.insert("*".to_string(), "MarkSearchMatches".to_string());
+        normal_mode.insert("~".to_string(), "InvertMarks".to_string());
 
         // Quit
         normal_mode.insert("q".to_string(), "Quit".to_string());
@@ -165,161 +451,170 @@ impl Default for Config {
         // Navigation in visual mode (extends selection)
         visual_mode.insert("j".to_string(), "ExtendSelectionNext".to_string());
         visual_mode.insert("k".to_string(), "ExtendSelectionPrevious".to_string());
+        visual_mode.insert("Down".to_string(), "ExtendSelectionNext".to_string());
+        visual_mode.insert("Up".to_string(), "ExtendSelectionPrevious".to_string());
 
         // Container actions for selected containers
         visual_mode.insert("s".to_string(), "StopSelectedContainers".to_string());
         visual_mode.insert("r".to_string(), "RestartSelectedContainers".to_string());
+        visual_mode.insert("Space".to_string(), "MarkSelection".to_string());
 
         // Mode switching
         visual_mode.insert("Escape".to_string(), "EnterNormalMode".to_string());
+        visual_mode.insert("v".to_string(), "EnterVisualMode".to_string());
+        visual_mode.insert(":".to_string(), "EnterCommandMode".to_string());
 
         // Example of visual-mode-only binding (uncomment to enable):
         // visual_mode.insert("d".to_string(), "SomeVisualOnlyAction".to_string());
 
-        // Example of unmapping a key in visual mode (set to empty string):
-        // visual_mode.insert("q".to_string(), "".to_string()); // Unmap quit in visual mode
+        // Example of unmapping a motion in visual mode (set to empty string):
+        // visual_mode.insert("G".to_string(), "".to_string()); // Unmap GoToLast in visual mode
 
         let mut command_mode = HashMap::new();
         command_mode.insert("Enter".to_string(), "ExecuteCommand".to_string());
         command_mode.insert("Escape".to_string(), "CancelCommand".to_string());
         command_mode.insert("Tab".to_string(), "TabComplete".to_string());
 
-        let mut custom_colors = HashMap::new();
-        // Selection and visual mode colors
-        custom_colors.insert("selected_bg".to_string(), "#1a1a80".to_string());
-        custom_colors.insert("visual_bg".to_string(), "#5D2F00".to_string());
-        custom_colors.insert("search_highlight".to_string(), "#4D2D5F".to_string());
-
-        // Container/process list colors
-        custom_colors.insert("container_name".to_string(), "#00AA00".to_string());
-        custom_colors.insert("container_image".to_string(), "#AAAA00".to_string());
-        custom_colors.insert("container_status".to_string(), "#00AAAA".to_string());
-        custom_colors.insert("container_ip".to_string(), "#7777FF".to_string());
-        custom_colors.insert("container_ports".to_string(), "#DD55FF".to_string());
-
-        // Tabular view specific colors
-        custom_colors.insert("container_image_tabular".to_string(), "#00AAAA".to_string());
-        custom_colors.insert(
-            "container_status_tabular".to_string(),
-            "#0000AA".to_string(),
-        );
-        custom_colors.insert("container_ip_tabular".to_string(), "#7777FF".to_string());
-        custom_colors.insert("container_ports_tabular".to_string(), "#DD55FF".to_string());
-
-        // Mode indicator colors
-        custom_colors.insert("mode_normal".to_string(), "#00AA00".to_string());
-        custom_colors.insert("mode_visual".to_string(), "#AAAA00".to_string());
-        custom_colors.insert("mode_command".to_string(), "#0000AA".to_string());
-        custom_colors.insert("mode_search".to_string(), "#AA00AA".to_string());
-
-        // Hotkey colors
-        custom_colors.insert("hotkey_red".to_string(), "#AA0000".to_string());
-        custom_colors.insert("hotkey_yellow".to_string(), "#AAAA00".to_string());
-        custom_colors.insert("hotkey_green".to_string(), "#00AA00".to_string());
-        custom_colors.insert("hotkey_blue".to_string(), "#0000AA".to_string());
-        custom_colors.insert("hotkey_magenta".to_string(), "#AA00AA".to_string());
-        custom_colors.insert("hotkey_cyan".to_string(), "#00AAAA".to_string());
-        custom_colors.insert("hotkey_white".to_string(), "#CCCCCC".to_string());
-        custom_colors.insert("hotkey_gray".to_string(), "#666666".to_string());
-        custom_colors.insert("hotkey_light_blue".to_string(), "#6699CC".to_string());
-
-        // Background colors
-        custom_colors.insert("background_main".to_string(), "#000000".to_string());
-        custom_colors.insert("background_dark".to_string(), "#0F0F0F".to_string());
-        custom_colors.insert("background_table".to_string(), "#0F0F0F".to_string());
-        custom_colors.insert("background_selection".to_string(), "#1F1F1F".to_string());
-        custom_colors.insert("background_alt".to_string(), "#0F0F0F".to_string());
-        custom_colors.insert(
-            "background_selection_orange".to_string(),
-            "#2F1F0F".to_string(),
-        );
-        custom_colors.insert("background_very_dark".to_string(), "#0A0A0A".to_string());
-        custom_colors.insert("background_alt_dark".to_string(), "#0A0A0A".to_string());
-
-        // Border colors
-        custom_colors.insert("border_main".to_string(), "#00AAAA".to_string());
-        custom_colors.insert("border_light".to_string(), "#4A9EFF".to_string());
-
-        // Text colors
-        custom_colors.insert("text_selection".to_string(), "#8080FF".to_string());
-        custom_colors.insert("text_main".to_string(), "#FFFFFF".to_string());
-
-        // Message colors
-        custom_colors.insert("message_error".to_string(), "#AA0000".to_string());
-        custom_colors.insert("message_warning".to_string(), "#AAAA00".to_string());
-        custom_colors.insert("message_success".to_string(), "#00AA00".to_string());
-
-        // Filter colors
-        custom_colors.insert("filter_text".to_string(), "#FFFF00".to_string());
-        custom_colors.insert("filter_cursor".to_string(), "#0000FF".to_string());
-
-        let mut column_widths = HashMap::new();
-        column_widths.insert("name".to_string(), 25);
-        column_widths.insert("image".to_string(), 20);
-        column_widths.insert("status".to_string(), 15);
-        column_widths.insert("ip".to_string(), 15);
-        column_widths.insert("ports".to_string(), 20);
+        Self {
+            normal_mode,
+            visual_mode,
+            command_mode,
+        }
+    }
+}
 
+impl Default for Config {
+    fn default() -> Self {
         Self {
-            general: GeneralConfig {
-                tabular_mode: false,
-                auto_refresh_interval: 0,
-                max_history_items: 100,
-                experimental_fx: default_experimental_fx(),
-            },
-            keybindings: KeyBindings {
-                normal_mode,
-                visual_mode,
-                command_mode,
-            },
-            colors: ColorConfig {
-                theme: "default".to_string(),
-                custom_colors,
-            },
-            layout: LayoutConfig {
-                show_headers: true,
-                column_widths,
-                show_borders: true,
-            },
+            version: CONFIG_VERSION,
+            general: GeneralConfig::default(),
+            keybindings: KeyBindings::default(),
+            colors: ColorConfig::default(),
+            layout: LayoutConfig::default(),
             context_menu: ContextMenuConfig::default(),
+            commands: HashMap::new(),
+            confirm: ConfirmConfig::default(),
+            project_file: None,
         }
     }
 }
 
 impl Config {
+    /// Loads the config file, creating it with the defaults if it is
+    /// missing. Errors are printed and give the default configuration.
     pub fn load() -> Self {
-        let config_path = Self::config_file_path();
+        let (config, error) = Self::load_reporting();
+        if let Some(e) = error {
+            eprintln!("{}", e);
+            eprintln!("Using default configuration");
+        }
+        config
+    }
+
+    /// Like `load`, but hands back the error of a file that cannot be read
+    /// or parsed alongside the defaults used in its place.
+    pub fn load_reporting() -> (Self, Option<String>) {
+        if !Self::config_file_path().exists() {
+            // Config file doesn't exist, create default one
+            let mut config = Self::default();
+            if let Err(e) = config.save() {
+                eprintln!("Warning: Could not create default config file: {}", e);
+            }
+            config.detect_color_depth();
+            return (config, None);
+        }
 
-        match fs::read_to_string(&config_path) {
-            Ok(content) => {
-                match toml::from_str::<Config>(&content) {
-                    Ok(mut config) => {
-                        // Merge default colors with loaded colors
-                        let default_config = Self::default();
-                        for (key, value) in default_config.colors.custom_colors {
-                            config.colors.custom_colors.entry(key).or_insert(value);
-                        }
-                        config
-                    }
-                    Err(e) => {
-                        eprintln!("Error parsing config file {:?}: {}", config_path, e);
-                        eprintln!("Using default configuration");
-                        Self::default()
-                    }
-                }
+        match Self::read() {
+            Ok(config) => (config, None),
+            Err(e) => {
+                let mut config = Self::default();
+                config.detect_color_depth();
+                (config, Some(e))
             }
-            Err(_) => {
-                // Config file doesn't exist, create default one
-                let config = Self::default();
-                if let Err(e) = config.save() {
-                    eprintln!("Warning: Could not create default config file: {}", e);
-                }
-                config
+        }
+    }
+
+    /// Upgrades an outdated user config file in place, returning where
+    /// the original was kept.
+    pub fn upgrade_file() -> Result<Option<PathBuf>, String> {
+        upgrade_config_file(&Self::config_file_path())
+    }
+
+    /// Reads the config files without falling back or writing anything. A
+    /// missing user config reads as the defaults.
+    pub fn read() -> Result<Self, String> {
+        let config_path = Self::config_file_path();
+        let user = match fs::read_to_string(&config_path) {
+            Ok(content) => Self::parse(&content)
+                .map_err(|e| format!("Could not parse {}: {}", config_path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(format!("Could not read {}: {}", config_path.display(), e)),
+        };
+
+        let Some(project_path) = project_config_path() else {
+            return Ok(user);
+        };
+        let mut value = toml::Value::try_from(&user).map_err(|e| e.to_string())?;
+        merge_project_values(&mut value, read_value(&project_path)?);
+        let mut config = Config::deserialize(value)
+            .map_err(|e| format!("Could not apply {}: {}", project_path.display(), e))?;
+        config.project_file = Some(project_path);
+        config.detect_color_depth();
+        Ok(config)
+    }
+
+    /// Parses config file contents, migrating them from an older version
+    /// first. Colours left out of `custom_colors` come from the theme.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut value = toml::from_str::<toml::Value>(content).map_err(|e| e.to_string())?;
+        migrate_value(&mut value);
+        let mut config = Config::deserialize(value).map_err(|e| e.to_string())?;
+        config.detect_color_depth();
+        Ok(config)
+    }
+
+    /// Resolves `colors.color_depth` against the terminal.
+    pub fn detect_color_depth(&mut self) {
+        self.colors.depth = ColorDepth::from_setting(&self.colors.color_depth);
+    }
+
+    /// Lists colour settings that cannot be used: an unknown theme or depth,
+    /// and custom colours that do not parse.
+    pub fn color_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !THEMES.contains(&self.colors.theme.as_str()) {
+            problems.push(format!(
+                "colors: unknown theme '{}' (expected one of {})",
+                self.colors.theme,
+                THEMES.join(", ")
+            ));
+        }
+        if !COLOR_DEPTHS.contains(&self.colors.color_depth.as_str()) {
+            problems.push(format!(
+                "colors: unknown color_depth '{}' (expected one of {})",
+                self.colors.color_depth,
+                COLOR_DEPTHS.join(", ")
+            ));
+        }
+        let mut custom: Vec<_> = self.colors.custom_colors.iter().collect();
+        custom.sort();
+        for (key, value) in custom {
+            if parse_color(value).is_none() {
+                problems.push(format!("colors: '{}' for {} is not a colour", value, key));
             }
         }
+        problems
+    }
+
+    /// True if both configs hold the same settings, as they would be saved.
+    pub fn same_settings(&self, other: &Config) -> bool {
+        toml::to_string(self).ok() == toml::to_string(other).ok()
     }
 
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+    /// Writes the settings to the user config file. Returns the settings
+    /// a project `.dprs.toml` sets to another value, whose new value is kept
+    /// out of the user config and does not take effect on the next start.
+    pub fn save(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let config_path = Self::config_file_path();
 
         // Ensure config directory exists
@@ -327,17 +622,33 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
+        let mut value = toml::Value::try_from(self)?;
+        let mut overridden = Vec::new();
+        // Settings from a project's `.dprs.toml` stay out of the user config
+        if let Some(project_path) = &self.project_file {
+            overridden = strip_values(&mut value, &read_value(project_path)?);
+            let mut user = if config_path.exists() {
+                read_value(&config_path)?
+            } else {
+                toml::Value::try_from(Self::default())?
+            };
+            merge_values(&mut user, value, "", &[]);
+            value = user;
+        }
+
+        let content = toml::to_string_pretty(&value)?;
         fs::write(&config_path, content)?;
 
-        Ok(())
+        Ok(overridden)
     }
 
-    fn config_file_path() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".dprs")
-            .join("config")
+    /// Command and search history live next to the config file.
+    pub fn history_file_path() -> PathBuf {
+        Self::config_file_path().with_file_name("history")
+    }
+
+    pub fn config_file_path() -> PathBuf {
+        user_config_path()
     }
 
     pub fn get_key_binding(&self, mode: &str, key: &str) -> Option<&str> {
@@ -362,12 +673,18 @@ impl Config {
         std::time::Duration::from_secs(self.general.auto_refresh_interval)
     }
 
+    /// The colour for a key: the custom colour if set, else the theme's,
+    /// downgraded to the terminal's colour depth.
     pub fn get_color(&self, key: &str) -> Color {
-        if let Some(hex) = self.colors.custom_colors.get(key) {
-            Self::hex_to_color(hex).unwrap_or(Color::White)
-        } else {
-            Color::White
-        }
+        let color = self
+            .colors
+            .custom_colors
+            .get(key)
+            .map(String::as_str)
+            .or_else(|| theme_color(&self.colors.theme, key))
+            .and_then(|value| Self::hex_to_color(value).or_else(|| parse_color(value)))
+            .unwrap_or(Color::White);
+        self.colors.depth.downgrade(color)
     }
 
     fn hex_to_color(hex: &str) -> Option<Color> {
@@ -391,11 +708,13 @@ pub fn key_event_to_string(key: crossterm::event::KeyEvent) -> String {
     if key.modifiers.contains(KeyModifiers::ALT) {
         result.push_str("Alt+");
     }
-    if key.modifiers.contains(KeyModifiers::SHIFT) {
+    // Shifted characters already arrive in their shifted form, as `G`
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
         result.push_str("Shift+");
     }
 
     match key.code {
+        KeyCode::Char(' ') => result.push_str("Space"),
         KeyCode::Char(c) => result.push(c),
         KeyCode::Enter => result.push_str("Enter"),
         KeyCode::Esc => result.push_str("Escape"),
@@ -418,6 +737,102 @@ pub fn key_event_to_string(key: crossterm::event::KeyEvent) -> String {
     result
 }
 
+/// Names of the non-character keys, as `key_event_to_string` writes them.
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Escape",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+    "Space",
+];
+
+/// Splits a key sequence from the config, such as `gg` or `Ctrl+wj`, into
+/// keys named the way `key_event_to_string` names them. Modifiers may be in
+/// any case and order, `Esc` and `Return` are accepted, and `Shift+g` is `G`.
+pub fn split_key_sequence(sequence: &str) -> Result<Vec<String>, String> {
+    if sequence.is_empty() {
+        return Err("empty key".to_string());
+    }
+
+    let mut keys = Vec::new();
+    let mut rest = sequence;
+    while !rest.is_empty() {
+        let (key, after) = parse_key(rest)?;
+        keys.push(key);
+        rest = after;
+    }
+    Ok(keys)
+}
+
+fn parse_key(text: &str) -> Result<(String, &str), String> {
+    let (mut ctrl, mut alt, mut shift) = (false, false, false);
+    let mut rest = text;
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let (flag, length) = if lower.starts_with("ctrl+") {
+            (&mut ctrl, 5)
+        } else if lower.starts_with("alt+") {
+            (&mut alt, 4)
+        } else if lower.starts_with("shift+") {
+            (&mut shift, 6)
+        } else {
+            break;
+        };
+        *flag = true;
+        rest = &rest[length..];
+    }
+
+    let aliases = [("Esc", "Escape"), ("Return", "Enter"), ("Del", "Delete")];
+    let named = NAMED_KEYS
+        .iter()
+        .map(|name| (*name, *name))
+        .chain(aliases)
+        .filter(|(spelling, _)| rest.starts_with(spelling))
+        .max_by_key(|(spelling, _)| spelling.len());
+    let function_key = rest
+        .strip_prefix('F')
+        .map(|after| after.chars().take_while(|c| c.is_ascii_digit()).count())
+        .filter(|&digits| digits > 0);
+
+    let (mut name, after) = if let Some((spelling, name)) = named {
+        (name.to_string(), &rest[spelling.len()..])
+    } else if let Some(digits) = function_key {
+        (rest[..digits + 1].to_string(), &rest[digits + 1..])
+    } else if let Some(c) = rest.chars().next() {
+        let name = match c {
+            ' ' => "Space".to_string(),
+            c if shift => c.to_uppercase().to_string(),
+            c => c.to_string(),
+        };
+        (name, &rest[c.len_utf8()..])
+    } else {
+        return Err(format!("missing key after modifier in '{}'", text));
+    };
+
+    // Shift is part of a character, as `key_event_to_string` leaves it
+    let is_char = name.chars().count() == 1;
+    if shift && !is_char {
+        name = format!("Shift+{}", name);
+    }
+    if alt {
+        name = format!("Alt+{}", name);
+    }
+    if ctrl {
+        name = format!("Ctrl+{}", name);
+    }
+    Ok((name, after))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,6 +858,111 @@ mod tests {
         // Should return white for unknown color
         assert_eq!(config.get_color("unknown_color"), Color::White);
     }
+
+    #[test]
+    fn test_theme_and_custom_colors() {
+        let mut config = Config::default();
+        config.colors.theme = "light".to_string();
+        assert_eq!(
+            config.get_color("background_main"),
+            Color::Rgb(255, 255, 255)
+        );
+
+        config
+            .colors
+            .custom_colors
+            .insert("background_main".to_string(), "blue".to_string());
+        config
+            .colors
+            .custom_colors
+            .insert("mark".to_string(), "202".to_string());
+        assert_eq!(config.get_color("background_main"), Color::Blue);
+        assert_eq!(config.get_color("mark"), Color::Indexed(202));
+        assert!(config.color_problems().is_empty());
+
+        config.colors.depth = ColorDepth::NoColor;
+        assert_eq!(config.get_color("mark"), Color::Reset);
+
+        config.colors.theme = "neon".to_string();
+        config
+            .colors
+            .custom_colors
+            .insert("text_main".to_string(), "#12345".to_string());
+        assert_eq!(
+            config.color_problems(),
+            vec![
+                "colors: unknown theme 'neon' (expected one of default, light, solarized, high-contrast)",
+                "colors: '#12345' for text_main is not a colour",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_partial_config() {
+        let config = Config::parse(
+            "version = 2\n[general]\ntabular_mode = true\n[layout]\nlog_pane_size = 60",
+        )
+        .unwrap();
+        assert!(config.general.tabular_mode);
+        assert_eq!(config.general.max_history_items, 100);
+        assert_eq!(config.layout.log_pane_size, 60);
+        assert!(config.layout.show_borders);
+        assert_eq!(config.get_key_binding("normal", "j"), Some("SelectNext"));
+        assert_eq!(config.context_menu.actions.len(), 17);
+        // Only the action that deletes volumes asks first
+        let confirmed: Vec<_> = config
+            .context_menu
+            .actions
+            .iter()
+            .filter(|action| action.confirm)
+            .map(|action| action.label.as_str())
+            .collect();
+        assert_eq!(confirmed, vec!["Down & Remove Volumes"]);
+
+        // An unversioned file is migrated as it is read
+        assert_eq!(Config::parse("").unwrap().version, CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_split_key_sequence() {
+        let split = |sequence: &str| split_key_sequence(sequence).unwrap();
+        assert_eq!(split("gg"), vec!["g", "g"]);
+        assert_eq!(split("Ctrl+u"), vec!["Ctrl+u"]);
+        assert_eq!(split("ctrl+U"), vec!["Ctrl+U"]);
+        assert_eq!(split("Shift+ctrl+g"), vec!["Ctrl+G"]);
+        assert_eq!(split("Esc"), vec!["Escape"]);
+        assert_eq!(split("Shift+Tab"), vec!["Shift+Tab"]);
+        assert_eq!(split("F5"), vec!["F5"]);
+        assert_eq!(split("F"), vec!["F"]);
+        assert_eq!(split(" "), vec!["Space"]);
+        assert_eq!(split("Ctrl+wj"), vec!["Ctrl+w", "j"]);
+        assert!(split_key_sequence("Ctrl+").is_err());
+        assert!(split_key_sequence("").is_err());
+    }
+
+    #[test]
+    fn test_confirm_policies() {
+        let shared = HashMap::from([("env".to_string(), "shared".to_string())]);
+        let local = HashMap::new();
+        let mut confirm = ConfirmConfig::default();
+        assert!(confirm.requires_confirmation(&[&local]));
+        assert!(!confirm.requires_confirmation(&[]));
+
+        confirm.policy = "never".to_string();
+        assert!(!confirm.requires_confirmation(&[&shared]));
+
+        confirm.policy = "count".to_string();
+        confirm.min_containers = 1;
+        assert!(!confirm.requires_confirmation(&[&local]));
+        assert!(confirm.requires_confirmation(&[&local, &local]));
+
+        confirm.policy = "label".to_string();
+        confirm.label = "env=shared".to_string();
+        assert!(confirm.requires_confirmation(&[&local, &shared]));
+        assert!(!confirm.requires_confirmation(&[&local]));
+        confirm.label = "env".to_string();
+        assert!(confirm.requires_confirmation(&[&shared]));
+    }
 }
 
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
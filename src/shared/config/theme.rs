@@ -0,0 +1,368 @@
+// The theme module holds the built-in colour themes and turns colour values
+// from the config into terminal colours. A value is `#RRGGBB`, a colour name
+// such as `lightblue`, or a 256-colour palette index. Colours are downgraded
+// to what the terminal can show: the 256-colour palette, the 16 ANSI colours,
+// or no colour at all when NO_COLOR is set.
+
+use ratatui::style::Color;
+use std::str::FromStr;
+
+pub const THEMES: &[&str] = &["default", "light", "solarized", "high-contrast"];
+
+/// Values of `colors.color_depth`. "auto" detects the terminal's depth.
+pub const COLOR_DEPTHS: &[&str] = &["auto", "truecolor", "256", "16", "none"];
+
+const DEFAULT: &[(&str, &str)] = &[
+    // Selection and visual mode colors
+    ("selected_bg", "#1a1a80"),
+    ("visual_bg", "#5D2F00"),
+    ("search_highlight", "#4D2D5F"),
+    ("mark", "#FF8800"),
+    // Container/process list colors
+    ("container_name", "#00AA00"),
+    ("container_image", "#AAAA00"),
+    ("container_status", "#00AAAA"),
+    ("container_ip", "#7777FF"),
+    ("container_ports", "#DD55FF"),
+    // Tabular view specific colors
+    ("container_image_tabular", "#00AAAA"),
+    ("container_status_tabular", "#0000AA"),
+    ("container_ip_tabular", "#7777FF"),
+    ("container_ports_tabular", "#DD55FF"),
+    // Mode indicator colors
+    ("mode_normal", "#00AA00"),
+    ("mode_visual", "#AAAA00"),
+    ("mode_command", "#0000AA"),
+    ("mode_search", "#AA00AA"),
+    // Hotkey colors
+    ("hotkey_red", "#AA0000"),
+    ("hotkey_yellow", "#AAAA00"),
+    ("hotkey_green", "#00AA00"),
+    ("hotkey_blue", "#0000AA"),
+    ("hotkey_magenta", "#AA00AA"),
+    ("hotkey_cyan", "#00AAAA"),
+    ("hotkey_white", "#CCCCCC"),
+    ("hotkey_gray", "#666666"),
+    ("hotkey_light_blue", "#6699CC"),
+    // Background colors
+    ("background_main", "#000000"),
+    ("background_dark", "#0F0F0F"),
+    ("background_table", "#0F0F0F"),
+    ("background_selection", "#1F1F1F"),
+    ("background_alt", "#0F0F0F"),
+    ("background_selection_orange", "#2F1F0F"),
+    ("background_very_dark", "#0A0A0A"),
+    ("background_alt_dark", "#0A0A0A"),
+    // Border colors
+    ("border_main", "#00AAAA"),
+    ("border_light", "#4A9EFF"),
+    // Text colors
+    ("text_selection", "#8080FF"),
+    ("text_main", "#FFFFFF"),
+    ("text_dim", "#888888"),
+    ("text_highlight", "#FFFF55"),
+    // Message colors
+    ("message_error", "#AA0000"),
+    ("message_warning", "#AAAA00"),
+    ("message_success", "#00AA00"),
+    // Filter colors
+    ("filter_text", "#FFFF00"),
+    ("filter_cursor", "#0000FF"),
+];
+
+const LIGHT: &[(&str, &str)] = &[
+    ("selected_bg", "#C8D8F0"),
+    ("visual_bg", "#F5D9B8"),
+    ("search_highlight", "#E8D0F0"),
+    ("mark", "#C05000"),
+    ("container_name", "#006400"),
+    ("container_image", "#806000"),
+    ("container_status", "#006B6B"),
+    ("container_ip", "#3030B0"),
+    ("container_ports", "#8B008B"),
+    ("container_image_tabular", "#006B6B"),
+    ("container_status_tabular", "#00008B"),
+    ("container_ip_tabular", "#3030B0"),
+    ("container_ports_tabular", "#8B008B"),
+    ("mode_normal", "#006400"),
+    ("mode_visual", "#806000"),
+    ("mode_command", "#00008B"),
+    ("mode_search", "#8B008B"),
+    ("hotkey_red", "#B00000"),
+    ("hotkey_yellow", "#806000"),
+    ("hotkey_green", "#006400"),
+    ("hotkey_blue", "#00008B"),
+    ("hotkey_magenta", "#8B008B"),
+    ("hotkey_cyan", "#006B6B"),
+    ("hotkey_white", "#333333"),
+    ("hotkey_gray", "#888888"),
+    ("hotkey_light_blue", "#2060A0"),
+    ("background_main", "#FFFFFF"),
+    ("background_dark", "#F0F0F0"),
+    ("background_table", "#F0F0F0"),
+    ("background_selection", "#E0E0E0"),
+    ("background_alt", "#F0F0F0"),
+    ("background_selection_orange", "#FBE5CC"),
+    ("background_very_dark", "#F7F7F7"),
+    ("background_alt_dark", "#F7F7F7"),
+    ("border_main", "#006B6B"),
+    ("border_light", "#2060A0"),
+    ("text_selection", "#3030B0"),
+    ("text_main", "#000000"),
+    ("text_dim", "#777777"),
+    ("text_highlight", "#806000"),
+    ("message_error", "#B00000"),
+    ("message_warning", "#806000"),
+    ("message_success", "#006400"),
+    ("filter_text", "#806000"),
+    ("filter_cursor", "#00008B"),
+];
+
+const SOLARIZED: &[(&str, &str)] = &[
+    ("selected_bg", "#073642"),
+    ("visual_bg", "#3D2B0B"),
+    ("search_highlight", "#2F2A4A"),
+    ("mark", "#CB4B16"),
+    ("container_name", "#859900"),
+    ("container_image", "#B58900"),
+    ("container_status", "#2AA198"),
+    ("container_ip", "#268BD2"),
+    ("container_ports", "#D33682"),
+    ("container_image_tabular", "#2AA198"),
+    ("container_status_tabular", "#268BD2"),
+    ("container_ip_tabular", "#6C71C4"),
+    ("container_ports_tabular", "#D33682"),
+    ("mode_normal", "#859900"),
+    ("mode_visual", "#B58900"),
+    ("mode_command", "#268BD2"),
+    ("mode_search", "#D33682"),
+    ("hotkey_red", "#DC322F"),
+    ("hotkey_yellow", "#B58900"),
+    ("hotkey_green", "#859900"),
+    ("hotkey_blue", "#268BD2"),
+    ("hotkey_magenta", "#D33682"),
+    ("hotkey_cyan", "#2AA198"),
+    ("hotkey_white", "#93A1A1"),
+    ("hotkey_gray", "#586E75"),
+    ("hotkey_light_blue", "#6C71C4"),
+    ("background_main", "#002B36"),
+    ("background_dark", "#073642"),
+    ("background_table", "#073642"),
+    ("background_selection", "#0A4250"),
+    ("background_alt", "#073642"),
+    ("background_selection_orange", "#3D2B0B"),
+    ("background_very_dark", "#00212B"),
+    ("background_alt_dark", "#00212B"),
+    ("border_main", "#2AA198"),
+    ("border_light", "#268BD2"),
+    ("text_selection", "#6C71C4"),
+    ("text_main", "#93A1A1"),
+    ("text_dim", "#586E75"),
+    ("text_highlight", "#B58900"),
+    ("message_error", "#DC322F"),
+    ("message_warning", "#B58900"),
+    ("message_success", "#859900"),
+    ("filter_text", "#B58900"),
+    ("filter_cursor", "#268BD2"),
+];
+
+const HIGH_CONTRAST: &[(&str, &str)] = &[
+    ("selected_bg", "#0000FF"),
+    ("visual_bg", "#AA5500"),
+    ("search_highlight", "#AA00AA"),
+    ("mark", "#FFFF00"),
+    ("container_name", "#00FF00"),
+    ("container_image", "#FFFF00"),
+    ("container_status", "#00FFFF"),
+    ("container_ip", "#FFFFFF"),
+    ("container_ports", "#FF00FF"),
+    ("container_image_tabular", "#00FFFF"),
+    ("container_status_tabular", "#FFFFFF"),
+    ("container_ip_tabular", "#FFFFFF"),
+    ("container_ports_tabular", "#FF00FF"),
+    ("mode_normal", "#00FF00"),
+    ("mode_visual", "#FFFF00"),
+    ("mode_command", "#00FFFF"),
+    ("mode_search", "#FF00FF"),
+    ("hotkey_red", "#FF0000"),
+    ("hotkey_yellow", "#FFFF00"),
+    ("hotkey_green", "#00FF00"),
+    ("hotkey_blue", "#5555FF"),
+    ("hotkey_magenta", "#FF00FF"),
+    ("hotkey_cyan", "#00FFFF"),
+    ("hotkey_white", "#FFFFFF"),
+    ("hotkey_gray", "#BBBBBB"),
+    ("hotkey_light_blue", "#00FFFF"),
+    ("background_main", "#000000"),
+    ("background_dark", "#000000"),
+    ("background_table", "#000000"),
+    ("background_selection", "#444444"),
+    ("background_alt", "#000000"),
+    ("background_selection_orange", "#553300"),
+    ("background_very_dark", "#000000"),
+    ("background_alt_dark", "#000000"),
+    ("border_main", "#FFFFFF"),
+    ("border_light", "#FFFF00"),
+    ("text_selection", "#FFFF00"),
+    ("text_main", "#FFFFFF"),
+    ("text_dim", "#BBBBBB"),
+    ("text_highlight", "#FFFF00"),
+    ("message_error", "#FF0000"),
+    ("message_warning", "#FFFF00"),
+    ("message_success", "#00FF00"),
+    ("filter_text", "#FFFF00"),
+    ("filter_cursor", "#00FFFF"),
+];
+
+/// The value a built-in theme gives a colour key. Unknown themes and keys a
+/// theme leaves out fall back to the default theme.
+pub fn theme_color(theme: &str, key: &str) -> Option<&'static str> {
+    let colors = match theme {
+        "light" => LIGHT,
+        "solarized" => SOLARIZED,
+        "high-contrast" => HIGH_CONTRAST,
+        _ => DEFAULT,
+    };
+    colors
+        .iter()
+        .chain(DEFAULT)
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| *value)
+}
+
+/// Parses a colour value: `#RRGGBB`, a name such as `red` or `lightblue`,
+/// or a palette index from 0 to 255.
+pub fn parse_color(value: &str) -> Option<Color> {
+    Color::from_str(value.trim()).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    NoColor,
+}
+
+impl ColorDepth {
+    /// The depth for a `colors.color_depth` setting, detecting it from the
+    /// environment for "auto" or anything unrecognised.
+    pub fn from_setting(setting: &str) -> Self {
+        match setting {
+            "truecolor" => ColorDepth::TrueColor,
+            "256" => ColorDepth::Ansi256,
+            "16" => ColorDepth::Ansi16,
+            "none" => ColorDepth::NoColor,
+            _ => Self::detect(|name| std::env::var(name).ok()),
+        }
+    }
+
+    /// Works out the terminal's depth from NO_COLOR, COLORTERM and TERM.
+    pub fn detect(env: impl Fn(&str) -> Option<String>) -> Self {
+        // https://no-color.org: set and not empty means no colour
+        if env("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::NoColor;
+        }
+        if env("COLORTERM").is_some_and(|value| value == "truecolor" || value == "24bit") {
+            return ColorDepth::TrueColor;
+        }
+        match env("TERM") {
+            Some(term) if term == "dumb" => ColorDepth::NoColor,
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    /// Maps a colour to the nearest one the depth can show.
+    pub fn downgrade(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => Color::Reset,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_indexed(r, g, b)),
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi(r, g, b),
+            (ColorDepth::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                let (r, g, b) = indexed_to_rgb(index);
+                nearest_ansi(r, g, b)
+            }
+            (ColorDepth::Ansi16, Color::Indexed(index)) => ANSI[index as usize].0,
+            _ => color,
+        }
+    }
+}
+
+/// The 16 ANSI colours with their usual xterm values.
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 colour cube at indices 16 to 231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_ansi(r: u8, g: u8, b: u8) -> Color {
+    ANSI.iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::White)
+}
+
+fn rgb_to_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    // The grayscale ramp at 232 to 255 runs from 8 to 238 in steps of 10
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+    if distance(indexed_to_rgb(gray), (r, g, b)) < distance(indexed_to_rgb(cube), (r, g, b)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI[index as usize].1,
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let value = 8 + 10 * (index - 232);
+            (value, value, value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
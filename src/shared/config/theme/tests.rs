@@ -0,0 +1,77 @@
+// The tests module for themes covers colour parsing, theme lookup with its
+// fallback to the default theme, depth detection and colour downgrading.
+
+use super::*;
+use std::collections::HashMap;
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(parse_color("#FF8800"), Some(Color::Rgb(255, 136, 0)));
+    assert_eq!(parse_color("lightblue"), Some(Color::LightBlue));
+    assert_eq!(parse_color("red"), Some(Color::Red));
+    assert_eq!(parse_color("208"), Some(Color::Indexed(208)));
+    assert_eq!(parse_color("not-a-colour"), None);
+}
+
+#[test]
+fn test_theme_color() {
+    for theme in THEMES {
+        for (key, _) in DEFAULT {
+            let value = theme_color(theme, key).unwrap();
+            assert!(parse_color(value).is_some(), "{} {}", theme, key);
+        }
+    }
+    assert_eq!(theme_color("light", "background_main"), Some("#FFFFFF"));
+    assert_eq!(
+        theme_color("nonexistent", "background_main"),
+        Some("#000000")
+    );
+    assert_eq!(theme_color("default", "no_such_key"), None);
+}
+
+#[test]
+fn test_detect_depth() {
+    let detect = |vars: &[(&str, &str)]| {
+        let env: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ColorDepth::detect(|name| env.get(name).cloned())
+    };
+    assert_eq!(
+        detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]),
+        ColorDepth::NoColor
+    );
+    assert_eq!(
+        detect(&[("NO_COLOR", ""), ("COLORTERM", "truecolor")]),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+    assert_eq!(detect(&[("TERM", "xterm")]), ColorDepth::Ansi16);
+    assert_eq!(detect(&[("TERM", "dumb")]), ColorDepth::NoColor);
+    assert_eq!(ColorDepth::from_setting("256"), ColorDepth::Ansi256);
+}
+
+#[test]
+fn test_downgrade() {
+    let orange = Color::Rgb(255, 136, 0);
+    assert_eq!(ColorDepth::TrueColor.downgrade(orange), orange);
+    assert_eq!(ColorDepth::Ansi256.downgrade(orange), Color::Indexed(208));
+    assert_eq!(
+        ColorDepth::Ansi256.downgrade(Color::Rgb(128, 128, 128)),
+        Color::Indexed(244)
+    );
+    assert_eq!(
+        ColorDepth::Ansi16.downgrade(Color::Rgb(10, 10, 10)),
+        Color::Black
+    );
+    assert_eq!(
+        ColorDepth::Ansi16.downgrade(Color::Indexed(196)),
+        Color::LightRed
+    );
+    assert_eq!(ColorDepth::Ansi16.downgrade(Color::Indexed(4)), Color::Blue);
+    assert_eq!(ColorDepth::Ansi16.downgrade(Color::Cyan), Color::Cyan);
+    assert_eq!(ColorDepth::NoColor.downgrade(orange), Color::Reset);
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -0,0 +1,66 @@
+// The watcher module notices when config files change on disk so they can
+// be reloaded while dprs runs. It polls each file's modification time and
+// size at most once per interval, which avoids a platform file watching
+// dependency and copes with editors that save by replacing the file. A file
+// that disappears is not reported; the config in use stays as it is.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the run loop looks at the config files.
+pub const CONFIG_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Stamp = Option<(SystemTime, u64)>;
+
+pub struct ConfigWatcher {
+    files: Vec<(PathBuf, Stamp)>,
+    interval: Duration,
+    last_check: Instant,
+}
+
+impl ConfigWatcher {
+    pub fn new(paths: Vec<PathBuf>, interval: Duration) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+        Self {
+            files,
+            interval,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Returns true once for each change to a watched file that still
+    /// exists.
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < self.interval {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let mut changed = false;
+        for (path, last_stamp) in &mut self.files {
+            let current = stamp(path);
+            if current != *last_stamp {
+                changed |= current.is_some();
+                *last_stamp = current;
+            }
+        }
+        changed
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests;
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
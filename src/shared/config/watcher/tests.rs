@@ -0,0 +1,30 @@
+// The tests module for the config watcher checks that edits are reported
+// once and that a deleted file is not.
+
+use super::*;
+
+#[test]
+fn test_reports_changes_once() {
+    let dir = std::env::temp_dir().join(format!("dprs-watcher-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config");
+    fs::write(&path, "a = 1\n").unwrap();
+
+    let mut watcher = ConfigWatcher::new(vec![path.clone()], Duration::ZERO);
+    assert!(!watcher.changed());
+
+    fs::write(&path, "a = 12\n").unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+
+    fs::remove_file(&path).unwrap();
+    assert!(!watcher.changed());
+
+    // Coming back counts as a change
+    fs::write(&path, "a = 1\n").unwrap();
+    assert!(watcher.changed());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -53,6 +53,43 @@ impl LogTabs {
     }
 }
 
+pub fn render_log_tabs<B: Backend>(f: &mut Frame, log_tabs: &LogTabs, area: Rect, config: &Config) {
+    let titles: Vec<Line> = log_tabs
+        .titles
+        .iter()
+        .map(|t| {
+            Line::from(vec![Span::styled(
+                t,
+                Style::default()
+                    .bg(config.get_color("background_main"))
+                    .fg(config.get_color("text_main")),
+            )])
+        })
+        .collect();
+
+    let tabs = Tabs::new(titles)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Containers")
+                .border_type(BorderType::Rounded),
+        )
+        .select(log_tabs.index)
+        .style(
+            Style::default()
+                .bg(config.get_color("background_main"))
+                .fg(config.get_color("text_main")),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(config.get_color("background_main"))
+                .fg(config.get_color("message_warning"))
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(tabs, area);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,41 +159,4 @@ mod tests {
     }
 }
 
-pub fn render_log_tabs<B: Backend>(f: &mut Frame, log_tabs: &LogTabs, area: Rect, config: &Config) {
-    let titles: Vec<Line> = log_tabs
-        .titles
-        .iter()
-        .map(|t| {
-            Line::from(vec![Span::styled(
-                t,
-                Style::default()
-                    .bg(config.get_color("background_main"))
-                    .fg(config.get_color("text_main")),
-            )])
-        })
-        .collect();
-
-    let tabs = Tabs::new(titles)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Containers")
-                .border_type(BorderType::Rounded),
-        )
-        .select(log_tabs.index)
-        .style(
-            Style::default()
-                .bg(config.get_color("background_main"))
-                .fg(config.get_color("text_main")),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(config.get_color("background_main"))
-                .fg(config.get_color("message_warning"))
-                .add_modifier(Modifier::BOLD),
-        );
-
-    f.render_widget(tabs, area);
-}
-
 // Copyright (c) 2025 Durable Programming, LLC. All rights reserved.
@@ -175,6 +175,16 @@ pub fn render_log_view<B: Backend>(
     log_view: &mut LogView,
     area: Rect,
     config: &Config,
+) {
+    render_titled_log_view::<B>(f, log_view, area, config, "Logs");
+}
+
+pub fn render_titled_log_view<B: Backend>(
+    f: &mut Frame,
+    log_view: &mut LogView,
+    area: Rect,
+    config: &Config,
+    title: &str,
 ) {
     let logs = &log_view.logs;
     let now = Instant::now();
@@ -270,7 +280,7 @@ pub fn render_log_view<B: Backend>(
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Logs ({})", current_position)),
+                .title(format!("{} ({})", title, current_position)),
         )
         .style(Style::default().bg(config.get_color("background_main")))
         .wrap(Wrap { trim: false })
@@ -5,11 +5,14 @@
 // The module supports starting/stopping log collection, retrieving collected logs,
 // and refreshing the container list. It ensures proper resource cleanup with async
 // tasks and implements graceful shutdown through Drop trait implementation.
+// Errors are kept for the caller to show rather than printed, since the
+// watchers run under a full-screen terminal UI.
 
 use bollard::query_parameters::{ListContainersOptionsBuilder, LogsOptionsBuilder};
 use bollard::Docker;
 use std::collections::VecDeque;
 use std::io::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
@@ -18,8 +21,12 @@ pub struct DockerLogWatcher {
     container_name: String,
     logs: Arc<Mutex<VecDeque<String>>>,
     max_logs: usize,
+    // Total number of lines received, including ones already trimmed from
+    // `logs`, so consumers can tell new lines apart once the buffer is full.
+    received: Arc<AtomicUsize>,
     handle: Option<JoinHandle<()>>,
     running: Arc<Mutex<bool>>,
+    error: Arc<Mutex<Option<String>>>,
 }
 
 impl DockerLogWatcher {
@@ -28,8 +35,10 @@ impl DockerLogWatcher {
             container_name,
             logs: Arc::new(Mutex::new(VecDeque::with_capacity(max_logs))),
             max_logs,
+            received: Arc::new(AtomicUsize::new(0)),
             handle: None,
             running: Arc::new(Mutex::new(false)),
+            error: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -37,7 +46,10 @@ impl DockerLogWatcher {
         let container_name = self.container_name.clone();
         let logs = Arc::clone(&self.logs);
         let max_logs = self.max_logs;
+        let received = Arc::clone(&self.received);
         let running = Arc::clone(&self.running);
+        let error = Arc::clone(&self.error);
+        let fail = move |message: String| *error.lock().unwrap() = Some(message);
 
         // Set running state to true
         *running.lock().unwrap() = true;
@@ -47,7 +59,7 @@ impl DockerLogWatcher {
             let rt = match tokio::runtime::Runtime::new() {
                 Ok(rt) => rt,
                 Err(e) => {
-                    eprintln!("Failed to create tokio runtime: {}", e);
+                    fail(format!("Failed to create tokio runtime: {}", e));
                     return;
                 }
             };
@@ -56,7 +68,7 @@ impl DockerLogWatcher {
                 let docker = match Docker::connect_with_defaults() {
                     Ok(docker) => docker,
                     Err(e) => {
-                        eprintln!("Failed to connect to Docker: {}", e);
+                        fail(format!("Failed to connect to Docker: {}", e));
                         return;
                     }
                 };
@@ -102,9 +114,10 @@ impl DockerLogWatcher {
                                 while logs.len() > max_logs {
                                     logs.pop_front();
                                 }
+                                received.fetch_add(1, Ordering::SeqCst);
                             }
                             Err(e) => {
-                                eprintln!("Error reading logs for {}: {}", container_name, e);
+                                fail(format!("Error reading logs for {}: {}", container_name, e));
                                 break;
                             }
                         },
@@ -136,20 +149,15 @@ impl DockerLogWatcher {
                 let timeout = Duration::from_secs(1);
                 let start = std::time::Instant::now();
 
-                let mut join_result = None;
                 while start.elapsed() < timeout {
                     // Check if thread is still running
                     if handle.is_finished() {
-                        join_result = Some(handle.join());
+                        let _ = handle.join();
                         break;
                     }
                     std::thread::sleep(Duration::from_millis(10));
                 }
-
-                // If thread hasn't finished, we'll just let it go
-                if join_result.is_none() {
-                    eprintln!("Warning: Log watcher thread did not stop gracefully within timeout");
-                }
+                // If the thread hasn't finished, we just let it go
             });
         }
     }
@@ -159,6 +167,15 @@ impl DockerLogWatcher {
         logs.iter().cloned().collect()
     }
 
+    /// Why the watcher stopped receiving logs, if it failed.
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+
+    pub fn received_count(&self) -> usize {
+        self.received.load(Ordering::SeqCst)
+    }
+
     pub fn container_name(&self) -> &str {
         &self.container_name
     }
@@ -267,6 +284,8 @@ mod tests {
         let watcher = DockerLogWatcher::new("test-container".to_string(), 100);
         assert_eq!(watcher.container_name(), "test-container");
         assert_eq!(watcher.get_logs().len(), 0);
+        assert_eq!(watcher.received_count(), 0);
+        assert_eq!(watcher.error(), None);
     }
 
     #[test]
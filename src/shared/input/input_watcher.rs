@@ -1,9 +1,17 @@
+// The input_watcher module reads terminal events on a background thread and
+// hands them to the main loop through a channel. Reading can be paused while
+// another program has the terminal, so its keystrokes are not taken.
+
 use crossterm::event::{self, Event};
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
 pub struct InputWatcher {
     receiver: mpsc::Receiver<Event>,
+    paused: Arc<AtomicBool>,
+    // Set by the thread once it has stopped reading for a pause
+    idle: Arc<AtomicBool>,
 }
 
 impl Default for InputWatcher {
@@ -15,8 +23,17 @@ impl Default for InputWatcher {
 impl InputWatcher {
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
+        let paused = Arc::new(AtomicBool::new(false));
+        let idle = Arc::new(AtomicBool::new(false));
 
+        let thread_paused = Arc::clone(&paused);
+        let thread_idle = Arc::clone(&idle);
         std::thread::spawn(move || loop {
+            if thread_paused.load(Ordering::SeqCst) {
+                thread_idle.store(true, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(10));
+                continue;
+            }
             if let Ok(true) = event::poll(Duration::from_millis(10)) {
                 if let Ok(event) = event::read() {
                     if sender.send(event).is_err() {
@@ -26,10 +43,30 @@ impl InputWatcher {
             }
         });
 
-        Self { receiver }
+        Self {
+            receiver,
+            paused,
+            idle,
+        }
     }
 
     pub fn try_recv(&self) -> Result<Event, mpsc::TryRecvError> {
         self.receiver.try_recv()
     }
+
+    /// Stops reading the terminal, returning once the thread has let go of
+    /// it.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        while !self.idle.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    pub fn resume(&self) {
+        self.idle.store(false, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+    }
 }
+
+// Copyright (c) 2025 Durable Programming, LLC. All rights reserved.